@@ -1,8 +1,58 @@
+use crate::cryptography::EncryptionScheme;
 use serde::Deserialize;
+use std::io;
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CatchConnectCLIResponse {
     pub public_key: String,
     pub integration_id: String,
+    /// Scheme picked by the server. Backends that predate negotiation omit it and expect CBC.
+    #[serde(default)]
+    pub encryption_scheme: ServerEncryptionScheme,
+    /// Envelope version the server accepts for file content, if any.
+    #[serde(default)]
+    pub envelope_version: Option<u8>,
+    /// Base64 Ed25519 signature of the environment and public key by a Catch root key, sent on
+    /// key rotation.
+    #[serde(default)]
+    pub public_key_signature: Option<String>,
+}
+
+/// Encryption scheme named by the server, which may be one a newer CLI introduced.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ServerEncryptionScheme {
+    Supported(EncryptionScheme),
+    Unsupported(String),
+}
+
+impl Default for ServerEncryptionScheme {
+    fn default() -> Self {
+        ServerEncryptionScheme::Supported(EncryptionScheme::default())
+    }
+}
+
+impl ServerEncryptionScheme {
+    /// The scheme to encrypt with. Fails when this CLI doesn't know the server's choice.
+    pub fn supported(&self) -> io::Result<EncryptionScheme> {
+        match self {
+            ServerEncryptionScheme::Supported(scheme) => Ok(*scheme),
+            ServerEncryptionScheme::Unsupported(name) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "The server chose the {} encryption scheme, which this CLI doesn't support; \
+                     upgrade the CLI and try again",
+                    name
+                ),
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CatchRevokeFilesResponse {
+    pub deleted_files: Option<u64>,
+    pub deleted_artifacts: Option<u64>,
 }
@@ -35,14 +35,23 @@ impl Default for CatchApiClient {
     fn default() -> Self {
         let base_url = std::env::var("CATCH_CLI_BASE_API_URL");
 
+        Self::new(base_url.unwrap_or_else(|_| BASE_CATCH_API_URL.to_string()))
+    }
+}
+
+impl CatchApiClient {
+    pub fn new(base_url: String) -> Self {
         Self {
             client: Client::new(),
-            base_url: base_url.unwrap_or_else(|_| BASE_CATCH_API_URL.to_string()),
+            base_url,
         }
     }
-}
 
-impl CatchApiClient {
+    /// The API this client talks to; also used to key per-environment local state.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     async fn handle_response<T: DeserializeOwned>(
         response: Response,
     ) -> Result<CatchApiResponse<T>, CatchApiError> {
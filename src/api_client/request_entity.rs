@@ -1,4 +1,7 @@
 use crate::code_reader::CatchCLICodeFile;
+use crate::code_uploader::UploadMetadata;
+use crate::cryptography::EncryptionScheme;
+use crate::upload_manifest::SignedUploadManifest;
 use serde::Serialize;
 
 #[derive(Serialize, Debug, Clone)]
@@ -7,6 +10,8 @@ pub struct CatchConnectCLIRequest {
     pub repo_name: String,
     pub repo_owner: String,
     pub session_id: String,
+    pub supported_encryption_schemes: Vec<EncryptionScheme>,
+    pub supported_envelope_versions: Vec<u8>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -24,6 +29,16 @@ pub struct CatchCLIUploadFilesRequest {
     pub files: Vec<CatchCLICodeFile>,
     #[serde(rename = "clientEncryptedKey")]
     pub client_encrypted_key: String,
-    #[serde(rename = "clientEncryptedIv")]
-    pub client_encrypted_iv: String,
+    #[serde(rename = "clientEncryptedIv", skip_serializing_if = "Option::is_none")]
+    pub client_encrypted_iv: Option<String>,
+    #[serde(
+        rename = "encryptionScheme",
+        skip_serializing_if = "EncryptionScheme::is_legacy"
+    )]
+    pub encryption_scheme: EncryptionScheme,
+    #[serde(rename = "envelopeVersion", skip_serializing_if = "Option::is_none")]
+    pub envelope_version: Option<u8>,
+    pub manifest: SignedUploadManifest,
+    #[serde(flatten)]
+    pub metadata: UploadMetadata,
 }
@@ -1,16 +1,76 @@
 use serde::Deserialize;
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StepStatus {
+    #[default]
+    #[serde(alias = "not-yet")]
+    Pending,
+    #[serde(alias = "in-progress")]
+    Running,
+    Completed,
+    Failed,
+    #[serde(other)]
+    Unknown,
+}
+
+impl StepStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, StepStatus::Completed | StepStatus::Failed)
+    }
+}
+
+/// Steps of a session process, in the order the backend runs them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SessionStep {
+    ExtractingCandidates,
+    FetchingCode,
+    IndexingCode,
+    AnalyzingPlatform,
+    AnalyzingModuleStructure,
+    GeneratingDiff,
+    GeneratingDocs,
+}
+
+impl SessionStep {
+    pub const ALL: [SessionStep; 7] = [
+        SessionStep::ExtractingCandidates,
+        SessionStep::FetchingCode,
+        SessionStep::IndexingCode,
+        SessionStep::AnalyzingPlatform,
+        SessionStep::AnalyzingModuleStructure,
+        SessionStep::GeneratingDiff,
+        SessionStep::GeneratingDocs,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStep::ExtractingCandidates => "extracting-candidates",
+            SessionStep::FetchingCode => "fetching-code",
+            SessionStep::IndexingCode => "indexing-code",
+            SessionStep::AnalyzingPlatform => "analyzing-platform",
+            SessionStep::AnalyzingModuleStructure => "analyzing-module-structure",
+            SessionStep::GeneratingDiff => "generating-diff",
+            SessionStep::GeneratingDocs => "generating-docs",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CatchSessionAnalyzingModuleStructureResult {
-    pub status: String,
+    #[serde(default)]
+    pub status: StepStatus,
+    #[serde(default)]
     pub structure: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CatchSessionExtractingCandidatesResult {
-    pub status: String,
+    #[serde(default)]
+    pub status: StepStatus,
+    #[serde(default)]
     pub candidates: Vec<String>,
 }
 
@@ -21,11 +81,12 @@ pub struct CatchPlatformInfo {
     pub architecture_description: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CatchSessionAnalyzePlatformResult {
-    pub status: String,
-    pub platform_info: CatchPlatformInfo,
+    #[serde(default)]
+    pub status: StepStatus,
+    pub platform_info: Option<CatchPlatformInfo>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -37,41 +98,101 @@ pub struct CatchDiffFile {
     pub original_content: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CatchSessionGeneratingDiffResult {
+    #[serde(default)]
     pub files: Vec<CatchDiffFile>,
-    pub status: String,
+    #[serde(default)]
+    pub status: StepStatus,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CatchSessionResult {
+    #[serde(default)]
     pub step: String,
-    pub status: String,
+    #[serde(default)]
+    pub status: StepStatus,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct CatchSessionOutput {
+    #[serde(default)]
     pub docs: Vec<String>,
-    pub fetching_code: CatchSessionResult,
-    pub indexing_code: CatchSessionResult,
-    pub generating_diff: CatchSessionGeneratingDiffResult,
-    pub generating_docs: CatchSessionResult,
-    pub analyzing_platform: CatchSessionAnalyzePlatformResult,
-    pub extracting_candidates: CatchSessionExtractingCandidatesResult,
-    pub analyzing_module_structure: CatchSessionAnalyzingModuleStructureResult,
+    pub fetching_code: Option<CatchSessionResult>,
+    pub indexing_code: Option<CatchSessionResult>,
+    pub generating_diff: Option<CatchSessionGeneratingDiffResult>,
+    pub generating_docs: Option<CatchSessionResult>,
+    pub analyzing_platform: Option<CatchSessionAnalyzePlatformResult>,
+    pub extracting_candidates: Option<CatchSessionExtractingCandidatesResult>,
+    pub analyzing_module_structure: Option<CatchSessionAnalyzingModuleStructureResult>,
+}
+
+impl CatchSessionOutput {
+    /// Status of a single step, or `None` if the backend has not reported it yet.
+    pub fn step_status(&self, step: SessionStep) -> Option<StepStatus> {
+        match step {
+            SessionStep::ExtractingCandidates => {
+                self.extracting_candidates.as_ref().map(|r| r.status)
+            }
+            SessionStep::FetchingCode => self.fetching_code.as_ref().map(|r| r.status),
+            SessionStep::IndexingCode => self.indexing_code.as_ref().map(|r| r.status),
+            SessionStep::AnalyzingPlatform => self.analyzing_platform.as_ref().map(|r| r.status),
+            SessionStep::AnalyzingModuleStructure => {
+                self.analyzing_module_structure.as_ref().map(|r| r.status)
+            }
+            SessionStep::GeneratingDiff => self.generating_diff.as_ref().map(|r| r.status),
+            SessionStep::GeneratingDocs => self.generating_docs.as_ref().map(|r| r.status),
+        }
+    }
+
+    /// The first step that has not completed yet, or `None` once every step is completed.
+    pub fn current_step(&self) -> Option<SessionStep> {
+        SessionStep::ALL
+            .into_iter()
+            .find(|step| self.step_status(*step) != Some(StepStatus::Completed))
+    }
+
+    pub fn has_failed(&self) -> bool {
+        SessionStep::ALL
+            .into_iter()
+            .any(|step| self.step_status(step) == Some(StepStatus::Failed))
+    }
+
+    /// A process is terminal once any step has failed or all steps have completed.
+    pub fn is_terminal(&self) -> bool {
+        self.has_failed() || self.current_step().is_none()
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CatchSessionProcessInfo {
     pub id: Option<String>,
-    pub status: Option<String>,
+    pub status: Option<StepStatus>,
     pub output: Option<CatchSessionOutput>,
 }
 
+impl CatchSessionProcessInfo {
+    pub fn current_step(&self) -> Option<SessionStep> {
+        match &self.output {
+            Some(output) => output.current_step(),
+            None => Some(SessionStep::ExtractingCandidates),
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        if self.status.is_some_and(|status| status.is_finished()) {
+            return true;
+        }
+        self.output
+            .as_ref()
+            .is_some_and(|output| output.is_terminal())
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CatchSessionStatusResponse {
@@ -0,0 +1,112 @@
+use crate::app_manifest::{AppManifest, AppManifestKind};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::BTreeMap;
+
+/// App ID, SDK levels, permissions, features and URL schemes declared in an
+/// `AndroidManifest.xml`.
+///
+/// Most projects set the app ID and SDK levels in `build.gradle` instead; they're only
+/// reported here when the manifest declares them itself.
+pub fn parse_android_manifest(path: &str, content: &str) -> Option<AppManifest> {
+    let mut manifest = AppManifest::new(path, AppManifestKind::AndroidManifest);
+    let mut reader = Reader::from_str(content);
+    let mut is_manifest = false;
+
+    loop {
+        let element = match reader.read_event().ok()? {
+            Event::Start(element) | Event::Empty(element) => element,
+            Event::Eof => break,
+            _ => continue,
+        };
+        match element.name().as_ref() {
+            b"manifest" => {
+                is_manifest = true;
+                manifest.app_id = attribute(&element, b"package");
+            }
+            b"uses-sdk" => {
+                manifest.min_sdk = attribute(&element, b"android:minSdkVersion");
+                manifest.target_sdk = attribute(&element, b"android:targetSdkVersion");
+            }
+            b"uses-permission" | b"uses-permission-sdk-23" => {
+                push_unique(
+                    &mut manifest.permissions,
+                    attribute(&element, b"android:name"),
+                );
+            }
+            b"uses-feature" => {
+                push_unique(
+                    &mut manifest.capabilities,
+                    attribute(&element, b"android:name"),
+                );
+            }
+            // `<data>` also appears outside intent filters, e.g. in `<queries>`; those schemes
+            // are ones the app opens, not ones it handles, but both matter for a review.
+            b"data" => {
+                push_unique(
+                    &mut manifest.url_schemes,
+                    attribute(&element, b"android:scheme"),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    is_manifest.then_some(manifest)
+}
+
+/// Fills in the app ID, namespace and SDK levels of the manifest of a module, e.g.
+/// `app/src/main/AndroidManifest.xml`, from the settings of the module's build script, e.g.
+/// `app/build.gradle.kts`, keyed by path; see [`parse_android_settings`].
+///
+/// The build script wins where both declare a value, as it does in the merged manifest. The
+/// namespace stands in for the app ID when neither declares one.
+///
+/// [`parse_android_settings`]: crate::dependency_summary::gradle::parse_android_settings
+pub fn apply_build_script(
+    manifest: &mut AppManifest,
+    build_scripts: &BTreeMap<&str, BTreeMap<String, String>>,
+) {
+    let module_dir = match manifest.path.starts_with("src/") {
+        true => "",
+        false => match manifest.path.rsplit_once("/src/") {
+            Some((module_dir, _)) => module_dir,
+            None => return,
+        },
+    };
+    let settings = ["build.gradle.kts", "build.gradle"]
+        .iter()
+        .map(|name| match module_dir.is_empty() {
+            true => name.to_string(),
+            false => format!("{}/{}", module_dir, name),
+        })
+        .find_map(|path| build_scripts.get(path.as_str()));
+    let Some(settings) = settings else {
+        return;
+    };
+
+    let setting = |name: &str| settings.get(name).cloned();
+    manifest.namespace = setting("namespace");
+    manifest.app_id = setting("applicationId")
+        .or(manifest.app_id.take())
+        .or(manifest.namespace.clone());
+    manifest.min_sdk = setting("minSdk").or(manifest.min_sdk.take());
+    manifest.target_sdk = setting("targetSdk").or(manifest.target_sdk.take());
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.as_ref() == name)
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+fn push_unique(values: &mut Vec<String>, value: Option<String>) {
+    if let Some(value) = value {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+}
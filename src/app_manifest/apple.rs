@@ -0,0 +1,273 @@
+use crate::app_manifest::{AppManifest, AppManifestKind, PrivacyEntry, PrivacyEntryKind};
+use crate::xcode_project::{join_path, TargetKind, XcodeProjects, XcodeTarget};
+use plist::{Dictionary, Value};
+use std::io::Cursor;
+
+/// Deployment target settings by `SDKROOT`.
+const DEPLOYMENT_TARGETS: [(&str, &str); 5] = [
+    ("iphoneos", "IPHONEOS_DEPLOYMENT_TARGET"),
+    ("macosx", "MACOSX_DEPLOYMENT_TARGET"),
+    ("watchos", "WATCHOS_DEPLOYMENT_TARGET"),
+    ("appletvos", "TVOS_DEPLOYMENT_TARGET"),
+    ("xros", "XROS_DEPLOYMENT_TARGET"),
+];
+
+/// Bundle ID, minimum OS version, usage descriptions, URL schemes and background modes of an
+/// `Info.plist`, in XML or binary form.
+pub fn parse_info_plist(path: &str, content: &[u8]) -> Option<AppManifest> {
+    let plist = read_dictionary(content)?;
+    if !plist.keys().any(|key| key.starts_with("CFBundle")) {
+        return None;
+    }
+    Some(info_plist_manifest(path, &plist))
+}
+
+fn info_plist_manifest(path: &str, plist: &Dictionary) -> AppManifest {
+    let mut manifest = AppManifest::new(path, AppManifestKind::InfoPlist);
+    manifest.app_id = string(plist, "CFBundleIdentifier");
+    manifest.min_sdk =
+        string(plist, "MinimumOSVersion").or_else(|| string(plist, "LSMinimumSystemVersion"));
+    manifest.permissions = plist
+        .keys()
+        .filter(|key| key.ends_with("UsageDescription"))
+        .cloned()
+        .collect();
+    manifest.url_schemes = plist
+        .get("CFBundleURLTypes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|url_type| url_type.as_dictionary()?.get("CFBundleURLSchemes"))
+        .flat_map(strings)
+        .collect();
+    manifest.capabilities = plist
+        .get("UIBackgroundModes")
+        .map(strings)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mode| format!("background: {}", mode))
+        .collect();
+    let allows_arbitrary_loads = plist
+        .get("NSAppTransportSecurity")
+        .and_then(Value::as_dictionary)
+        .and_then(|security| security.get("NSAllowsArbitraryLoads"))
+        .and_then(Value::as_boolean);
+    if allows_arbitrary_loads == Some(true) {
+        manifest
+            .capabilities
+            .push("NSAllowsArbitraryLoads".to_string());
+    }
+
+    manifest
+}
+
+/// Resolves the build settings in the Info.plist manifests of the app and extension targets
+/// of `projects`, e.g. `$(PRODUCT_BUNDLE_IDENTIFIER)`, and adds the Info.plist of targets
+/// Xcode generates it for (`GENERATE_INFOPLIST_FILE`).
+///
+/// `files` are the app manifests that were read; a generated Info.plist is merged with the
+/// target's `INFOPLIST_FILE` even when that file lacks the `CFBundle` keys
+/// [`parse_info_plist`] looks for. An Info.plist shared by several targets is resolved for
+/// the first of them.
+pub fn apply_build_settings(
+    manifests: &mut Vec<AppManifest>,
+    projects: &XcodeProjects,
+    files: &[(&str, &[u8])],
+) {
+    for (_, project, target) in projects.targets() {
+        if !matches!(target.kind, TargetKind::Application | TargetKind::Extension) {
+            continue;
+        }
+        let project_dir = project.path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let info_plist = target
+            .build_setting("INFOPLIST_FILE")
+            .and_then(|path| join_path(project_dir, &path));
+        let is_generated =
+            target.build_setting("GENERATE_INFOPLIST_FILE").as_deref() == Some("YES");
+
+        let existing = info_plist.as_ref().and_then(|path| {
+            manifests.iter().position(|manifest| {
+                manifest.kind == AppManifestKind::InfoPlist && &manifest.path == path
+            })
+        });
+        let index = match existing {
+            Some(index) => index,
+            None if is_generated => {
+                let path = info_plist.unwrap_or_else(|| project.path.clone());
+                let manifest = files
+                    .iter()
+                    .find(|(file_path, _)| *file_path == path)
+                    .and_then(|(_, content)| read_dictionary(content))
+                    .map(|plist| info_plist_manifest(&path, &plist))
+                    .unwrap_or_else(|| AppManifest::new(&path, AppManifestKind::InfoPlist));
+                manifests.push(manifest);
+                manifests.len() - 1
+            }
+            None => continue,
+        };
+        if manifests[index].target.is_none() {
+            resolve_info_plist(&mut manifests[index], target, is_generated);
+        }
+    }
+}
+
+/// Expands the build settings `manifest` refers to, and fills in what Xcode adds to the
+/// built Info.plist: the minimum OS version, and for generated ones the bundle ID and the
+/// `INFOPLIST_KEY_` usage descriptions.
+fn resolve_info_plist(manifest: &mut AppManifest, target: &XcodeTarget, is_generated: bool) {
+    manifest.target = Some(target.name.clone());
+    manifest.app_id = manifest.app_id.as_deref().map(|id| target.expand(id));
+    if is_generated && manifest.app_id.is_none() {
+        manifest.app_id = target.build_setting("PRODUCT_BUNDLE_IDENTIFIER");
+    }
+    manifest.min_sdk = match &manifest.min_sdk {
+        Some(min_sdk) => Some(target.expand(min_sdk)),
+        None => deployment_target(target),
+    };
+    for scheme in &mut manifest.url_schemes {
+        *scheme = target.expand(scheme);
+    }
+
+    if is_generated {
+        let usage_descriptions = target.build_settings.keys().filter_map(|name| {
+            name.strip_prefix("INFOPLIST_KEY_")
+                .filter(|key| key.ends_with("UsageDescription"))
+        });
+        for key in usage_descriptions {
+            if !manifest
+                .permissions
+                .iter()
+                .any(|permission| permission == key)
+            {
+                manifest.permissions.push(key.to_string());
+            }
+        }
+    }
+}
+
+/// The deployment target for the target's `SDKROOT`, or the first one set.
+fn deployment_target(target: &XcodeTarget) -> Option<String> {
+    let sdk = target.build_setting("SDKROOT");
+    DEPLOYMENT_TARGETS
+        .iter()
+        .find(|(sdk_root, _)| sdk.as_deref() == Some(*sdk_root))
+        .and_then(|(_, setting)| target.build_setting(setting))
+        .or_else(|| {
+            DEPLOYMENT_TARGETS
+                .iter()
+                .find_map(|(_, setting)| target.build_setting(setting))
+        })
+}
+
+/// Entitlements of a `.entitlements` file, e.g. `aps-environment: development` or
+/// `com.apple.developer.associated-domains: applinks:example.com`.
+pub fn parse_entitlements(path: &str, content: &[u8]) -> Option<AppManifest> {
+    let plist = read_dictionary(content)?;
+
+    let mut manifest = AppManifest::new(path, AppManifestKind::Entitlements);
+    for (key, value) in &plist {
+        let capability = match value {
+            Value::Boolean(true) => key.clone(),
+            Value::Boolean(false) => continue,
+            Value::String(value) => format!("{}: {}", key, value),
+            Value::Array(_) => format!("{}: {}", key, strings(value).join(", ")),
+            _ => key.clone(),
+        };
+        manifest.capabilities.push(capability);
+    }
+
+    Some(manifest)
+}
+
+/// Tracking, tracking domains, collected data types and required reason APIs declared in a
+/// `PrivacyInfo.xcprivacy`.
+pub fn parse_privacy_manifest(path: &str, content: &[u8]) -> Option<AppManifest> {
+    let plist = read_dictionary(content)?;
+
+    let mut manifest = AppManifest::new(path, AppManifestKind::PrivacyManifest);
+    if let Some(tracking) = plist.get("NSPrivacyTracking").and_then(Value::as_boolean) {
+        manifest.privacy.push(PrivacyEntry {
+            kind: PrivacyEntryKind::Tracking,
+            value: tracking.to_string(),
+            details: Vec::new(),
+        });
+    }
+    if let Some(domains) = plist.get("NSPrivacyTrackingDomains") {
+        manifest
+            .privacy
+            .extend(strings(domains).into_iter().map(|domain| PrivacyEntry {
+                kind: PrivacyEntryKind::TrackingDomain,
+                value: domain,
+                details: Vec::new(),
+            }));
+    }
+    for data_type in dictionaries(&plist, "NSPrivacyCollectedDataTypes") {
+        let Some(value) = string(data_type, "NSPrivacyCollectedDataType") else {
+            continue;
+        };
+        let mut details = Vec::new();
+        for (key, label) in [
+            ("NSPrivacyCollectedDataTypeLinked", "linked"),
+            ("NSPrivacyCollectedDataTypeTracking", "tracking"),
+        ] {
+            if data_type.get(key).and_then(Value::as_boolean) == Some(true) {
+                details.push(label.to_string());
+            }
+        }
+        if let Some(purposes) = data_type.get("NSPrivacyCollectedDataTypePurposes") {
+            details.extend(strings(purposes));
+        }
+        manifest.privacy.push(PrivacyEntry {
+            kind: PrivacyEntryKind::CollectedData,
+            value,
+            details,
+        });
+    }
+    for api_type in dictionaries(&plist, "NSPrivacyAccessedAPITypes") {
+        let Some(value) = string(api_type, "NSPrivacyAccessedAPIType") else {
+            continue;
+        };
+        manifest.privacy.push(PrivacyEntry {
+            kind: PrivacyEntryKind::AccessedApi,
+            value,
+            details: api_type
+                .get("NSPrivacyAccessedAPITypeReasons")
+                .map(strings)
+                .unwrap_or_default(),
+        });
+    }
+
+    Some(manifest)
+}
+
+fn read_dictionary(content: &[u8]) -> Option<Dictionary> {
+    Value::from_reader(Cursor::new(content))
+        .ok()?
+        .into_dictionary()
+}
+
+fn string(dictionary: &Dictionary, key: &str) -> Option<String> {
+    dictionary
+        .get(key)
+        .and_then(Value::as_string)
+        .map(str::to_string)
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_string)
+        .map(str::to_string)
+        .collect()
+}
+
+fn dictionaries<'a>(dictionary: &'a Dictionary, key: &str) -> impl Iterator<Item = &'a Dictionary> {
+    dictionary
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_dictionary)
+}
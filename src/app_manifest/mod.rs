@@ -0,0 +1,308 @@
+pub mod android;
+pub mod apple;
+
+use crate::app_manifest::ui::AppManifestReportView;
+use crate::code_reader::{PlaintextFiles, ScanOptions};
+use crate::cryptography::{FileCipher, SealedMetadata};
+use crate::dependency_summary::gradle::parse_android_settings;
+use crate::xcode_project::{XcodeProjects, MAX_PROJECT_FILE_SIZE, XCODE_PROJECT_GLOBS};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+mod ui;
+
+/// Path the report is encrypted under; the server uses it to authenticate the content.
+pub const APP_MANIFEST_REPORT_PATH: &str = ".catch/app-manifests.json";
+
+pub const MAX_APP_MANIFEST_SIZE: u64 = 4 * 1024 * 1024;
+
+pub const APP_MANIFEST_GLOBS: &[&str] = &[
+    "**/AndroidManifest.xml",
+    "**/*Info.plist",
+    "**/*.entitlements",
+    "**/*.xcprivacy",
+];
+
+/// Build scripts Android app IDs and SDK levels are usually set in. The build settings of
+/// Xcode projects are read from [`XCODE_PROJECT_GLOBS`].
+pub const GRADLE_BUILD_GLOBS: &[&str] = &["**/build.gradle", "**/build.gradle.kts"];
+
+/// `$(NAME)` and `${NAME}`, build settings and manifest placeholders.
+static VARIABLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$\([^)]*\)|\$\{[^}]*\}").unwrap());
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AppManifestKind {
+    AndroidManifest,
+    InfoPlist,
+    Entitlements,
+    PrivacyManifest,
+}
+
+impl fmt::Display for AppManifestKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppManifestKind::AndroidManifest => write!(f, "Android manifest"),
+            AppManifestKind::InfoPlist => write!(f, "Info.plist"),
+            AppManifestKind::Entitlements => write!(f, "entitlements"),
+            AppManifestKind::PrivacyManifest => write!(f, "privacy manifest"),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrivacyEntryKind {
+    /// Whether the app uses data for tracking, as defined by App Tracking Transparency.
+    Tracking,
+    TrackingDomain,
+    CollectedData,
+    /// APIs that require a reason, e.g. `NSPrivacyAccessedAPICategoryUserDefaults`.
+    AccessedApi,
+}
+
+impl fmt::Display for PrivacyEntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrivacyEntryKind::Tracking => write!(f, "Tracking"),
+            PrivacyEntryKind::TrackingDomain => write!(f, "Tracking domain"),
+            PrivacyEntryKind::CollectedData => write!(f, "Collected data"),
+            PrivacyEntryKind::AccessedApi => write!(f, "Accessed API"),
+        }
+    }
+}
+
+/// One entry of a privacy manifest.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyEntry {
+    pub kind: PrivacyEntryKind,
+    pub value: String,
+    /// Declared purposes or reasons, and whether collected data is linked or used to track.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+/// What one app manifest declares. Fields a kind of manifest doesn't have are left empty.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppManifest {
+    /// Path relative to the scanned directory.
+    pub path: String,
+    pub kind: AppManifestKind,
+    /// Xcode target the Info.plist belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Android application ID or iOS bundle ID, with the build settings the project sets
+    /// resolved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    /// Android namespace of the module.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_sdk: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_sdk: Option<String>,
+    /// Android permissions, or the iOS usage descriptions the app asks permissions with.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub url_schemes: Vec<String>,
+    /// Entitlements, background modes and hardware features.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub privacy: Vec<PrivacyEntry>,
+    /// Variables left in the app ID, SDK levels and URL schemes, e.g. build settings from
+    /// `.xcconfig` files or Gradle manifest placeholders; those values aren't the final ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unresolved: Vec<String>,
+}
+
+impl AppManifest {
+    pub fn new(path: &str, kind: AppManifestKind) -> Self {
+        Self {
+            path: path.to_string(),
+            kind,
+            target: None,
+            app_id: None,
+            namespace: None,
+            min_sdk: None,
+            target_sdk: None,
+            permissions: Vec::new(),
+            url_schemes: Vec::new(),
+            capabilities: Vec::new(),
+            privacy: Vec::new(),
+            unresolved: Vec::new(),
+        }
+    }
+
+    /// Records the variables left in the values that are resolved at build time.
+    fn flag_unresolved(&mut self) {
+        let values = [&self.app_id, &self.min_sdk, &self.target_sdk]
+            .into_iter()
+            .flatten()
+            .chain(&self.url_schemes);
+        let mut unresolved = Vec::new();
+        for value in values {
+            for variable in VARIABLE.find_iter(value) {
+                if !unresolved.iter().any(|known| known == variable.as_str()) {
+                    unresolved.push(variable.as_str().to_string());
+                }
+            }
+        }
+        self.unresolved = unresolved;
+    }
+
+    /// Labeled values of the fields that are set, in display order.
+    pub fn fields(&self) -> Vec<(String, String)> {
+        let mut fields = Vec::new();
+        let values = [
+            ("Target", self.target.clone()),
+            ("App ID", self.app_id.clone()),
+            ("Namespace", self.namespace.clone()),
+            ("Min SDK", self.min_sdk.clone()),
+            ("Target SDK", self.target_sdk.clone()),
+        ];
+        for (label, value) in values {
+            if let Some(value) = value {
+                fields.push((label.to_string(), value));
+            }
+        }
+        let lists = [
+            ("Permissions", &self.permissions),
+            ("URL schemes", &self.url_schemes),
+            ("Capabilities", &self.capabilities),
+            ("Unresolved", &self.unresolved),
+        ];
+        for (label, values) in lists {
+            for value in values {
+                fields.push((label.to_string(), value.clone()));
+            }
+        }
+        for entry in &self.privacy {
+            let value = match entry.details.is_empty() {
+                true => entry.value.clone(),
+                false => format!("{} ({})", entry.value, entry.details.join(", ")),
+            };
+            fields.push((entry.kind.to_string(), value));
+        }
+        fields
+    }
+}
+
+/// App IDs, SDK levels, permissions, URL schemes, capabilities and privacy manifest entries of
+/// the mobile apps in a scan.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppManifestReport {
+    pub manifests: Vec<AppManifest>,
+}
+
+impl AppManifestReport {
+    pub fn is_empty(&self) -> bool {
+        self.manifests.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return "No app manifests found.\n".to_string();
+        }
+
+        let mut output = String::new();
+        for manifest in &self.manifests {
+            output.push_str(&format!("{} ({})\n", manifest.path, manifest.kind));
+            for (label, value) in manifest.fields() {
+                output.push_str(&format!("  {:<16} {}\n", format!("{}:", label), value));
+            }
+        }
+        output
+    }
+
+    pub fn seal(&self, cipher: &FileCipher) -> io::Result<SealedMetadata> {
+        cipher.seal_json(APP_MANIFEST_REPORT_PATH, self)
+    }
+
+    /// Parses the app manifests among `files`, read with at least [`APP_MANIFEST_GLOBS`] and
+    /// [`GRADLE_BUILD_GLOBS`], and resolves their app IDs and SDK levels from the Gradle
+    /// build scripts among `files` and the build settings of `projects`. Manifests that can't
+    /// be parsed are left out.
+    pub fn from_files(files: &PlaintextFiles, projects: &XcodeProjects) -> io::Result<Self> {
+        let build_scripts: BTreeMap<&str, BTreeMap<String, String>> = files
+            .matching(GRADLE_BUILD_GLOBS, MAX_APP_MANIFEST_SIZE)?
+            .into_iter()
+            .filter_map(|(path, content)| {
+                Some((
+                    path,
+                    parse_android_settings(std::str::from_utf8(content).ok()?),
+                ))
+            })
+            .collect();
+        let manifest_files = files.matching(APP_MANIFEST_GLOBS, MAX_APP_MANIFEST_SIZE)?;
+
+        let mut manifests: Vec<AppManifest> = manifest_files
+            .iter()
+            .filter_map(|(path, content)| parse_app_manifest(path, content))
+            .collect();
+        for manifest in &mut manifests {
+            if manifest.kind == AppManifestKind::AndroidManifest {
+                android::apply_build_script(manifest, &build_scripts);
+            }
+        }
+        apple::apply_build_settings(&mut manifests, projects, &manifest_files);
+        for manifest in &mut manifests {
+            manifest.flag_unresolved();
+        }
+        Ok(Self { manifests })
+    }
+}
+
+/// Parses `content` according to the file name of `path`. Returns `None` for files that
+/// aren't app manifests, or can't be parsed.
+///
+/// Only plists with `CFBundle` keys are taken for Info.plist files, which leaves out
+/// configuration files like `GoogleService-Info.plist`.
+pub fn parse_app_manifest(path: &str, content: &[u8]) -> Option<AppManifest> {
+    let file_name = Path::new(path).file_name()?.to_str()?;
+    if file_name == "AndroidManifest.xml" {
+        android::parse_android_manifest(path, std::str::from_utf8(content).ok()?)
+    } else if file_name.ends_with("Info.plist") {
+        apple::parse_info_plist(path, content)
+    } else if file_name.ends_with(".entitlements") {
+        apple::parse_entitlements(path, content)
+    } else if file_name.ends_with(".xcprivacy") {
+        apple::parse_privacy_manifest(path, content)
+    } else {
+        None
+    }
+}
+
+/// Finds the app manifests under `dir` and parses them, along with the Gradle build scripts
+/// and Xcode projects their values are resolved from.
+///
+/// The files are found with the exclude patterns, roots and [`ScanOptions::source`] of
+/// `options`; see [`PlaintextFiles::read`]. Manifests that can't be parsed are left out.
+pub async fn analyze_app_manifests(
+    dir: &Path,
+    options: &ScanOptions,
+) -> io::Result<AppManifestReport> {
+    let globs: Vec<&str> = [APP_MANIFEST_GLOBS, GRADLE_BUILD_GLOBS, XCODE_PROJECT_GLOBS].concat();
+    let max_file_size = MAX_APP_MANIFEST_SIZE.max(MAX_PROJECT_FILE_SIZE);
+    let files = PlaintextFiles::read(dir, &globs, max_file_size, options).await?;
+    AppManifestReport::from_files(&files, &XcodeProjects::from_files(&files)?)
+}
+
+/// Shows `report` full screen until the user continues.
+pub fn show_app_manifest_report(report: &AppManifestReport) -> io::Result<()> {
+    let view = AppManifestReportView::new(report);
+
+    let terminal = ratatui::init();
+
+    view.run(terminal)
+}
@@ -0,0 +1,112 @@
+use crate::app_manifest::AppManifestReport;
+use crate::terminal::finalize_terminal;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+    DefaultTerminal, Frame,
+};
+use std::io;
+
+const INFO_TEXT: &str = "(Enter) Continue | (↑) scroll up | (↓) scroll down";
+
+pub struct AppManifestReportView {
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+    manifest_count: usize,
+}
+
+impl AppManifestReportView {
+    pub fn new(report: &AppManifestReport) -> Self {
+        let mut lines = Vec::new();
+        for manifest in &report.manifests {
+            lines.push(Line::from(vec![
+                Span::from(manifest.path.clone()).bold(),
+                Span::from(format!("  {}", manifest.kind)).fg(tailwind::NEUTRAL.c400),
+            ]));
+            for (label, value) in manifest.fields() {
+                lines.push(Line::from(vec![
+                    Span::from(format!("  {:<16} ", format!("{}:", label)))
+                        .fg(tailwind::NEUTRAL.c400),
+                    Span::from(value),
+                ]));
+            }
+            lines.push(Line::default());
+        }
+
+        Self {
+            lines,
+            scroll: 0,
+            manifest_count: report.manifests.len(),
+        }
+    }
+
+    fn scroll_down(&mut self) {
+        #[allow(clippy::cast_possible_truncation)]
+        let max = self.lines.len().saturating_sub(1) as u16;
+        self.scroll = (self.scroll + 1).min(max);
+    }
+
+    fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn run(mut self, mut terminal: DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match key.code {
+                        KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => {
+                            finalize_terminal(&mut terminal)?;
+                            return Ok(());
+                        }
+                        KeyCode::Down => self.scroll_down(),
+                        KeyCode::Up => self.scroll_up(),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(4)]);
+        let rects = vertical.split(frame.area());
+
+        self.render_report(frame, rects[0]);
+        self.render_footer(frame, rects[1]);
+    }
+
+    fn render_report(&self, frame: &mut Frame, area: Rect) {
+        let report = Paragraph::new(self.lines.clone())
+            .scroll((self.scroll, 0))
+            .bg(tailwind::NEUTRAL.c950)
+            .block(Block::bordered().title(" App manifests "));
+        frame.render_widget(report, area);
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let summary_line = Line::from(format!(
+            "{} app manifest(s) will be attached to the upload as encrypted metadata",
+            self.manifest_count
+        ));
+
+        let info_footer = Paragraph::new(vec![Line::from(INFO_TEXT), summary_line])
+            .style(
+                Style::new()
+                    .fg(tailwind::NEUTRAL.c200)
+                    .bg(tailwind::NEUTRAL.c950),
+            )
+            .centered()
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(tailwind::NEUTRAL.c400)),
+            );
+        frame.render_widget(info_footer, area);
+    }
+}
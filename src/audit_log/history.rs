@@ -0,0 +1,170 @@
+use crate::audit_log::{AuditEntry, AuditEvent, AuditLog};
+use crate::key_pinning::format_fingerprint;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+/// One line per recorded event, oldest first.
+pub fn render_history(entries: &[AuditEntry]) -> String {
+    if entries.is_empty() {
+        return "No uploads recorded yet.\n".to_string();
+    }
+
+    let mut output = format!(
+        "{:<20}  {:<8}  {:<36}  {:<30}  {:>5}  {}\n",
+        "TIMESTAMP", "EVENT", "SESSION", "REPOSITORY", "FILES", "ENVIRONMENT"
+    );
+    for entry in entries {
+        let timestamp = entry.timestamp.format("%Y-%m-%d %H:%M:%S");
+        match &entry.event {
+            AuditEvent::Upload(record) => output.push_str(&format!(
+                "{:<20}  {:<8}  {:<36}  {:<30}  {:>5}  {}\n",
+                timestamp,
+                "upload",
+                record.session_id,
+                format!("{}/{}", record.org_name, record.repo_name),
+                record.files.len(),
+                record.environment
+            )),
+            AuditEvent::Revoke(record) => output.push_str(&format!(
+                "{:<20}  {:<8}  {:<36}  {:<30}  {:>5}  {}\n",
+                timestamp,
+                "revoke",
+                record.session_id,
+                "",
+                record
+                    .deleted_files
+                    .map(|count| count.to_string())
+                    .unwrap_or("-".to_string()),
+                record.environment
+            )),
+        }
+    }
+    output
+}
+
+/// Full details of every event recorded for a single session.
+pub fn render_session(entries: &[AuditEntry], session_id: &str) -> String {
+    let mut output = String::new();
+
+    for entry in entries
+        .iter()
+        .filter(|e| e.event.session_id() == session_id)
+    {
+        match &entry.event {
+            AuditEvent::Upload(record) => {
+                output.push_str(&format!("Upload at {}\n", entry.timestamp.to_rfc3339()));
+                output.push_str(&format!("  Environment:     {}\n", record.environment));
+                output.push_str(&format!("  Session:         {}\n", record.session_id));
+                output.push_str(&format!("  Integration:     {}\n", record.integration_id));
+                output.push_str(&format!(
+                    "  Repository:      {}/{}\n",
+                    record.org_name, record.repo_name
+                ));
+                output.push_str(&format!(
+                    "  Git HEAD:        {}\n",
+                    record.git_head.as_deref().unwrap_or("(unknown)")
+                ));
+                output.push_str(&format!(
+                    "  Server key:      {}\n",
+                    format_fingerprint(&record.server_key_fingerprint)
+                ));
+                if let Some(base_session_id) = &record.base_session_id {
+                    output.push_str(&format!("  Changes since:   {}\n", base_session_id));
+                }
+                output.push_str(&format!("  Files ({}):\n", record.files.len()));
+                for file in &record.files {
+                    output.push_str(&format!(
+                        "    {}  {} bytes  sha256:{}\n",
+                        file.path, file.size, file.plaintext_sha256
+                    ));
+                }
+                if !record.deleted_files.is_empty() {
+                    output.push_str(&format!(
+                        "  Deleted files ({}):\n",
+                        record.deleted_files.len()
+                    ));
+                    for path in &record.deleted_files {
+                        output.push_str(&format!("    {}\n", path));
+                    }
+                }
+            }
+            AuditEvent::Revoke(record) => {
+                output.push_str(&format!("Revoked at {}\n", entry.timestamp.to_rfc3339()));
+                output.push_str(&format!("  Environment:     {}\n", record.environment));
+                output.push_str(&format!("  Integration:     {}\n", record.integration_id));
+                if let Some(count) = record.deleted_files {
+                    output.push_str(&format!("  Deleted files:   {}\n", count));
+                }
+                if let Some(count) = record.deleted_artifacts {
+                    output.push_str(&format!("  Deleted derived artifacts: {}\n", count));
+                }
+            }
+        }
+    }
+
+    if output.is_empty() {
+        output = format!("No uploads recorded for session {}.\n", session_id);
+    }
+    output
+}
+
+pub fn print_history(state_dir: &Path, session_id: Option<&str>, json: bool) -> io::Result<()> {
+    let entries = AuditLog::new(state_dir).entries()?;
+    let entries: Vec<AuditEntry> = match session_id {
+        Some(session_id) => entries
+            .into_iter()
+            .filter(|entry| entry.event.session_id() == session_id)
+            .collect(),
+        None => entries,
+    };
+
+    if json {
+        let output = serde_json::to_string_pretty(&entries).map_err(io::Error::other)?;
+        println!("{}", output);
+    } else {
+        match session_id {
+            Some(session_id) => print!("{}", render_session(&entries, session_id)),
+            None => print!("{}", render_history(&entries)),
+        }
+    }
+    Ok(())
+}
+
+/// Lists uploads that have not been revoked and asks which one to revoke.
+pub fn prompt_upload_selection(uploads: &[AuditEntry]) -> io::Result<Option<String>> {
+    if uploads.is_empty() {
+        println!("No uploads left to revoke.");
+        return Ok(None);
+    }
+
+    for (index, entry) in uploads.iter().enumerate() {
+        if let AuditEvent::Upload(record) = &entry.event {
+            println!(
+                "{:>3}) {}  {}  {}/{}  ({} files)",
+                index + 1,
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                record.session_id,
+                record.org_name,
+                record.repo_name,
+                record.files.len()
+            );
+        }
+    }
+    print!(
+        "Select an upload to revoke (1-{}, empty to cancel): ",
+        uploads.len()
+    );
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(answer
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|choice| choice.checked_sub(1))
+        .and_then(|index| uploads.get(index))
+        .map(|entry| entry.event.session_id().to_string()))
+}
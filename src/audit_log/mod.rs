@@ -0,0 +1,165 @@
+pub mod history;
+
+use crate::upload_manifest::UploadManifestEntry;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const AUDIT_LOG_FILE_NAME: &str = "audit.log";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadRecord {
+    pub environment: String,
+    pub session_id: String,
+    pub integration_id: String,
+    pub org_name: String,
+    pub repo_name: String,
+    pub git_head: Option<String>,
+    pub server_key_fingerprint: String,
+    pub files: Vec<UploadManifestEntry>,
+    /// Set for incremental uploads: the session whose upload `files` were changes to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deleted_files: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeRecord {
+    pub environment: String,
+    pub session_id: String,
+    pub integration_id: String,
+    pub deleted_files: Option<u64>,
+    pub deleted_artifacts: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum AuditEvent {
+    Upload(UploadRecord),
+    Revoke(RevokeRecord),
+}
+
+impl AuditEvent {
+    pub fn session_id(&self) -> &str {
+        match self {
+            AuditEvent::Upload(record) => &record.session_id,
+            AuditEvent::Revoke(record) => &record.session_id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+/// Append-only JSON Lines log of what this installation sent, kept in the state directory.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(state_dir: &Path) -> Self {
+        Self {
+            path: state_dir.join(AUDIT_LOG_FILE_NAME),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, event: AuditEvent) -> io::Result<AuditEntry> {
+        let entry = AuditEntry {
+            timestamp: Utc::now(),
+            event,
+        };
+        let mut line = serde_json::to_string(&entry).map_err(io::Error::other)?;
+        line.push('\n');
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = open_append(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        file.sync_all()?;
+
+        Ok(entry)
+    }
+
+    /// The most recent upload recorded for `session_id`.
+    pub fn find_upload(&self, session_id: &str) -> io::Result<Option<UploadRecord>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .rev()
+            .find_map(|entry| match entry.event {
+                AuditEvent::Upload(record) if record.session_id == session_id => Some(record),
+                _ => None,
+            }))
+    }
+
+    /// Uploads whose session has not been revoked since, oldest first.
+    pub fn active_uploads(&self) -> io::Result<Vec<AuditEntry>> {
+        let entries = self.entries()?;
+        let revoked: Vec<&str> = entries
+            .iter()
+            .filter(|entry| matches!(entry.event, AuditEvent::Revoke(_)))
+            .map(|entry| entry.event.session_id())
+            .collect();
+
+        Ok(entries
+            .iter()
+            .filter(|entry| matches!(entry.event, AuditEvent::Upload(_)))
+            .filter(|entry| !revoked.contains(&entry.event.session_id()))
+            .cloned()
+            .collect())
+    }
+
+    /// All entries in the order they were recorded. Unreadable lines are skipped with a warning.
+    pub fn entries(&self) -> io::Result<Vec<AuditEntry>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(|(index, line)| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!("Skipping unreadable audit log line {}: {}", index + 1, e);
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(unix)]
+fn open_append(path: &Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .mode(0o600)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn open_append(path: &Path) -> io::Result<fs::File> {
+    fs::OpenOptions::new().append(true).create(true).open(path)
+}
@@ -1,16 +1,51 @@
+use catch_cli::api_client::cli_entity::CatchConnectCLIResponse;
+use catch_cli::api_client::CatchApiClient;
+use catch_cli::app_manifest::{
+    analyze_app_manifests, show_app_manifest_report, AppManifestReport, APP_MANIFEST_GLOBS,
+    GRADLE_BUILD_GLOBS, MAX_APP_MANIFEST_SIZE,
+};
+use catch_cli::audit_log::history::{print_history, prompt_upload_selection};
+use catch_cli::audit_log::{AuditEvent, AuditLog, UploadRecord};
 use catch_cli::code_analyzer::ui::request_code_candidates;
 use catch_cli::code_candidate_selector::{filter_code_files, select_codes};
-use catch_cli::code_reader::find_and_read_files;
-use catch_cli::code_uploader::upload_codes;
+use catch_cli::code_reader::budget::{format_size, parse_size, SizeLimits};
+use catch_cli::code_reader::source::FileSource;
+use catch_cli::code_reader::ui::{index_files_with_progress, read_files_with_progress};
+use catch_cli::code_reader::walker::{summarize_pruned, SymlinkPolicy};
+use catch_cli::code_reader::{index_files, PlaintextFiles, ScanOptions};
+use catch_cli::code_revoker::{affected_sessions, find_upload, revoke_upload, RevokeScope};
+use catch_cli::code_uploader::{upload_codes, UploadMetadata};
+use catch_cli::config::{config_dir, state_dir, CatchConfig};
+use catch_cli::cryptography::envelope::ENVELOPE_VERSION;
+use catch_cli::cryptography::{public_key_fingerprint, FileCipher};
+use catch_cli::dependency_summary::{
+    summarize_dependencies, DependencySummary, MANIFEST_GLOBS, MAX_MANIFEST_SIZE,
+};
 use catch_cli::git_info;
+use catch_cli::git_info::repository::GitRepository;
+use catch_cli::key_pinning::{check_server_key, format_fingerprint, KeyPinStatus};
 use catch_cli::ongoing_session::active_session_checker::{
     handle_sessions, is_session_valid, CatchSessionError,
 };
 use catch_cli::ongoing_session::session_connector::connect_cli_to_session;
+use catch_cli::project_roots::{detect_project_roots, pick_project_roots, resolve_roots};
+use catch_cli::secret_scanner::SecretPolicy;
+use catch_cli::session_sync::{watch_session, SessionSync};
+use catch_cli::terminal::{prompt_confirmation, prompt_confirmation_on};
+use catch_cli::upload_cache::{UploadCache, UploadDelta, UploadScope, UploadSnapshot};
+use catch_cli::upload_manifest::{load_or_create_signing_key, UploadManifest};
+use catch_cli::xcode_project::{
+    analyze_xcode_projects, pick_targets, XcodeProjects, MAX_PROJECT_FILE_SIZE, XCODE_PROJECT_GLOBS,
+};
+use clap::{Parser, Subcommand};
 use flume::{Receiver, Sender};
-use log::{error, info};
+use log::{error, info, warn};
 use once_cell::sync::Lazy;
+use std::io::IsTerminal;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::sync::Arc;
 use std::{io, panic};
 
 pub static SIGNALING_STOP: Lazy<(Sender<()>, Receiver<()>)> = Lazy::new(flume::unbounded);
@@ -22,7 +57,120 @@ fn shutdown() {
     exit(0);
 }
 
+#[derive(Parser)]
+#[command(name = "catch", version, about = "Catch (trycatch.ai) CLI")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Convert CRLF line endings to LF before uploading
+    #[arg(long)]
+    normalize_line_endings: bool,
+    /// Also scan files matching this glob, e.g. `**/*.graphql`
+    #[arg(long = "include", value_name = "GLOB", global = true)]
+    includes: Vec<String>,
+    /// Leave out files and directories matching this gitignore-style pattern, e.g. `secrets/`
+    #[arg(long = "exclude", value_name = "PATTERN", global = true)]
+    excludes: Vec<String>,
+    /// Refuse to upload when likely secrets are found, instead of asking what to do
+    #[arg(long)]
+    fail_on_secrets: bool,
+    /// Skip files larger than this, e.g. `512K` or `2M`
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    max_file_size: Option<u64>,
+    /// Stop adding files once their combined size reaches this, e.g. `20M`
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    max_total_size: Option<u64>,
+    /// Only scan this directory of the repository; can be repeated
+    #[arg(long = "root", value_name = "DIR")]
+    roots: Vec<PathBuf>,
+    /// How to treat symbolic links: `skip`, `within-root` or `follow`
+    #[arg(long, value_name = "POLICY", default_value = "within-root")]
+    symlinks: SymlinkPolicy,
+    /// Read and encrypt this many files at once [default: number of CPUs]
+    #[arg(long, short, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+    /// Only scan files in the git index, leaving out untracked files
+    #[arg(long, global = true)]
+    tracked_only: bool,
+    /// Scan files as committed in this commit, branch or tag instead of the working tree.
+    /// Takes anything git does, such as a commit id, a branch name, HEAD~1 or @{u}
+    #[arg(
+        long,
+        value_name = "REV",
+        global = true,
+        conflicts_with = "tracked_only"
+    )]
+    rev: Option<String>,
+    /// Upload every selected file, even when only some changed since the last upload
+    #[arg(long)]
+    full: bool,
+    /// Only scan the files of this Xcode target, besides files in no target; can be repeated
+    #[arg(long = "target", value_name = "NAME")]
+    targets: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List past uploads recorded in the local audit log, or show one of them
+    History {
+        /// Show the full record of uploads for this session
+        session_id: Option<String>,
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+        /// Revoke this session's upload, or pick one to revoke
+        #[arg(long)]
+        revoke: bool,
+        /// Don't ask for confirmation before revoking
+        #[arg(long, short, requires = "revoke")]
+        yes: bool,
+        /// Revoke everything uploaded to the session's integration, in any session
+        #[arg(long, requires = "revoke")]
+        whole_integration: bool,
+    },
+    /// Ask Catch to delete the code uploaded in a session and everything derived from it
+    Revoke {
+        session_id: String,
+        /// Don't ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+        /// Delete everything uploaded to the session's integration, including the uploads of
+        /// its other sessions
+        #[arg(long)]
+        whole_integration: bool,
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the include and exclude rules a scan of the current directory would use
+    Rules,
+    /// Keep the active session in sync with local edits to the uploaded files
+    Watch,
+    /// Print the app IDs, SDK levels, permissions, URL schemes, capabilities and privacy
+    /// manifest entries of the mobile apps a scan of the current directory finds
+    AppManifests {
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the dependencies declared in the manifests a scan of the current directory finds
+    Dependencies {
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the targets of the Xcode projects a scan of the current directory finds, marking
+    /// those scanned by default
+    Targets {
+        /// Print machine-readable JSON, with the files of each target
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+
     let _guard = sentry::init((
         "<redacted>",
         sentry::ClientOptions {
@@ -45,124 +193,776 @@ fn main() -> io::Result<()> {
                 shutdown();
             }));
 
-            // find ongoing session
-            let temp_path = std::env::temp_dir();
+            let current_dir = std::env::current_dir()?;
+            let repo_root =
+                git_info::find_repository_root(&current_dir).unwrap_or(current_dir.clone());
+            let roots = match resolve_roots(&repo_root, &current_dir, &cli.roots) {
+                Ok(roots) => roots,
+                Err(e) => {
+                    error!("Invalid scan root: {}", e);
+                    exit(-11);
+                }
+            };
+            let ask_for_roots = cli.roots.is_empty() && roots.is_empty();
+            let source = match cli.rev {
+                Some(rev) => match GitRepository::open(&repo_root).and_then(|r| r.resolve(&rev)) {
+                    Ok(commit) => {
+                        info!(":🔖 Scanning {} at commit {}", rev, commit);
+                        FileSource::Revision(commit.to_hex())
+                    }
+                    Err(e) => {
+                        error!("Invalid revision {}: {}", rev, e);
+                        exit(-12);
+                    }
+                },
+                None if cli.tracked_only => FileSource::TrackedOnly,
+                None => FileSource::WorkingTree,
+            };
+
+            let default_limits = SizeLimits::default();
+            let scan_options = ScanOptions {
+                normalize_line_endings: cli.normalize_line_endings,
+                includes: cli.includes,
+                excludes: cli.excludes,
+                secret_policy: if cli.fail_on_secrets {
+                    SecretPolicy::Fail
+                } else {
+                    SecretPolicy::Prompt
+                },
+                size_limits: SizeLimits {
+                    max_file_size: cli.max_file_size.unwrap_or(default_limits.max_file_size),
+                    max_total_size: cli.max_total_size.unwrap_or(default_limits.max_total_size),
+                },
+                symlinks: cli.symlinks,
+                roots,
+                source,
+                jobs: cli.jobs,
+                ..Default::default()
+            };
 
-            let active_session_id = match handle_sessions(&temp_path) {
-                Ok(session_id) => {
-                    info!("Found catch session: {}", session_id);
-                    session_id
+            match cli.command {
+                Some(Command::History {
+                    session_id,
+                    revoke: true,
+                    yes,
+                    whole_integration,
+                    json,
+                }) => {
+                    let session_id = match session_id {
+                        Some(session_id) => Some(session_id),
+                        None => prompt_upload_selection(
+                            &AuditLog::new(&state_dir()?).active_uploads()?,
+                        )?,
+                    };
+                    match session_id {
+                        Some(session_id) => {
+                            run_revoke(&session_id, yes, whole_integration, json).await
+                        }
+                        None => Ok(()),
+                    }
+                }
+                Some(Command::History {
+                    session_id, json, ..
+                }) => print_history(&state_dir()?, session_id.as_deref(), json),
+                Some(Command::Revoke {
+                    session_id,
+                    yes,
+                    whole_integration,
+                    json,
+                }) => run_revoke(&session_id, yes, whole_integration, json).await,
+                Some(Command::Rules) => {
+                    let rules = scan_options.rules(&repo_root)?;
+                    let index = index_files(&repo_root, &scan_options).await?;
+                    print!(
+                        "{}",
+                        rules.render(&scan_options.roots, &index.nested_catchignore_rules)
+                    );
+                    Ok(())
+                }
+                Some(Command::Watch) => run_watch(scan_options, &repo_root).await,
+                Some(Command::AppManifests { json }) => {
+                    let report = analyze_app_manifests(&repo_root, &scan_options).await?;
+                    match json {
+                        true => println!(
+                            "{}",
+                            serde_json::to_string_pretty(&report).map_err(io::Error::other)?
+                        ),
+                        false => print!("{}", report.render()),
+                    }
+                    Ok(())
                 }
-                Err(CatchSessionError::NoSessionFound) => {
-                    exit(-1);
+                Some(Command::Dependencies { json }) => {
+                    let summary = summarize_dependencies(&repo_root, &scan_options).await?;
+                    match json {
+                        true => println!(
+                            "{}",
+                            serde_json::to_string_pretty(&summary).map_err(io::Error::other)?
+                        ),
+                        false => print!("{}", summary.render()),
+                    }
+                    Ok(())
                 }
-                Err(CatchSessionError::MultipleSessionsFound) => {
-                    exit(-2);
+                Some(Command::Targets { json }) => {
+                    let projects = analyze_xcode_projects(&repo_root, &scan_options).await?;
+                    match json {
+                        true => println!(
+                            "{}",
+                            serde_json::to_string_pretty(&projects).map_err(io::Error::other)?
+                        ),
+                        false => print!("{}", projects.render()),
+                    }
+                    Ok(())
                 }
-                Err(CatchSessionError::IoError(e)) => {
-                    error!("An IO error occurred: {}", e);
-                    exit(-3)
+                None => {
+                    run_onboarding(
+                        scan_options,
+                        &repo_root,
+                        ask_for_roots,
+                        cli.full,
+                        &cli.targets,
+                    )
+                    .await
                 }
-            };
+            }
+        })
+}
+
+async fn run_revoke(
+    session_id: &str,
+    skip_confirmation: bool,
+    whole_integration: bool,
+    json: bool,
+) -> io::Result<()> {
+    let state_dir = state_dir()?;
+    let audit_log = AuditLog::new(&state_dir);
 
-            match is_session_valid(active_session_id.clone()).await {
-                Ok(is_valid) => {
-                    if !is_valid {
-                        error!(
+    let upload = match find_upload(&audit_log, session_id) {
+        Ok(upload) => upload,
+        Err(e) => {
+            error!("Failed to revoke session {}: {}", session_id, e);
+            exit(-9);
+        }
+    };
+
+    let scope = match whole_integration {
+        true => RevokeScope::Integration,
+        false => RevokeScope::Session,
+    };
+    if !skip_confirmation {
+        let sessions = affected_sessions(&audit_log, &upload, scope)?;
+        let question = match scope {
+            RevokeScope::Session => format!(
+                "This deletes the code uploaded in session {} to integration {} on {} and \
+                 everything derived from it. Continue?",
+                upload.session_id, upload.integration_id, upload.environment
+            ),
+            RevokeScope::Integration => format!(
+                "This deletes everything uploaded to integration {} on {} ({} session(s): {}) \
+                 and everything derived from it. Continue?",
+                upload.integration_id,
+                upload.environment,
+                sessions.len(),
+                sessions.join(", ")
+            ),
+        };
+        // Keep stdout to the JSON record.
+        let confirmed = match json {
+            true => prompt_confirmation_on(&mut io::stderr(), &question)?,
+            false => prompt_confirmation(&question)?,
+        };
+        if !confirmed {
+            eprintln!("Canceled");
+            return Ok(());
+        }
+    }
+
+    match revoke_upload(&audit_log, &upload, scope).await {
+        Ok(record) => {
+            match scope {
+                RevokeScope::Session => info!(
+                    ":✅ Catch confirmed deletion for session {}",
+                    record.session_id
+                ),
+                RevokeScope::Integration => info!(
+                    ":✅ Catch confirmed deletion for integration {}",
+                    record.integration_id
+                ),
+            }
+            let upload_cache =
+                UploadCache::new(&state_dir, &record.environment, &record.integration_id);
+            if let Err(e) = upload_cache.clear() {
+                warn!("Failed to clear the upload cache: {}", e);
+            }
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&record).map_err(io::Error::other)?
+                );
+                return Ok(());
+            }
+            if let Some(count) = record.deleted_files {
+                println!("Deleted files: {}", count);
+            }
+            if let Some(count) = record.deleted_artifacts {
+                println!("Deleted derived artifacts: {}", count);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            error!("Failed to revoke session {}: {}", session_id, e);
+            exit(-9);
+        }
+    }
+}
+
+async fn run_onboarding(
+    mut scan_options: ScanOptions,
+    repo_root: &Path,
+    ask_for_roots: bool,
+    full_upload: bool,
+    targets: &[String],
+) -> io::Result<()> {
+    // find ongoing session
+    let temp_path = std::env::temp_dir();
+
+    let active_session_id = match handle_sessions(&temp_path) {
+        Ok(session_id) => {
+            info!("Found catch session: {}", session_id);
+            session_id
+        }
+        Err(CatchSessionError::NoSessionFound) => {
+            exit(-1);
+        }
+        Err(CatchSessionError::MultipleSessionsFound) => {
+            exit(-2);
+        }
+        Err(CatchSessionError::IoError(e)) => {
+            error!("An IO error occurred: {}", e);
+            exit(-3)
+        }
+    };
+
+    match is_session_valid(active_session_id.clone()).await {
+        Ok(is_valid) => {
+            if !is_valid {
+                error!(
                     "This session({}) is already being processed. Please start a new session.",
                     active_session_id
                 );
-                        exit(-4);
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to check session status: {}", e);
-                    exit(-5);
+                exit(-4);
+            }
+        }
+        Err(e) => {
+            error!("Failed to check session status: {}", e);
+            exit(-5);
+        }
+    };
+
+    let (org_name, repo_name) = git_info::get_repo_info(repo_root)?;
+    let cli_connect_result = match connect_cli_to_session(
+        active_session_id.clone(),
+        org_name.clone(),
+        repo_name.clone(),
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Failed to connect CLI to session: {}", e);
+            exit(-4);
+        }
+    };
+
+    info!(
+        ":✅ Connected CLI to session: {:?}",
+        cli_connect_result.integration_id
+    );
+
+    let config_dir = config_dir()?;
+    let environment = CatchApiClient::default().base_url().to_string();
+    let server_key_fingerprint = pin_server_key(&config_dir, &environment, &cli_connect_result)?;
+    let cipher = Arc::new(create_cipher(&cli_connect_result));
+
+    if ask_for_roots && io::stdin().is_terminal() {
+        let detected_roots = detect_project_roots(repo_root);
+        if detected_roots.len() > 1 {
+            scan_options.roots = match pick_project_roots(&detected_roots) {
+                Ok(roots) => roots,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                    println!("Canceled");
+                    return Ok(());
                 }
+                Err(e) => return Err(e),
             };
+        }
+    }
+    if !scan_options.roots.is_empty() {
+        info!(":📁 Scanning {}", scan_options.roots.join(", "));
+    }
 
-            let (org_name, repo_name) = git_info::get_repo_info()?;
-            let cli_connect_result = match connect_cli_to_session(
-                active_session_id.clone(),
-                org_name,
-                repo_name,
-            )
+    let mut index = index_files_with_progress(repo_root, &scan_options).await?;
+    for skipped in &index.skipped {
+        warn!("Skipped {}: {}", skipped.path, skipped.reason);
+    }
+    let (skipped_links, pruned): (Vec<_>, Vec<_>) = index
+        .pruned
+        .iter()
+        .cloned()
+        .partition(|entry| entry.reason.is_symlink());
+    for link in &skipped_links {
+        warn!("Skipped link {}: {}", link.path, link.reason);
+    }
+    if !pruned.is_empty() {
+        info!(":🧹 Pruned {}", summarize_pruned(&pruned));
+    }
+    for file in &index.files {
+        info!(":📄 Found supported file: {:?}", file.path);
+    }
+
+    // Xcode projects, dependency manifests and app manifests come from one walk.
+    let metadata_globs: Vec<&str> = [
+        XCODE_PROJECT_GLOBS,
+        MANIFEST_GLOBS,
+        APP_MANIFEST_GLOBS,
+        GRADLE_BUILD_GLOBS,
+    ]
+    .concat();
+    let max_metadata_size = MAX_PROJECT_FILE_SIZE
+        .max(MAX_MANIFEST_SIZE)
+        .max(MAX_APP_MANIFEST_SIZE);
+    let metadata_files =
+        match PlaintextFiles::read(repo_root, &metadata_globs, max_metadata_size, &scan_options)
             .await
-            {
-                Ok(response) => response,
-                Err(e) => {
-                    error!("Failed to connect CLI to session: {}", e);
-                    exit(-4);
+        {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Failed to read project metadata: {}", e);
+                PlaintextFiles::default()
+            }
+        };
+
+    let xcode_targets = match XcodeProjects::from_files(&metadata_files) {
+        Ok(projects) if !projects.is_empty() => {
+            let selected = if !targets.is_empty() {
+                match projects.select_by_name(targets) {
+                    Ok(selected) => selected,
+                    Err(e) => {
+                        error!("{}", e);
+                        exit(-14);
+                    }
                 }
+            } else if io::stdin().is_terminal() {
+                pick_targets(&projects)?
+            } else {
+                projects.default_selection()
             };
+            for target in &selected {
+                info!(":🎯 {} ({})", target.target, target.project);
+            }
+            let (kept, left_out) = projects.filter_files(index.files, &selected);
+            if !left_out.is_empty() {
+                info!(
+                    ":🧹 Left out {} file(s) of unselected Xcode targets",
+                    left_out.len()
+                );
+            }
+            index.files = kept;
+            Some((projects, selected))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Failed to analyze Xcode projects: {}", e);
+            None
+        }
+    };
 
-            info!(
-                ":✅ Connected CLI to session: {:?}",
-                cli_connect_result.integration_id
-            );
+    let code_candidate_result = match request_code_candidates(
+        cli_connect_result.integration_id.clone(),
+        active_session_id.clone(),
+        index.paths(),
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Failed to request code candidates: {}", e);
+            exit(-6);
+        }
+    };
 
-            let encryption_key = rand::random::<[u8; 32]>();
-            let iv = rand::random::<[u8; 16]>();
+    let candidate_files = filter_code_files(index.files, code_candidate_result.candidates);
 
-            let current_dir = std::env::current_dir()?;
-            let pre_target_files = find_and_read_files(&current_dir, &encryption_key, &iv).await?;
+    let selected_files =
+        select_codes(candidate_files, scan_options.size_limits.max_total_size).unwrap_or_default();
 
-            for file in &pre_target_files.clone() {
-                info!(":📄 Found supported file: {:?}", file.path);
+    let scan_result =
+        read_files_with_progress(repo_root, &selected_files, &cipher, &scan_options).await?;
+    if scan_options.secret_policy == SecretPolicy::Fail && !scan_result.secrets.is_empty() {
+        for report in &scan_result.secrets {
+            for finding in &report.findings {
+                error!("Possible secret in {}, {}", report.path, finding);
             }
+        }
+        error!("Refusing to upload: likely secrets found");
+        exit(-10);
+    }
+    for report in &scan_result.secrets {
+        warn!(
+            "{} likely secret(s) in {}: {}",
+            report.findings.len(),
+            report.path,
+            report.decision
+        );
+    }
+    for skipped in &scan_result.skipped {
+        warn!("Skipped {}: {}", skipped.path, skipped.reason);
+    }
+    info!(
+        ":📦 Read {} file(s), {} of {} budget",
+        scan_result.files.len(),
+        format_size(scan_result.total_size),
+        format_size(scan_options.size_limits.max_total_size)
+    );
+    let selected_files = scan_result.files;
 
-            let code_candidate_result = match request_code_candidates(
-                cli_connect_result.integration_id.clone(),
-                active_session_id.clone(),
-                pre_target_files.clone(),
-            )
-            .await
-            {
-                Ok(result) => result,
-                Err(e) => {
-                    error!("Failed to request code candidates: {}", e);
-                    exit(-6);
-                }
+    let dependency_summary = match DependencySummary::from_files(&metadata_files) {
+        Ok(summary) if !summary.is_empty() => {
+            for manifest in &summary.manifests {
+                info!(":🧩 {} ({})", manifest.path, manifest.describe());
+            }
+            summary
+                .seal(&cipher)
+                .inspect_err(|e| warn!("Failed to encrypt the dependency summary: {}", e))
+                .ok()
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Failed to summarize dependencies: {}", e);
+            None
+        }
+    };
+    let no_projects = XcodeProjects::default();
+    let projects = xcode_targets
+        .as_ref()
+        .map_or(&no_projects, |(projects, _)| projects);
+    let app_manifests = match AppManifestReport::from_files(&metadata_files, projects) {
+        Ok(report) if !report.is_empty() => {
+            for manifest in &report.manifests {
+                info!(":📱 {} ({})", manifest.path, manifest.kind);
+            }
+            if io::stdin().is_terminal() {
+                show_app_manifest_report(&report)?;
+            }
+            report
+                .seal(&cipher)
+                .inspect_err(|e| warn!("Failed to encrypt the app manifest report: {}", e))
+                .ok()
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Failed to analyze app manifests: {}", e);
+            None
+        }
+    };
+    // Everything read from the metadata files is sealed by now; zero their plaintext.
+    drop(metadata_files);
+    let selected_targets: Vec<String> = xcode_targets
+        .iter()
+        .flat_map(|(_, selected)| selected)
+        .map(|key| format!("{}/{}", key.project, key.target))
+        .collect();
+    let xcode_targets = xcode_targets.and_then(|(projects, selected)| {
+        let paths: Vec<_> = selected_files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        projects
+            .membership(&paths, &selected)
+            .seal(&cipher)
+            .inspect_err(|e| warn!("Failed to encrypt the Xcode target membership: {}", e))
+            .ok()
+    });
+    let metadata = UploadMetadata {
+        dependency_summary,
+        app_manifests,
+        xcode_targets,
+    };
+
+    let upload_cache = state_dir()
+        .map(|dir| UploadCache::new(&dir, &environment, &cli_connect_result.integration_id));
+    let scope = UploadScope::new(&scan_options, selected_targets);
+    let snapshot = UploadSnapshot::new(
+        &active_session_id,
+        &org_name,
+        &repo_name,
+        scope.clone(),
+        &selected_files,
+    );
+    let delta = match &upload_cache {
+        Ok(upload_cache) if !full_upload => match upload_cache.load(&org_name, &repo_name) {
+            Ok(Some(previous)) if previous.scope != scope => {
+                info!(
+                    ":♻️ The upload of session {} covered other directories, targets or files; \
+                     uploading every file",
+                    previous.session_id
+                );
+                None
+            }
+            Ok(Some(previous)) => offer_incremental_upload(previous.diff(&selected_files))?,
+            Ok(None) => None,
+            Err(e) => {
+                warn!("Ignoring unreadable upload cache: {}", e);
+                None
+            }
+        },
+        _ => None,
+    };
+    if let Some(delta) = delta.as_ref().filter(|delta| delta.change_count() == 0) {
+        info!(
+            ":♻️ Nothing changed since the upload of session {}; pass --full to upload every \
+             file again",
+            delta.base_session_id
+        );
+        return Ok(());
+    }
+    let selected_files = match &delta {
+        Some(delta) => delta.changed_files(selected_files),
+        None => selected_files,
+    };
+
+    let signing_key = load_or_create_signing_key(&config_dir)?;
+    let mut manifest = UploadManifest::new(
+        &active_session_id,
+        &cli_connect_result.integration_id,
+        &selected_files,
+    );
+    if let Some(delta) = delta {
+        manifest = manifest.incremental(&delta.base_session_id, delta.deleted);
+    }
+    let manifest = manifest.with_metadata(&metadata).sign(&signing_key);
+
+    let upload_file_result = upload_codes(
+        cli_connect_result.integration_id,
+        active_session_id.clone(),
+        selected_files,
+        &cipher,
+        &cli_connect_result.public_key,
+        &manifest,
+        metadata,
+    )
+    .await;
+
+    match upload_file_result {
+        Ok(_) => {
+            info!(":✅ Uploaded code files successfully");
+
+            match state_dir().and_then(|dir| manifest.save(&dir)) {
+                Ok(path) => info!(":🧾 Saved signed upload manifest: {}", path.display()),
+                Err(e) => warn!("Failed to save upload manifest: {}", e),
+            }
+            if let Err(e) = upload_cache.and_then(|cache| cache.save(&snapshot)) {
+                warn!("Failed to update the upload cache: {}", e);
+            }
+
+            let record = UploadRecord {
+                environment,
+                session_id: active_session_id.clone(),
+                integration_id: manifest.manifest.integration_id.clone(),
+                org_name,
+                repo_name,
+                git_head: match &scan_options.source {
+                    FileSource::Revision(commit) => Some(commit.clone()),
+                    _ => git_info::get_git_head(repo_root).ok(),
+                },
+                server_key_fingerprint,
+                files: manifest.manifest.files.clone(),
+                base_session_id: manifest.manifest.base_session_id.clone(),
+                deleted_files: manifest.manifest.deleted_files.clone(),
             };
+            match state_dir().and_then(|dir| AuditLog::new(&dir).append(AuditEvent::Upload(record)))
+            {
+                Ok(_) => info!(":📒 Recorded upload in the local audit log"),
+                Err(e) => warn!("Failed to record upload in the audit log: {}", e),
+            }
 
-            let candidate_code_result =
-                filter_code_files(pre_target_files, code_candidate_result.candidates.clone());
+            println!("\n");
+            println!("Now, you can check the progress of the session on the Catch.");
 
-            let selected_files = select_codes(candidate_code_result.clone()).unwrap_or_default();
+            let url = format!(
+                "https://trycatch.ai/onboarding/{}?step=3-generating",
+                active_session_id
+            );
+            println!("🚀  {}", url);
 
-            let upload_file_result = upload_codes(
-                cli_connect_result.integration_id,
-                active_session_id.clone(),
-                selected_files,
-                encryption_key,
-                iv,
-                cli_connect_result.public_key,
-            )
-            .await;
+            if webbrowser::open(url.as_str()).is_ok() {
+                info!("Import files via CLI completed!")
+            }
+        }
+        Err(e) => {
+            error!("Failed to upload code files: {}", e);
+            exit(-7);
+        }
+    }
 
-            match upload_file_result {
-                Ok(_) => {
-                    info!(":✅ Uploaded code files successfully");
+    Ok(())
+}
 
-                    println!("\n");
-                    println!("Now, you can check the progress of the session on the Catch.");
+/// Asks whether to upload only what changed since the last upload. Returns the delta to
+/// upload, or `None` for a full upload. An empty delta is returned without asking, as there's
+/// nothing to upload.
+fn offer_incremental_upload(delta: UploadDelta) -> io::Result<Option<UploadDelta>> {
+    if delta.change_count() == 0 {
+        return Ok(Some(delta));
+    }
+    if delta.unchanged.is_empty() || !io::stdin().is_terminal() {
+        return Ok(None);
+    }
 
-                    let url = format!(
-                        "https://trycatch.ai/onboarding/{}?step=3-generating",
-                        active_session_id
-                    );
-                    println!("🚀  {}", url);
+    info!(
+        ":♻️ Since the upload of session {}: {}",
+        delta.base_session_id,
+        delta.summary()
+    );
+    for path in &delta.deleted {
+        info!(":🗑️ Deleted since then: {}", path);
+    }
+    let question = format!("Upload only the {} change(s)?", delta.change_count());
+    Ok(prompt_confirmation(&question)?.then_some(delta))
+}
 
-                    if webbrowser::open(url.as_str()).is_ok() {
-                        info!("Import files via CLI completed!")
-                    }
+/// Checks the session's server key against the one pinned for `environment` and returns its
+/// fingerprint. Exits when the key can't be trusted.
+fn pin_server_key(
+    config_dir: &Path,
+    environment: &str,
+    cli_connect_result: &CatchConnectCLIResponse,
+) -> io::Result<String> {
+    let mut config = CatchConfig::load(config_dir)?;
+    match check_server_key(
+        &mut config,
+        environment,
+        &cli_connect_result.public_key,
+        cli_connect_result.public_key_signature.as_deref(),
+    ) {
+        Ok(status) => {
+            info!(
+                ":🔑 Server key fingerprint: {}",
+                format_fingerprint(status.fingerprint())
+            );
+            match &status {
+                KeyPinStatus::FirstUse { .. } => {
+                    info!("Pinned the server key for this environment");
+                    config.save(config_dir)?;
                 }
-                Err(e) => {
-                    error!("Failed to upload code files: {}", e);
-                    exit(-7);
+                KeyPinStatus::Rotated { previous, .. } => {
+                    warn!(
+                        "Server key rotated from {} (signed by a trusted root key)",
+                        format_fingerprint(previous)
+                    );
+                    config.save(config_dir)?;
                 }
+                KeyPinStatus::Matched { .. } => {}
             }
+            Ok(status.fingerprint().to_string())
+        }
+        Err(e) => {
+            error!("Refusing to upload: {}", e);
+            exit(-8);
+        }
+    }
+}
 
-            Ok(())
-        })
+fn create_cipher(cli_connect_result: &CatchConnectCLIResponse) -> FileCipher {
+    let scheme = match cli_connect_result.encryption_scheme.supported() {
+        Ok(scheme) => scheme,
+        Err(e) => {
+            error!("{}", e);
+            exit(-4);
+        }
+    };
+    info!(":🔐 Using {:?} content encryption", scheme);
+    let cipher = FileCipher::generate(scheme);
+    if cli_connect_result.envelope_version != Some(ENVELOPE_VERSION) {
+        return cipher;
+    }
+    match public_key_fingerprint(&cli_connect_result.public_key) {
+        Ok(key_id) => cipher.with_envelope(key_id),
+        Err(e) => {
+            error!("Invalid server public key: {}", e);
+            exit(-4);
+        }
+    }
+}
+
+async fn run_watch(scan_options: ScanOptions, repo_root: &Path) -> io::Result<()> {
+    let session_id = match handle_sessions(&std::env::temp_dir()) {
+        Ok(session_id) => session_id,
+        Err(CatchSessionError::NoSessionFound) => exit(-1),
+        Err(CatchSessionError::MultipleSessionsFound) => exit(-2),
+        Err(CatchSessionError::IoError(e)) => {
+            error!("An IO error occurred: {}", e);
+            exit(-3)
+        }
+    };
+
+    let state_dir = state_dir()?;
+    let environment = CatchApiClient::default().base_url().to_string();
+    let upload = match AuditLog::new(&state_dir).find_upload(&session_id)? {
+        Some(upload) if upload.environment == environment => upload,
+        _ => {
+            error!(
+                "Nothing uploaded to session {} yet. Run catch to upload your code first.",
+                session_id
+            );
+            exit(-13);
+        }
+    };
+
+    let cli_connect_result = match connect_cli_to_session(
+        session_id.clone(),
+        upload.org_name.clone(),
+        upload.repo_name.clone(),
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Failed to connect CLI to session: {}", e);
+            exit(-4);
+        }
+    };
+    if cli_connect_result.integration_id != upload.integration_id {
+        error!(
+            "Session {} is no longer attached to integration {}",
+            session_id, upload.integration_id
+        );
+        exit(-13);
+    }
+
+    let config_dir = config_dir()?;
+    let server_key_fingerprint = pin_server_key(&config_dir, &environment, &cli_connect_result)?;
+    let cipher = Arc::new(create_cipher(&cli_connect_result));
+    let sync = SessionSync::new(
+        UploadRecord {
+            server_key_fingerprint,
+            ..upload
+        },
+        cli_connect_result.public_key,
+        cipher,
+        load_or_create_signing_key(&config_dir)?,
+        repo_root,
+        &state_dir,
+        scan_options,
+    );
+    let sync = match sync {
+        Ok(sync) => sync,
+        Err(e) => {
+            error!("Can't watch session {}: {}", session_id, e);
+            exit(-13);
+        }
+    };
+
+    watch_session(sync).await?;
+    info!("Stopped watching session {}", session_id);
+    Ok(())
 }
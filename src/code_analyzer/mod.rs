@@ -5,19 +5,13 @@ use crate::api_client::session_status_entity::{
     CatchSessionExtractingCandidatesResult, CatchSessionStatusResponse,
 };
 use crate::api_client::{CatchApiClient, CatchApiResponse};
-use crate::code_reader::CatchCLICodeFile;
 use std::io;
 
 pub async fn request_rcp(
     integration_id: String,
     session_id: String,
-    code_files: Vec<CatchCLICodeFile>,
+    file_paths: Vec<String>,
 ) -> io::Result<CatchApiResponse<()>> {
-    let file_paths: Vec<String> = code_files
-        .into_iter()
-        .map(|file| file.path.clone())
-        .collect();
-
     let api_client = CatchApiClient::default();
 
     let response = api_client
@@ -33,9 +27,9 @@ pub async fn request_rcp(
     match response {
         Ok(rst) => match rst {
             CatchApiResponse::NoContent => Ok(CatchApiResponse::NoContent),
-            _ => Err(io::Error::new(io::ErrorKind::Other, "Invalid response")),
+            _ => Err(io::Error::other("Invalid response")),
         },
-        Err(e) => Err(io::Error::new(io::ErrorKind::Other, format!("{:?}", e))),
+        Err(e) => Err(io::Error::other(format!("{:?}", e))),
     }
 }
 
@@ -52,19 +46,17 @@ pub async fn check_rcp_status(
         Ok(rst) => match rst {
             CatchApiResponse::Success(response) => {
                 if let Some(output) = response.process.output {
-                    Ok(output.extracting_candidates)
+                    output.extracting_candidates.ok_or_else(|| {
+                        io::Error::other("Candidate extraction has not been reported yet")
+                    })
                 } else {
-                    Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("{:?}", "Invalid response"),
-                    ))
+                    Err(io::Error::other(format!("{:?}", "Invalid response")))
                 }
             }
-            CatchApiResponse::NoContent => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("{:?}", "Invalid response"),
-            )),
+            CatchApiResponse::NoContent => {
+                Err(io::Error::other(format!("{:?}", "Invalid response")))
+            }
         },
-        Err(e) => Err(io::Error::new(io::ErrorKind::Other, format!("{:?}", e))),
+        Err(e) => Err(io::Error::other(format!("{:?}", e))),
     }
 }
@@ -1,7 +1,8 @@
-use crate::api_client::session_status_entity::CatchSessionExtractingCandidatesResult;
+use crate::api_client::session_status_entity::{
+    CatchSessionExtractingCandidatesResult, StepStatus,
+};
 use crate::api_client::CatchApiResponse;
 use crate::code_analyzer::{check_rcp_status, request_rcp};
-use crate::code_reader::CatchCLICodeFile;
 use crate::terminal::finalize_terminal;
 use log::{error, info, warn};
 use ratatui::backend::CrosstermBackend;
@@ -30,7 +31,7 @@ impl CodeCandidateUiState {
 pub async fn request_code_candidates(
     integration_id: String,
     session_id: String,
-    code_files: Vec<CatchCLICodeFile>,
+    file_paths: Vec<String>,
 ) -> io::Result<CatchSessionExtractingCandidatesResult> {
     enable_raw_mode()?;
 
@@ -50,7 +51,7 @@ pub async fn request_code_candidates(
     let (_, row) = position()?;
     let area = Rect::new(0, row, terminal_size.width, 3);
 
-    let mut api_future = tokio::spawn(request_rcp(integration_id, session_id.clone(), code_files));
+    let mut api_future = tokio::spawn(request_rcp(integration_id, session_id.clone(), file_paths));
 
     loop {
         terminal.draw(|f| {
@@ -89,19 +90,19 @@ pub async fn request_code_candidates(
                                         break;
                                     }
                                     _ => {
-                                        return Err(io::Error::new(io::ErrorKind::Other, "Invalid response"));
+                                        return Err(io::Error::other("Invalid response"));
                                     }
                                 }
                             }
                             None => {
                                 error!("Failed to get code candidates");
-                                return Err(io::Error::new(io::ErrorKind::Other, "Failed to get code candidates"));
+                                return Err(io::Error::other("Failed to get code candidates"));
                             }
                         }
                     }
                     Err(e) => {
                         info!("API request unsuccessful");
-                        return Err(io::Error::new(io::ErrorKind::Other, format!("API request failed: {}", e)));
+                        return Err(io::Error::other(format!("API request failed: {}", e)));
                     }
                 }
             }
@@ -113,21 +114,21 @@ pub async fn request_code_candidates(
     if api_result.is_some() {
         match check_rcp_status(session_id.clone()).await {
             Ok(result) => {
-                if result.status == "completed" {
+                if result.status == StepStatus::Completed {
                     println!(" {} - Completed", message.clone());
                     Ok(result)
                 } else {
                     println!(" {} - Failed", message.clone());
-                    Err(io::Error::new(io::ErrorKind::Other, "API request failed"))
+                    Err(io::Error::other("API request failed"))
                 }
             }
             Err(e) => {
                 println!(" {} - Failed", message.clone());
-                Err(io::Error::new(io::ErrorKind::Other, e))
+                Err(io::Error::other(e))
             }
         }
     } else {
         println!(" {} - Failed", message.clone());
-        Err(io::Error::new(io::ErrorKind::Other, "API request failed"))
+        Err(io::Error::other("API request failed"))
     }
 }
@@ -1,12 +1,14 @@
 use crate::code_candidate_selector::ui::CodeCandidateSelector;
-use crate::code_reader::CatchCLICodeFile;
+use crate::code_reader::IndexedFile;
 use std::io;
 
 mod ui;
 
-pub fn select_codes(code_files: Vec<CatchCLICodeFile>) -> io::Result<Vec<CatchCLICodeFile>> {
-    let code_files_clone = code_files.clone();
-    let selector = CodeCandidateSelector::new(code_files_clone);
+pub fn select_codes(
+    code_files: Vec<IndexedFile>,
+    max_total_size: u64,
+) -> io::Result<Vec<IndexedFile>> {
+    let selector = CodeCandidateSelector::new(&code_files, max_total_size);
 
     let terminal = ratatui::init();
 
@@ -19,10 +21,7 @@ pub fn select_codes(code_files: Vec<CatchCLICodeFile>) -> io::Result<Vec<CatchCL
     }
 }
 
-pub fn filter_code_files(
-    all_files: Vec<CatchCLICodeFile>,
-    paths: Vec<String>,
-) -> Vec<CatchCLICodeFile> {
+pub fn filter_code_files(all_files: Vec<IndexedFile>, paths: Vec<String>) -> Vec<IndexedFile> {
     let path_set: std::collections::HashSet<String> = paths.into_iter().collect();
 
     all_files
@@ -1,4 +1,5 @@
-use crate::code_reader::CatchCLICodeFile;
+use crate::code_reader::budget::format_size;
+use crate::code_reader::IndexedFile;
 use crate::terminal::finalize_terminal;
 use ratatui::{
     crossterm::event::{self, Event, KeyCode, KeyEventKind},
@@ -50,12 +51,19 @@ impl TableColors {
 struct Data {
     is_selected: String,
     file_name: String,
+    file_size: String,
     file_path: String,
+    size: u64,
 }
 
 impl Data {
-    const fn ref_array(&self) -> [&String; 3] {
-        [&self.is_selected, &self.file_name, &self.file_path]
+    const fn ref_array(&self) -> [&String; 4] {
+        [
+            &self.is_selected,
+            &self.file_name,
+            &self.file_size,
+            &self.file_path,
+        ]
     }
 
     fn is_sel(&self) -> &str {
@@ -66,6 +74,10 @@ impl Data {
         &self.file_name
     }
 
+    fn size(&self) -> &str {
+        &self.file_size
+    }
+
     fn path(&self) -> &str {
         &self.file_path
     }
@@ -74,19 +86,22 @@ impl Data {
 pub struct CodeCandidateSelector {
     state: TableState,
     items: Vec<Data>,
-    longest_item_lens: (u16, u16, u16),
+    longest_item_lens: (u16, u16, u16, u16),
+    max_total_size: u64,
     scroll_state: ScrollbarState,
     colors: TableColors,
 }
 
 impl CodeCandidateSelector {
-    pub fn new(candidate_files: Vec<CatchCLICodeFile>) -> Self {
+    pub fn new(candidate_files: &[IndexedFile], max_total_size: u64) -> Self {
         let data_vec: Vec<Data> = candidate_files
-            .into_iter()
+            .iter()
             .map(|file| Data {
                 is_selected: "+".to_string(),
                 file_name: extract_filename(file.path.as_str()).to_string(),
+                file_size: format_size(file.size),
                 file_path: file.path.to_string(),
+                size: file.size,
             })
             .collect();
 
@@ -96,9 +111,18 @@ impl CodeCandidateSelector {
             scroll_state: ScrollbarState::new((data_vec.len() - 1) * ITEM_HEIGHT),
             colors: TableColors::new(&tailwind::NEUTRAL),
             items: data_vec,
+            max_total_size,
         }
     }
 
+    fn selected_size(&self) -> u64 {
+        self.items
+            .iter()
+            .filter(|data| data.is_selected == "+")
+            .map(|data| data.size)
+            .sum()
+    }
+
     fn select(&mut self) {
         let i = self.state.selected().unwrap_or(0);
         let is_selected = match self.items[i].is_selected.as_str() {
@@ -145,6 +169,7 @@ impl CodeCandidateSelector {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     match key.code {
+                        KeyCode::Enter if self.selected_size() > self.max_total_size => {}
                         KeyCode::Enter => {
                             finalize_terminal(&mut terminal)?;
                             return Ok(self
@@ -165,7 +190,7 @@ impl CodeCandidateSelector {
     }
 
     fn draw(&mut self, frame: &mut Frame) {
-        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(3)]);
+        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(4)]);
         let rects = vertical.split(frame.area());
 
         self.render_table(frame, rects[0]);
@@ -181,7 +206,7 @@ impl CodeCandidateSelector {
             .add_modifier(Modifier::REVERSED)
             .fg(self.colors.selected_style_fg);
 
-        let header = ["", "File Name", "File Path"]
+        let header = ["", "File Name", "Size", "File Path"]
             .into_iter()
             .map(Cell::from)
             .collect::<Row>()
@@ -206,7 +231,8 @@ impl CodeCandidateSelector {
                 // + 1 is for padding.
                 Constraint::Length(self.longest_item_lens.0 + 2),
                 Constraint::Min(self.longest_item_lens.1 + 1),
-                Constraint::Min(self.longest_item_lens.2 + 1),
+                Constraint::Length(self.longest_item_lens.2 + 1),
+                Constraint::Min(self.longest_item_lens.3 + 1),
             ],
         )
         .header(header)
@@ -237,7 +263,20 @@ impl CodeCandidateSelector {
     }
 
     fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let info_footer = Paragraph::new(Line::from(INFO_TEXT))
+        let selected_size = self.selected_size();
+        let budget_text = format!(
+            "Selected {} of {} budget",
+            format_size(selected_size),
+            format_size(self.max_total_size)
+        );
+        let budget_line = if selected_size > self.max_total_size {
+            Line::from(format!("{} - unselect files to continue", budget_text))
+                .fg(tailwind::RED.c400)
+        } else {
+            Line::from(budget_text)
+        };
+
+        let info_footer = Paragraph::new(vec![Line::from(INFO_TEXT), budget_line])
             .style(
                 Style::new()
                     .fg(self.colors.row_fg)
@@ -253,7 +292,7 @@ impl CodeCandidateSelector {
     }
 }
 
-fn constraint_len_calculator(items: &[Data]) -> (u16, u16, u16) {
+fn constraint_len_calculator(items: &[Data]) -> (u16, u16, u16, u16) {
     let selected_len = items
         .iter()
         .map(Data::is_sel)
@@ -266,6 +305,12 @@ fn constraint_len_calculator(items: &[Data]) -> (u16, u16, u16) {
         .map(UnicodeWidthStr::width)
         .max()
         .unwrap_or(0);
+    let size_len = items
+        .iter()
+        .map(Data::size)
+        .map(UnicodeWidthStr::width)
+        .max()
+        .unwrap_or(0);
     let path_len = items
         .iter()
         .map(Data::path)
@@ -275,7 +320,12 @@ fn constraint_len_calculator(items: &[Data]) -> (u16, u16, u16) {
         .unwrap_or(0);
 
     #[allow(clippy::cast_possible_truncation)]
-    (selected_len as u16, name_len as u16, path_len as u16)
+    (
+        selected_len as u16,
+        name_len as u16,
+        size_len as u16,
+        path_len as u16,
+    )
 }
 
 fn extract_filename(path: &str) -> &str {
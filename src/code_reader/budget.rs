@@ -0,0 +1,51 @@
+const KIB: u64 = 1024;
+const MIB: u64 = 1024 * KIB;
+
+/// Size caps applied while scanning, in bytes of plaintext.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeLimits {
+    pub max_file_size: u64,
+    pub max_total_size: u64,
+}
+
+impl Default for SizeLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: MIB,
+            max_total_size: 20 * MIB,
+        }
+    }
+}
+
+/// Formats a byte count for people, e.g. `512 B`, `3.4 KiB` or `20.0 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    if bytes < KIB {
+        format!("{} B", bytes)
+    } else if bytes < MIB {
+        format!("{:.1} KiB", bytes as f64 / KIB as f64)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    }
+}
+
+/// Parses sizes such as `500000`, `512K`, `512KiB` or `2M`; suffixes are binary.
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("Invalid size: {}", value))?;
+
+    let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => KIB,
+        "M" | "MB" | "MIB" => MIB,
+        _ => return Err(format!("Invalid size unit: {}", unit)),
+    };
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("Size is too large: {}", value))
+}
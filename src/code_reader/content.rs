@@ -0,0 +1,188 @@
+use crate::code_reader::budget::format_size;
+use crate::code_reader::encoding::{
+    decode_text, detect_utf16, normalize_line_endings, DecodedText, LineEnding,
+};
+use crate::code_reader::walker::PruneReason;
+use encoding_rs::UTF_8;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fmt;
+use std::io::Cursor;
+use zeroize::Zeroizing;
+
+const BINARY_PLIST_MAGIC: &[u8] = b"bplist00";
+
+/// How much of a file is inspected when deciding whether it is binary, same as git.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Banners code generators leave in a comment at the top of their output, by name. Each must
+/// start a comment line, so code or prose that merely mentions them doesn't count.
+static GENERATED_MARKERS: Lazy<Vec<(&'static str, Regex)>> = Lazy::new(|| {
+    [
+        (
+            "Code generated … DO NOT EDIT.",
+            r"^\s*(?://|#|/?\*|--)\s*Code generated .*DO NOT EDIT\.",
+        ),
+        ("@generated", r"^\s*(?://|#|/?\*+|<!--|--)\s*@generated\b"),
+        ("<auto-generated>", r"^\s*(?://|#|<!--)\s*<auto-generated"),
+        (
+            "This file is automatically generated",
+            r"^\s*(?://|#|/?\*|<!--|--)\s*This file (?:is|was|has been) (?:automatically|auto-)generated",
+        ),
+    ]
+    .into_iter()
+    .map(|(name, pattern)| (name, Regex::new(pattern).unwrap()))
+    .collect()
+});
+/// Banners are only looked for in the first lines, within the first kilobyte.
+const GENERATED_MARKER_LINES: usize = 5;
+const GENERATED_MARKER_SEARCH_LEN: usize = 1024;
+
+/// How much of each file indexing reads to recognize generated and minified files before
+/// they're offered for selection; enough for the banner search and a fair line length average.
+pub const GENERATED_SNIFF_LEN: usize = 8 * 1024;
+
+/// Files this long whose lines average more than [`MINIFIED_AVERAGE_LINE_LEN`] are minified.
+const MINIFIED_MIN_LEN: usize = 2048;
+const MINIFIED_AVERAGE_LINE_LEN: usize = 500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    Binary,
+    InvalidBinaryPlist(String),
+    Unreadable(String),
+    /// Excluded because of this many likely secrets.
+    ContainsSecrets(usize),
+    TooLarge {
+        size: u64,
+        limit: u64,
+    },
+    OverTotalBudget {
+        limit: u64,
+    },
+    /// Carries the marker that identified the file as generated.
+    Generated(&'static str),
+    Minified,
+    /// A link, or a file under one, that [`ScanOptions::symlinks`] leaves out.
+    ///
+    /// [`ScanOptions::symlinks`]: crate::code_reader::ScanOptions::symlinks
+    Symlink(PruneReason),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Binary => write!(f, "binary file"),
+            SkipReason::InvalidBinaryPlist(e) => write!(f, "unreadable binary plist: {}", e),
+            SkipReason::Unreadable(e) => write!(f, "could not read file: {}", e),
+            SkipReason::ContainsSecrets(count) => {
+                write!(f, "contains {} likely secret(s)", count)
+            }
+            SkipReason::TooLarge { size, limit } => write!(
+                f,
+                "file is {}, over the per-file limit of {}",
+                format_size(*size),
+                format_size(*limit)
+            ),
+            SkipReason::OverTotalBudget { limit } => write!(
+                f,
+                "total size budget of {} already used up",
+                format_size(*limit)
+            ),
+            SkipReason::Generated(marker) => write!(f, "generated file (\"{}\")", marker),
+            SkipReason::Minified => write!(f, "minified file"),
+            SkipReason::Symlink(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: SkipReason,
+}
+
+/// UTF-8 plaintext ready to be encrypted, and how it was stored on disk.
+pub struct PreparedContent {
+    pub text: Zeroizing<Vec<u8>>,
+    pub encoding: &'static str,
+    pub bom: bool,
+    pub lossy: bool,
+    pub original_line_ending: Option<LineEnding>,
+}
+
+/// Turns raw file bytes into the plaintext that gets uploaded.
+///
+/// Binary plists are converted to their XML form so they can be read like any other plist,
+/// and legacy encodings are transcoded to UTF-8; anything else that looks binary is rejected.
+pub fn prepare_content(
+    bytes: Zeroizing<Vec<u8>>,
+    normalize: bool,
+) -> Result<PreparedContent, SkipReason> {
+    let mut decoded = if bytes.starts_with(BINARY_PLIST_MAGIC) {
+        DecodedText {
+            text: binary_plist_to_xml(&bytes)?,
+            encoding: UTF_8,
+            bom: false,
+            lossy: false,
+        }
+    } else if detect_utf16(&bytes).is_none() && is_binary(&bytes) {
+        return Err(SkipReason::Binary);
+    } else {
+        decode_text(bytes)
+    };
+
+    let original_line_ending = if normalize {
+        normalize_line_endings(&mut decoded.text)
+    } else {
+        None
+    };
+
+    Ok(PreparedContent {
+        text: decoded.text,
+        encoding: decoded.encoding.name(),
+        bom: decoded.bom,
+        lossy: decoded.lossy,
+        original_line_ending,
+    })
+}
+
+/// Recognizes generated and minified files, which are large and not worth analyzing. `text`
+/// may be the first [`GENERATED_SNIFF_LEN`] bytes only, in which case the average line length
+/// is that of those bytes.
+pub fn detect_generated(path: &str, text: &[u8]) -> Option<SkipReason> {
+    let head = String::from_utf8_lossy(&text[..text.len().min(GENERATED_MARKER_SEARCH_LEN)]);
+    let marker = head.lines().take(GENERATED_MARKER_LINES).find_map(|line| {
+        GENERATED_MARKERS
+            .iter()
+            .find(|(_, regex)| regex.is_match(line))
+            .map(|(name, _)| *name)
+    });
+    if let Some(marker) = marker {
+        return Some(SkipReason::Generated(marker));
+    }
+
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let lines = text.iter().filter(|&&b| b == b'\n').count() + 1;
+    if file_name.contains(".min.")
+        || (text.len() >= MINIFIED_MIN_LEN && text.len() / lines > MINIFIED_AVERAGE_LINE_LEN)
+    {
+        return Some(SkipReason::Minified);
+    }
+    None
+}
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+fn binary_plist_to_xml(bytes: &[u8]) -> Result<Zeroizing<Vec<u8>>, SkipReason> {
+    let value = plist::Value::from_reader(Cursor::new(bytes))
+        .map_err(|e| SkipReason::InvalidBinaryPlist(e.to_string()))?;
+
+    let mut xml = Zeroizing::new(Vec::new());
+    value
+        .to_writer_xml(&mut *xml)
+        .map_err(|e| SkipReason::InvalidBinaryPlist(e.to_string()))?;
+    Ok(xml)
+}
@@ -0,0 +1,106 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    /// Both CRLF and bare LF line endings; normalizing them loses which line had which.
+    Mixed,
+}
+
+/// Text transcoded to UTF-8, along with what's needed to write it back the way it was.
+pub struct DecodedText {
+    pub text: Zeroizing<Vec<u8>>,
+    pub encoding: &'static Encoding,
+    pub bom: bool,
+    /// Set when bytes that aren't valid in `encoding` were replaced with U+FFFD.
+    pub lossy: bool,
+}
+
+/// Detects UTF-16 from a byte order mark, or from the zero bytes ASCII-heavy UTF-16 text
+/// leaves in every other position. Returns the encoding and the length of the BOM.
+pub fn detect_utf16(bytes: &[u8]) -> Option<(&'static Encoding, usize)> {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        return (encoding != UTF_8).then_some((encoding, bom_len));
+    }
+    if bytes.len() < 2 || bytes.len() % 2 != 0 {
+        return None;
+    }
+
+    let pairs = bytes.len() / 2;
+    let even_zeros = bytes.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd_zeros = bytes.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    if even_zeros == 0 && odd_zeros * 2 >= pairs {
+        Some((UTF_16LE, 0))
+    } else if odd_zeros == 0 && even_zeros * 2 >= pairs {
+        Some((UTF_16BE, 0))
+    } else {
+        None
+    }
+}
+
+/// Transcodes `bytes` to UTF-8. UTF-8 (with or without a BOM) is passed through; anything
+/// else is decoded with the encoding `chardetng` guesses for it, e.g. EUC-KR or Shift_JIS.
+pub fn decode_text(bytes: Zeroizing<Vec<u8>>) -> DecodedText {
+    if let Some((encoding, bom_len)) = detect_utf16(&bytes) {
+        return decode_with(encoding, &bytes[bom_len..], bom_len > 0);
+    }
+    if let Some(stripped) = bytes.strip_prefix(b"\xef\xbb\xbf") {
+        return DecodedText {
+            text: Zeroizing::new(stripped.to_vec()),
+            encoding: UTF_8,
+            bom: true,
+            lossy: false,
+        };
+    }
+    if std::str::from_utf8(&bytes).is_ok() {
+        return DecodedText {
+            text: bytes,
+            encoding: UTF_8,
+            bom: false,
+            lossy: false,
+        };
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(&bytes, true);
+    decode_with(detector.guess(None, true), &bytes, false)
+}
+
+fn decode_with(encoding: &'static Encoding, bytes: &[u8], bom: bool) -> DecodedText {
+    let (text, lossy) = encoding.decode_without_bom_handling(bytes);
+    DecodedText {
+        text: Zeroizing::new(text.into_owned().into_bytes()),
+        encoding,
+        bom,
+        lossy,
+    }
+}
+
+/// Rewrites CRLF line endings to LF, returning the original style when anything changed:
+/// [`LineEnding::Mixed`] when some lines already ended in a bare LF.
+pub fn normalize_line_endings(text: &mut Zeroizing<Vec<u8>>) -> Option<LineEnding> {
+    let line_feeds = text.iter().filter(|&&byte| byte == b'\n').count();
+    let crlfs = text.windows(2).filter(|pair| *pair == b"\r\n").count();
+    if crlfs == 0 {
+        return None;
+    }
+
+    let mut normalized = Zeroizing::new(Vec::with_capacity(text.len()));
+    let mut bytes = text.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        if byte == b'\r' && bytes.peek() == Some(&&b'\n') {
+            continue;
+        }
+        normalized.push(byte);
+    }
+    *text = normalized;
+    match crlfs == line_feeds {
+        true => Some(LineEnding::Crlf),
+        false => Some(LineEnding::Mixed),
+    }
+}
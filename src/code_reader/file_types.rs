@@ -0,0 +1,102 @@
+/// Globs, relative to the scanned directory, for the files one platform's code lives in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlatformFileTypes {
+    pub platform: String,
+    pub patterns: Vec<String>,
+}
+
+/// The file types a scan picks up, grouped by platform.
+///
+/// [`FileTypeRegistry::default`] covers every platform Catch supports out of the box; more can
+/// be added with [`FileTypeRegistry::register`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTypeRegistry {
+    platforms: Vec<PlatformFileTypes>,
+}
+
+const BUILT_IN_FILE_TYPES: &[(&str, &[&str])] = &[
+    (
+        "android",
+        &[
+            "**/*.java",
+            "**/*.kt",
+            "**/*.kts",
+            "**/*.gradle",
+            "**/*.toml",
+            "**/AndroidManifest.xml",
+            "**/res/**/*.xml",
+        ],
+    ),
+    (
+        "ios",
+        &[
+            "**/*.swift",
+            "**/*.m",
+            "**/*.mm",
+            "**/Podfile",
+            "**/*.entitlements",
+            "**/*.plist",
+            "**/*.xcprivacy",
+        ],
+    ),
+    ("native", &["**/*.h", "**/*.hpp"]),
+    (
+        "react-native",
+        &[
+            "**/*.js",
+            "**/*.jsx",
+            "**/*.ts",
+            "**/*.tsx",
+            "**/app.json",
+            "**/package.json",
+        ],
+    ),
+    ("flutter", &["**/*.dart", "**/pubspec.yaml"]),
+    ("unity", &["**/*.cs"]),
+    ("python", &["**/*.py"]),
+    ("go", &["**/*.go", "**/go.mod"]),
+    ("rust", &["**/*.rs"]),
+];
+
+impl Default for FileTypeRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for (platform, patterns) in BUILT_IN_FILE_TYPES {
+            registry.register(platform, patterns.iter().map(|p| p.to_string()));
+        }
+        registry
+    }
+}
+
+impl FileTypeRegistry {
+    pub fn empty() -> Self {
+        Self {
+            platforms: Vec::new(),
+        }
+    }
+
+    /// Adds `patterns` to `platform`, creating the platform if it isn't registered yet.
+    pub fn register(&mut self, platform: &str, patterns: impl IntoIterator<Item = String>) {
+        let index = match self.platforms.iter().position(|p| p.platform == platform) {
+            Some(index) => index,
+            None => {
+                self.platforms.push(PlatformFileTypes {
+                    platform: platform.to_string(),
+                    patterns: Vec::new(),
+                });
+                self.platforms.len() - 1
+            }
+        };
+
+        let registered = &mut self.platforms[index].patterns;
+        for pattern in patterns {
+            if !registered.contains(&pattern) {
+                registered.push(pattern);
+            }
+        }
+    }
+
+    pub fn platforms(&self) -> &[PlatformFileTypes] {
+        &self.platforms
+    }
+}
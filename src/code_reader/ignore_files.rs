@@ -0,0 +1,188 @@
+use crate::code_reader::rules::CATCHIGNORE_FILE_NAME;
+use crate::git_info::repository::{GitRepository, GitSnapshot};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::warn;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Ignore files read in every directory, highest precedence first, as the `ignore` crate
+/// orders them.
+const WORKING_TREE_FILE_NAMES: &[&str] = &[CATCHIGNORE_FILE_NAME, ".ignore", ".gitignore"];
+
+type ReadIgnoreFile<'a> = Box<dyn Fn(&Path) -> io::Result<Option<Vec<u8>>> + Send + Sync + 'a>;
+
+/// The gitignore-style files that apply to a scan, read lazily one directory at a time.
+///
+/// Paths are matched the way the `ignore` crate's walker matches them: for each kind of file,
+/// the deepest one with a matching pattern decides, and a kind only applies when no
+/// higher-precedence kind matched. Repository-wide files, `.git/info/exclude` and the global
+/// git excludes file, come last. Unlike the walker, the caller learns about every match, so
+/// ignored entries can be reported.
+pub struct IgnoreFiles<'a> {
+    /// Outermost directory whose ignore files apply: the repository root, or the scanned
+    /// directory outside repositories.
+    top_dir: PathBuf,
+    file_names: &'static [&'static str],
+    read: ReadIgnoreFile<'a>,
+    repository: Vec<Gitignore>,
+    directories: Mutex<HashMap<PathBuf, Arc<Vec<Gitignore>>>>,
+    /// Content of the `.catchignore` files read so far, by path.
+    catchignore_files: Mutex<BTreeMap<PathBuf, String>>,
+}
+
+impl IgnoreFiles<'static> {
+    /// `.catchignore`, `.ignore` and `.gitignore` files on disk, from the root of the
+    /// repository containing `base_dir` down, plus the repository-wide files.
+    pub fn working_tree(base_dir: &Path) -> Self {
+        let mut repository = Vec::new();
+        let top_dir = match GitRepository::open(base_dir) {
+            Ok(git) => {
+                let mut exclude = GitignoreBuilder::new(&git.work_dir);
+                if let Some(e) = exclude.add(git.common_dir.join("info").join("exclude")) {
+                    warn_unless_not_found(&e);
+                }
+                repository.extend(exclude.build().ok());
+                git.work_dir
+            }
+            Err(_) => base_dir.to_path_buf(),
+        };
+        repository.push(Gitignore::global().0);
+
+        Self {
+            top_dir,
+            file_names: WORKING_TREE_FILE_NAMES,
+            read: Box::new(|path| match fs::read(path) {
+                Ok(content) => Ok(Some(content)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e),
+            }),
+            repository,
+            directories: Mutex::new(HashMap::new()),
+            catchignore_files: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl<'a> IgnoreFiles<'a> {
+    /// The `.catchignore` files committed in `snapshot`, a revision of the repository whose
+    /// working tree is `work_dir`. Paths are matched as if the revision were checked out
+    /// there. Other ignore files only concern untracked files, so they don't apply.
+    pub fn snapshot(work_dir: &Path, snapshot: &'a GitSnapshot) -> Self {
+        let work_dir = work_dir.to_path_buf();
+        Self {
+            top_dir: work_dir.clone(),
+            file_names: &[CATCHIGNORE_FILE_NAME],
+            read: Box::new(move |path| {
+                let Some(repository_path) = path
+                    .strip_prefix(&work_dir)
+                    .ok()
+                    .and_then(|path| path.to_str())
+                else {
+                    return Ok(None);
+                };
+                let repository_path = repository_path.replace('\\', "/");
+                match snapshot.files.contains_key(&repository_path) {
+                    true => Ok(Some(snapshot.read(&repository_path)?)),
+                    false => Ok(None),
+                }
+            }),
+            repository: Vec::new(),
+            directories: Mutex::new(HashMap::new()),
+            catchignore_files: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl IgnoreFiles<'_> {
+    /// Whether `path`, an absolute path under the scanned directory, is ignored. Its parent
+    /// directories are assumed not to be.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let directories: Vec<Arc<Vec<Gitignore>>> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|ancestor| ancestor.starts_with(&self.top_dir))
+            .map(|directory| self.matchers(directory))
+            .collect();
+
+        for kind in 0..self.file_names.len() {
+            for matchers in &directories {
+                let matched = matchers[kind].matched(path, is_dir);
+                if !matched.is_none() {
+                    return matched.is_ignore();
+                }
+            }
+        }
+        for matcher in &self.repository {
+            let matched = matcher.matched(path, is_dir);
+            if !matched.is_none() {
+                return matched.is_ignore();
+            }
+        }
+        false
+    }
+
+    /// The `.catchignore` files met so far, with their content: those of every directory a
+    /// path was matched in.
+    pub fn catchignore_files(&self) -> BTreeMap<PathBuf, String> {
+        self.catchignore_files.lock().unwrap().clone()
+    }
+
+    fn matchers(&self, directory: &Path) -> Arc<Vec<Gitignore>> {
+        if let Some(matchers) = self.directories.lock().unwrap().get(directory) {
+            return Arc::clone(matchers);
+        }
+
+        let matchers: Arc<Vec<Gitignore>> = Arc::new(
+            self.file_names
+                .iter()
+                .map(|file_name| self.load(directory, &directory.join(file_name)))
+                .collect(),
+        );
+        self.directories
+            .lock()
+            .unwrap()
+            .insert(directory.to_path_buf(), Arc::clone(&matchers));
+        matchers
+    }
+
+    fn load(&self, directory: &Path, path: &Path) -> Gitignore {
+        let content = match (self.read)(path) {
+            Ok(Some(content)) => content,
+            Ok(None) => return Gitignore::empty(),
+            Err(e) => {
+                warn!("Failed to read {}: {}", path.display(), e);
+                return Gitignore::empty();
+            }
+        };
+        let content = String::from_utf8_lossy(&content);
+        if path.file_name() == Some(CATCHIGNORE_FILE_NAME.as_ref()) {
+            self.catchignore_files
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), content.to_string());
+        }
+
+        let mut builder = GitignoreBuilder::new(directory);
+        for line in content.lines() {
+            if let Err(e) = builder.add_line(Some(path.to_path_buf()), line) {
+                warn!("Ignoring invalid pattern in {}: {}", path.display(), e);
+            }
+        }
+        builder.build().unwrap_or_else(|e| {
+            warn!("Failed to read {}: {}", path.display(), e);
+            Gitignore::empty()
+        })
+    }
+}
+
+fn warn_unless_not_found(error: &ignore::Error) {
+    if !error
+        .io_error()
+        .is_some_and(|e| e.kind() == io::ErrorKind::NotFound)
+    {
+        warn!("Failed to read git excludes: {}", error);
+    }
+}
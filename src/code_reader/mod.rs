@@ -1,96 +1,549 @@
-use crate::cryptography::encrypt_aes_256;
+pub mod budget;
+pub mod content;
+pub mod encoding;
+pub mod file_types;
+pub mod ignore_files;
+pub mod progress;
+pub mod rules;
+pub mod source;
+pub mod ui;
+pub mod walker;
+
+use crate::code_reader::budget::SizeLimits;
+use crate::code_reader::content::{
+    detect_generated, prepare_content, PreparedContent, SkipReason, SkippedFile,
+    GENERATED_SNIFF_LEN,
+};
+use crate::code_reader::encoding::LineEnding;
+use crate::code_reader::file_types::FileTypeRegistry;
+use crate::code_reader::progress::ScanProgress;
+use crate::code_reader::rules::{Rule, ScanRules};
+use crate::code_reader::source::{ContentSource, FileSource};
+use crate::code_reader::walker::{PruneReason, PrunedEntry, SymlinkPolicy};
+use crate::cryptography::FileCipher;
+use crate::secret_scanner::{self, SecretDecision, SecretFinding, SecretPolicy, SecretReport};
 use base64::engine::general_purpose;
 use base64::Engine;
+use globset::{GlobBuilder, GlobSetBuilder};
+use rayon::prelude::*;
+use rsa::sha2::{Digest, Sha256};
 use serde::Serialize;
-use std::future::Future;
+use std::mem;
+use std::num::NonZeroUsize;
 use std::path::Path;
-use std::pin::Pin;
-use tokio::fs;
+use std::sync::Arc;
 use tokio::io;
+use zeroize::Zeroizing;
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct CodeFileMetadata {
+    /// Size of the plaintext in bytes.
+    pub size: u64,
+    /// Hex SHA-256 of the plaintext; only sent as part of the signed upload manifest.
+    #[serde(skip)]
+    pub plaintext_sha256: String,
+    /// Encoding the file is stored in on disk; the uploaded plaintext is always UTF-8.
+    pub encoding: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bom: bool,
+    /// Set when bytes that aren't valid in `encoding` were replaced with U+FFFD, so the
+    /// uploaded text differs from the file.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub lossy: bool,
+    /// Set when CRLF line endings were normalized to LF before upload.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_line_ending: Option<LineEnding>,
+}
 
 #[derive(Serialize, Debug, Clone)]
 pub struct CatchCLICodeFile {
     pub path: String,
     pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    pub metadata: CodeFileMetadata,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Convert CRLF line endings to LF before upload.
+    pub normalize_line_endings: bool,
+    /// File types scanned for each platform.
+    pub file_types: FileTypeRegistry,
+    /// Globs for files to scan on top of `file_types`.
+    pub includes: Vec<String>,
+    /// Gitignore-style patterns for files and directories to leave out.
+    pub excludes: Vec<String>,
+    /// What to do with files that look like they contain secrets.
+    pub secret_policy: SecretPolicy,
+    pub size_limits: SizeLimits,
+    pub symlinks: SymlinkPolicy,
+    /// Directories, relative to the scanned directory and not nested in each other, to limit
+    /// the scan to; the whole directory is scanned when empty.
+    pub roots: Vec<String>,
+    /// Whether files come from the working tree, the git index or a commit.
+    pub source: FileSource,
+    /// How many files are read and encrypted at once; defaults to the number of CPUs.
+    pub jobs: Option<NonZeroUsize>,
+    /// Updated as files are indexed and read.
+    pub progress: Arc<ScanProgress>,
+}
+
+impl ScanOptions {
+    pub fn rules(&self, base_dir: &Path) -> io::Result<ScanRules> {
+        ScanRules::new(base_dir, &self.file_types, &self.includes, &self.excludes)
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs
+            .or_else(|| std::thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get)
+    }
+}
+
+/// A supported file found while indexing; nothing but its location and size is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedFile {
+    /// Path relative to the indexed directory.
+    pub path: String,
+    /// Size on disk, or in the scanned revision, in bytes.
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FileIndex {
+    pub files: Vec<IndexedFile>,
+    pub skipped: Vec<SkippedFile>,
+    pub pruned: Vec<PrunedEntry>,
+    /// Rules of the `.catchignore` files that applied besides the scanned directory's own.
+    pub nested_catchignore_rules: Vec<Rule>,
+}
+
+impl FileIndex {
+    pub fn paths(&self) -> Vec<String> {
+        self.files.iter().map(|file| file.path.clone()).collect()
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanResult {
+    pub files: Vec<CatchCLICodeFile>,
+    pub skipped: Vec<SkippedFile>,
+    pub pruned: Vec<PrunedEntry>,
+    /// Files in which likely secrets were found, and what was done about them.
+    pub secrets: Vec<SecretReport>,
+    /// Plaintext bytes of all files in [`ScanResult::files`].
+    pub total_size: u64,
+}
+
+/// A file read and scanned for secrets, waiting for a decision on what to upload.
+struct LoadedFile {
+    path: String,
+    content: PreparedContent,
+    findings: Vec<SecretFinding>,
+}
+
+fn load_file(
+    source: &ContentSource,
+    relative_path: String,
+    normalize: bool,
+) -> Result<LoadedFile, SkippedFile> {
+    let skip = |reason| SkippedFile {
+        path: relative_path.clone(),
+        reason,
+    };
+    let bytes = source
+        .read(&relative_path)
+        .map_err(|e| skip(SkipReason::Unreadable(e.to_string())))?;
+    let content = prepare_content(Zeroizing::new(bytes), normalize).map_err(skip)?;
+    if let Some(reason) = detect_generated(&relative_path, &content.text) {
+        return Err(skip(reason));
+    }
+    let findings = std::str::from_utf8(&content.text)
+        .map(secret_scanner::scan)
+        .unwrap_or_default();
+
+    Ok(LoadedFile {
+        path: relative_path,
+        content,
+        findings,
+    })
 }
 
-fn is_whitelisted(file_name: &str) -> bool {
-    let whitelist = [
-        "*.js",
-        "*.ts",
-        "*.py",
-        "*.java",
-        "*.kt",
-        "*.swift",
-        "*.m",
-        "*.mm",
-        "*.gradle",
-        "*.kts",
-        "*.toml",
-        "AndroidManifest.xml",
-        "Podfile",
-        "*.entitlements",
-        "*.plist",
-        "*.xcprivacy",
-    ];
-
-    for &pattern in &whitelist {
-        if pattern.starts_with('*') {
-            let extension = pattern.strip_prefix('*').unwrap_or("");
-            if file_name.ends_with(extension) {
-                return true;
+/// Applies the secret policy and the total size budget to `file`. Returns the content to
+/// upload, or `None` when the file was skipped.
+fn accept_file(
+    options: &ScanOptions,
+    file: LoadedFile,
+    result: &mut ScanResult,
+) -> io::Result<Option<(String, PreparedContent)>> {
+    let LoadedFile {
+        path,
+        mut content,
+        findings,
+    } = file;
+
+    if !findings.is_empty() {
+        let decision = secret_scanner::decide(options.secret_policy, &path, &findings)?;
+        match decision {
+            SecretDecision::Exclude => result.skipped.push(SkippedFile {
+                path: path.clone(),
+                reason: SkipReason::ContainsSecrets(findings.len()),
+            }),
+            SecretDecision::Redact => {
+                // Only UTF-8 content is scanned, so this can't fail.
+                let text = std::str::from_utf8(&content.text).map_err(io::Error::other)?;
+                content.text = secret_scanner::redact(text, &findings);
             }
-        } else if file_name == pattern {
-            return true;
+            SecretDecision::Proceed => {}
+        }
+        result.secrets.push(SecretReport {
+            path: path.clone(),
+            findings,
+            decision,
+        });
+        if decision == SecretDecision::Exclude {
+            return Ok(None);
         }
     }
-    false
-}
-
-fn visit_dirs<'a>(
-    encryption_key: &'a [u8; 32],
-    iv: &'a [u8; 16],
-    base_dir: &'a Path,
-    dir: &'a Path,
-    result: &'a mut Vec<CatchCLICodeFile>,
-) -> Pin<Box<dyn Future<Output = io::Result<()>> + 'a>> {
-    Box::pin(async move {
-        let mut entries = fs::read_dir(dir).await?;
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            if path.is_dir() {
-                visit_dirs(encryption_key, iv, base_dir, &path, result).await?;
-            } else if let Some(file_name) = path.file_name() {
-                if let Some(file_name_str) = file_name.to_str() {
-                    if is_whitelisted(file_name_str) {
-                        let relative_path = path
-                            .strip_prefix(base_dir)
-                            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-                        let content = fs::read_to_string(&path).await?;
-                        let encrypted_content = general_purpose::STANDARD.encode(encrypt_aes_256(
-                            encryption_key,
-                            iv,
-                            &content,
-                        ));
-
-                        result.push(CatchCLICodeFile {
-                            path: relative_path.to_string_lossy().into_owned(),
-                            content: encrypted_content,
-                        });
-                    }
+
+    let limit = options.size_limits.max_total_size;
+    if result.total_size + content.text.len() as u64 > limit {
+        result.skipped.push(SkippedFile {
+            path,
+            reason: SkipReason::OverTotalBudget { limit },
+        });
+        return Ok(None);
+    }
+    result.total_size += content.text.len() as u64;
+    Ok(Some((path, content)))
+}
+
+fn seal_file(
+    cipher: &FileCipher,
+    path: String,
+    content: PreparedContent,
+) -> io::Result<CatchCLICodeFile> {
+    let encrypted = cipher
+        .seal(&path, &content.text)
+        .map_err(io::Error::other)?;
+
+    Ok(CatchCLICodeFile {
+        path,
+        content: general_purpose::STANDARD.encode(encrypted.ciphertext),
+        nonce: encrypted
+            .nonce
+            .map(|nonce| general_purpose::STANDARD.encode(nonce)),
+        metadata: CodeFileMetadata {
+            size: content.text.len() as u64,
+            plaintext_sha256: hex::encode(Sha256::digest(&content.text)),
+            encoding: content.encoding.to_string(),
+            bom: content.bom,
+            lossy: content.lossy,
+            original_line_ending: content.original_line_ending,
+        },
+    })
+}
+
+fn index(dir: &Path, options: &ScanOptions) -> io::Result<FileIndex> {
+    let rules = Arc::new(options.rules(dir)?);
+    options.progress.reset();
+
+    let mut index = FileIndex::default();
+    let candidates: Vec<(String, Result<u64, String>)> = match &options.source {
+        FileSource::Revision(rev) => {
+            let listing = source::list_revision(dir, &rules, options, rev)?;
+            index.pruned = listing.pruned;
+            index.nested_catchignore_rules = listing.nested_catchignore_rules;
+            listing
+                .files
+                .into_iter()
+                .map(|file| (file.path, file.size))
+                .collect()
+        }
+        FileSource::WorkingTree | FileSource::TrackedOnly => {
+            let walk_output = walker::walk(dir, Arc::clone(&rules), options);
+            index.nested_catchignore_rules = walk_output.nested_catchignore_rules;
+            index.pruned = walk_output
+                .pruned
+                .into_iter()
+                .filter(|entry| entry.is_dir || rules.is_included(Path::new(&entry.path)))
+                .collect();
+            let files = walk_output.files.into_iter().map(|file| {
+                let relative_path = file
+                    .path
+                    .strip_prefix(dir)
+                    .unwrap_or(&file.path)
+                    .to_string_lossy()
+                    .into_owned();
+                (relative_path, file.size)
+            });
+
+            match options.source {
+                FileSource::TrackedOnly => {
+                    let tracked = source::tracked_paths(dir)?;
+                    let (files, untracked): (Vec<_>, Vec<_>) =
+                        files.partition(|(path, _)| tracked.contains(path));
+                    index
+                        .pruned
+                        .extend(untracked.into_iter().map(|(path, _)| PrunedEntry {
+                            path,
+                            is_dir: false,
+                            reason: PruneReason::Untracked,
+                        }));
+                    index.pruned.sort_by(|a, b| a.path.cmp(&b.path));
+                    files
                 }
+                _ => files.collect(),
             }
         }
-        Ok(())
-    })
+    };
+
+    for (relative_path, size) in candidates {
+        let size = match size {
+            Ok(size) => size,
+            Err(e) => {
+                index.skipped.push(SkippedFile {
+                    path: relative_path,
+                    reason: SkipReason::Unreadable(e),
+                });
+                continue;
+            }
+        };
+        let limit = options.size_limits.max_file_size;
+        if size > limit {
+            index.skipped.push(SkippedFile {
+                path: relative_path,
+                reason: SkipReason::TooLarge { size, limit },
+            });
+            continue;
+        }
+
+        index.files.push(IndexedFile {
+            path: relative_path,
+            size,
+        });
+    }
+
+    // Generated and minified files are left out here rather than when they're read, so that
+    // they're neither offered for selection nor counted against the budget. Files that only
+    // turn out to be generated once decoded are still caught when they're read.
+    let source = ContentSource::open(dir, options)?;
+    let (files, generated): (Vec<_>, Vec<_>) = mem::take(&mut index.files)
+        .into_par_iter()
+        .map(|file| {
+            let reason = source
+                .read_head(&file.path, GENERATED_SNIFF_LEN)
+                .ok()
+                .and_then(|head| detect_generated(&file.path, &Zeroizing::new(head)));
+            (file, reason)
+        })
+        .partition(|(_, reason)| reason.is_none());
+    index.files = files.into_iter().map(|(file, _)| file).collect();
+    index
+        .skipped
+        .extend(generated.into_iter().filter_map(|(file, reason)| {
+            Some(SkippedFile {
+                path: file.path,
+                reason: reason?,
+            })
+        }));
+    Ok(index)
+}
+
+/// Lists every supported file under `dir` without reading any content.
+///
+/// Only files matching the include globs of `options` are listed. Directories matched by git
+/// ignore rules, `.catchignore`, exclude patterns or [`walker::DEFAULT_EXCLUDED_DIRS`] are
+/// not descended into and are listed in [`FileIndex::pruned`], as are links left out under
+/// [`ScanOptions::symlinks`] and files left out by [`ScanOptions::source`]; files over the
+/// per-file size limit, and generated and minified files, are listed in
+/// [`FileIndex::skipped`]. Only the first [`GENERATED_SNIFF_LEN`] bytes of each file are read
+/// to recognize the latter.
+///
+/// With [`FileSource::Revision`], files are listed from the commit instead of the working
+/// tree, and links in it are never followed.
+pub async fn index_files(dir: &Path, options: &ScanOptions) -> io::Result<FileIndex> {
+    let dir = dir.to_path_buf();
+    let options = options.clone();
+    tokio::task::spawn_blocking(move || index(&dir, &options))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Builds the thread pool the files of one [`read_files`] call are encrypted on. Its threads
+/// exit once the call is done with it.
+fn encrypt_pool(jobs: usize) -> io::Result<rayon::ThreadPool> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .thread_name(|index| format!("catch-encrypt-{}", index))
+        .build()
+        .map_err(io::Error::other)
+}
+
+/// Reads, transcodes to UTF-8 and encrypts `files`, [`ScanOptions::jobs`] at a time.
+///
+/// Only that many files' plaintext is held at once, but the encrypted content of every
+/// accepted file is kept in the returned [`ScanResult`] until it's uploaded. Peak memory
+/// therefore grows with the selection: up to the total size budget of
+/// [`ScanOptions::size_limits`], plus about a third for the base64-encoded ciphertext.
+///
+/// Files are read from [`ScanOptions::source`] on the blocking thread pool and encrypted on
+/// a dedicated thread pool; secret prompts and the size budget are handled in between, in
+/// the order of `files`.
+/// Files that can't be read or are binary, generated, minified or over the total size budget
+/// don't fail the scan; they're reported in [`ScanResult::skipped`] instead. Files with
+/// likely secrets are excluded, redacted or kept according to [`ScanOptions::secret_policy`].
+pub async fn read_files(
+    dir: &Path,
+    files: &[IndexedFile],
+    cipher: &Arc<FileCipher>,
+    options: &ScanOptions,
+) -> io::Result<ScanResult> {
+    let jobs = options.jobs();
+    let source = {
+        let dir = dir.to_path_buf();
+        let options = options.clone();
+        tokio::task::spawn_blocking(move || ContentSource::open(&dir, &options))
+            .await
+            .map_err(io::Error::other)??
+    };
+    let source = Arc::new(source);
+    let pool = encrypt_pool(jobs)?;
+    options.progress.reset();
+
+    let mut result = ScanResult::default();
+    for chunk in files.chunks(jobs) {
+        let reads: Vec<_> = chunk
+            .iter()
+            .map(|file| {
+                let source = Arc::clone(&source);
+                let relative_path = file.path.clone();
+                let normalize = options.normalize_line_endings;
+                let progress = Arc::clone(&options.progress);
+                tokio::task::spawn_blocking(move || {
+                    let loaded = load_file(&source, relative_path, normalize);
+                    if let Ok(file) = &loaded {
+                        progress.record(file.content.text.len() as u64);
+                    }
+                    loaded
+                })
+            })
+            .collect();
+
+        let mut accepted = Vec::new();
+        for read in reads {
+            match read.await.map_err(io::Error::other)? {
+                Ok(file) => accepted.extend(accept_file(options, file, &mut result)?),
+                Err(skipped) => result.skipped.push(skipped),
+            }
+        }
+
+        let cipher = Arc::clone(cipher);
+        let (sender, receiver) = flume::bounded(1);
+        pool.spawn(move || {
+            let sealed: io::Result<Vec<_>> = accepted
+                .into_par_iter()
+                .map(|(path, content)| seal_file(&cipher, path, content))
+                .collect();
+            _ = sender.send(sealed);
+        });
+        let sealed = receiver.recv_async().await.map_err(io::Error::other)??;
+        result.files.extend(sealed);
+    }
+    Ok(result)
 }
 
+/// Indexes `dir` and reads every file found; see [`index_files`] and [`read_files`].
 pub async fn find_and_read_files(
     dir: &Path,
-    encryption_key: &[u8; 32],
-    iv: &[u8; 16],
-) -> io::Result<Vec<CatchCLICodeFile>> {
-    let mut result = Vec::new();
-    visit_dirs(encryption_key, iv, dir, dir, &mut result).await?;
+    cipher: &Arc<FileCipher>,
+    options: &ScanOptions,
+) -> io::Result<ScanResult> {
+    let index = index_files(dir, options).await?;
+    let mut result = read_files(dir, &index.files, cipher, options).await?;
+
+    result.skipped.extend(index.skipped);
+    result.skipped.sort_by(|a, b| a.path.cmp(&b.path));
+    result.pruned = index.pruned;
     Ok(result)
 }
+
+/// Files read as is, for analysis that never leaves this machine in plaintext. They're read
+/// once, in one walk, and each analysis picks its own with [`PlaintextFiles::matching`].
+/// Contents are zeroed when the files are dropped.
+#[derive(Default)]
+pub struct PlaintextFiles {
+    /// Content by path relative to the scanned directory.
+    files: Vec<(String, Zeroizing<Vec<u8>>)>,
+}
+
+impl PlaintextFiles {
+    /// Indexes the files under `dir` matching `globs` and reads them.
+    ///
+    /// Only the exclude patterns, roots, symlink policy and [`ScanOptions::source`] of
+    /// `options` apply; the file types, include globs and size limits of `options` are
+    /// replaced by `globs` and `max_file_size`. Files that can't be read are left out.
+    pub async fn read(
+        dir: &Path,
+        globs: &[&str],
+        max_file_size: u64,
+        options: &ScanOptions,
+    ) -> io::Result<Self> {
+        let options = ScanOptions {
+            file_types: FileTypeRegistry::empty(),
+            includes: globs.iter().map(|glob| glob.to_string()).collect(),
+            size_limits: SizeLimits {
+                max_file_size,
+                max_total_size: u64::MAX,
+            },
+            progress: Arc::default(),
+            ..options.clone()
+        };
+        let index = index_files(dir, &options).await?;
+
+        let dir = dir.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let source = ContentSource::open(&dir, &options)?;
+            let files = index
+                .files
+                .into_iter()
+                .filter_map(|file| {
+                    let content = source.read(&file.path).ok()?;
+                    Some((file.path, Zeroizing::new(content)))
+                })
+                .collect();
+            Ok(Self { files })
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+
+    /// The files matching one of `globs`, in the syntax of include globs, that are no larger
+    /// than `max_file_size`.
+    pub fn matching(&self, globs: &[&str], max_file_size: u64) -> io::Result<Vec<(&str, &[u8])>> {
+        let mut builder = GlobSetBuilder::new();
+        for glob in globs {
+            builder.add(
+                GlobBuilder::new(glob)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+            );
+        }
+        let globs = builder
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        Ok(self
+            .files
+            .iter()
+            .filter(|(path, content)| content.len() as u64 <= max_file_size && globs.is_match(path))
+            .map(|(path, content)| (path.as_str(), content.as_slice()))
+            .collect())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::code_reader::budget::format_size;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Running totals of a scan, updated from the walker and reader threads.
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    files: AtomicUsize,
+    bytes: AtomicU64,
+}
+
+impl ScanProgress {
+    pub fn record(&self, size: u64) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(size, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.files.store(0, Ordering::Relaxed);
+        self.bytes.store(0, Ordering::Relaxed);
+    }
+
+    pub fn files(&self) -> usize {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Display for ScanProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "scanned {} files / {}",
+            self.files(),
+            format_size(self.bytes())
+        )
+    }
+}
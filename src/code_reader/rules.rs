@@ -0,0 +1,174 @@
+use crate::code_reader::file_types::FileTypeRegistry;
+use crate::code_reader::walker::DEFAULT_EXCLUDED_DIRS;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Repo-level ignore file in gitignore syntax, honored in every directory like `.gitignore`.
+pub const CATCHIGNORE_FILE_NAME: &str = ".catchignore";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleSource {
+    /// A file type of the named platform in the [`FileTypeRegistry`].
+    FileType(String),
+    CommandLine,
+    /// A `.catchignore` file, by path relative to the scanned directory.
+    CatchIgnore(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub pattern: String,
+    pub source: RuleSource,
+}
+
+/// Which files a scan picks up: files matching an include glob, minus anything excluded by
+/// `--exclude` patterns (gitignore syntax, so `secrets/` prunes the whole directory).
+#[derive(Debug)]
+pub struct ScanRules {
+    pub includes: Vec<Rule>,
+    pub excludes: Vec<Rule>,
+    include_set: GlobSet,
+    exclude_matcher: Gitignore,
+}
+
+impl ScanRules {
+    pub fn new(
+        base_dir: &Path,
+        file_types: &FileTypeRegistry,
+        includes: &[String],
+        excludes: &[String],
+    ) -> io::Result<Self> {
+        let includes: Vec<Rule> = file_types
+            .platforms()
+            .iter()
+            .flat_map(|platform| {
+                platform.patterns.iter().map(|pattern| Rule {
+                    pattern: pattern.clone(),
+                    source: RuleSource::FileType(platform.platform.clone()),
+                })
+            })
+            .chain(includes.iter().map(|pattern| Rule {
+                pattern: pattern.clone(),
+                source: RuleSource::CommandLine,
+            }))
+            .collect();
+
+        let mut include_set = GlobSetBuilder::new();
+        for rule in &includes {
+            let glob = GlobBuilder::new(&rule.pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            include_set.add(glob);
+        }
+
+        let mut exclude_matcher = GitignoreBuilder::new(base_dir);
+        for pattern in excludes {
+            exclude_matcher
+                .add_line(None, pattern)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        }
+
+        let catchignore = base_dir.join(CATCHIGNORE_FILE_NAME);
+        let catchignore_rules = match fs::read_to_string(&catchignore) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("Failed to read {}: {}", catchignore.display(), e),
+                ))
+            }
+        };
+        let excludes = excludes
+            .iter()
+            .map(|pattern| Rule {
+                pattern: pattern.clone(),
+                source: RuleSource::CommandLine,
+            })
+            .chain(catchignore_file_rules(
+                CATCHIGNORE_FILE_NAME,
+                &catchignore_rules,
+            ))
+            .collect();
+
+        Ok(Self {
+            includes,
+            excludes,
+            include_set: include_set
+                .build()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+            exclude_matcher: exclude_matcher
+                .build()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        })
+    }
+
+    pub fn is_included(&self, relative_path: &Path) -> bool {
+        self.include_set.is_match(relative_path)
+    }
+
+    /// Whether a `--exclude` pattern matches the path. `.catchignore` files are applied with
+    /// the other ignore files, by [`crate::code_reader::ignore_files::IgnoreFiles`].
+    pub fn is_excluded(&self, relative_path: &Path, is_dir: bool) -> bool {
+        self.exclude_matcher
+            .matched_path_or_any_parents(relative_path, is_dir)
+            .is_ignore()
+    }
+
+    /// Human-readable listing of the directories scanned (`--root`) and of every rule in effect
+    /// and where it comes from, including the rules of the nested `.catchignore` files a scan
+    /// found; see
+    /// [`crate::code_reader::FileIndex::nested_catchignore_rules`].
+    pub fn render(&self, roots: &[String], nested_catchignore_rules: &[Rule]) -> String {
+        let mut output = String::from("Scanned directories:\n");
+        if roots.is_empty() {
+            output.push_str("  the whole repository\n");
+        }
+        for root in roots {
+            let _ = writeln!(output, "  {}/", root);
+        }
+
+        output.push_str("\nIncluded files:\n");
+        for rule in &self.includes {
+            let _ = writeln!(output, "  {}", describe(rule));
+        }
+
+        output.push_str("\nExcluded:\n");
+        for dir in DEFAULT_EXCLUDED_DIRS {
+            let _ = writeln!(output, "  {}/  (default)", dir);
+        }
+        for rule in self.excludes.iter().chain(nested_catchignore_rules) {
+            let _ = writeln!(output, "  {}", describe(rule));
+        }
+        output.push_str(
+            "  .gitignore, .git/info/exclude and the global git excludes file are respected\n",
+        );
+        output
+    }
+}
+
+fn describe(rule: &Rule) -> String {
+    match &rule.source {
+        RuleSource::FileType(platform) => format!("{}  ({})", rule.pattern, platform),
+        RuleSource::CommandLine => format!("{}  (command line)", rule.pattern),
+        RuleSource::CatchIgnore(path) => format!("{}  ({})", rule.pattern, path),
+    }
+}
+
+/// The patterns of the `.catchignore` file at `path`, skipping blank lines and comments.
+pub fn catchignore_file_rules(path: &str, content: &str) -> Vec<Rule> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| Rule {
+            pattern: line.to_string(),
+            source: RuleSource::CatchIgnore(path.to_string()),
+        })
+        .collect()
+}
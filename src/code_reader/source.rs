@@ -0,0 +1,295 @@
+use crate::code_reader::ignore_files::IgnoreFiles;
+use crate::code_reader::rules::{catchignore_file_rules, Rule, ScanRules, CATCHIGNORE_FILE_NAME};
+use crate::code_reader::walker::{PruneReason, PrunedEntry, DEFAULT_EXCLUDED_DIRS};
+use crate::code_reader::ScanOptions;
+use crate::git_info::repository::{GitRepository, GitSnapshot};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Where a scan takes files from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FileSource {
+    /// Every file in the working tree.
+    #[default]
+    WorkingTree,
+    /// Files in the working tree that are in the git index.
+    TrackedOnly,
+    /// Files as committed in a revision; the working tree isn't read at all.
+    Revision(String),
+}
+
+/// Reads the content of indexed files, from disk or from git objects.
+pub(crate) enum ContentSource {
+    WorkingTree(PathBuf),
+    Revision {
+        snapshot: Box<GitSnapshot>,
+        /// The scanned directory, relative to the repository root.
+        prefix: String,
+    },
+}
+
+impl ContentSource {
+    /// Opens the source of `options` for files under `dir`. This blocks on file system access.
+    pub(crate) fn open(dir: &Path, options: &ScanOptions) -> io::Result<Self> {
+        match &options.source {
+            FileSource::Revision(rev) => {
+                let (snapshot, prefix) = open_revision(dir, rev)?;
+                Ok(ContentSource::Revision {
+                    snapshot: Box::new(snapshot),
+                    prefix,
+                })
+            }
+            _ => Ok(ContentSource::WorkingTree(dir.to_path_buf())),
+        }
+    }
+
+    /// Up to the first `len` bytes of the file.
+    pub(crate) fn read_head(&self, relative_path: &str, len: usize) -> io::Result<Vec<u8>> {
+        match self {
+            ContentSource::WorkingTree(dir) => {
+                let mut head = Vec::with_capacity(len);
+                fs::File::open(dir.join(relative_path))?
+                    .take(len as u64)
+                    .read_to_end(&mut head)?;
+                Ok(head)
+            }
+            ContentSource::Revision { .. } => {
+                let mut content = self.read(relative_path)?;
+                content.truncate(len);
+                Ok(content)
+            }
+        }
+    }
+
+    pub(crate) fn read(&self, relative_path: &str) -> io::Result<Vec<u8>> {
+        match self {
+            ContentSource::WorkingTree(dir) => fs::read(dir.join(relative_path)),
+            ContentSource::Revision { snapshot, prefix } => {
+                Ok(snapshot.read(&join(prefix, relative_path))?)
+            }
+        }
+    }
+}
+
+fn join(prefix: &str, path: &str) -> String {
+    match prefix.is_empty() {
+        true => path.to_string(),
+        false => format!("{}/{}", prefix, path),
+    }
+}
+
+/// `dir` relative to the root of `repository`, with `/` separators.
+fn repository_prefix(repository: &GitRepository, dir: &Path) -> io::Result<String> {
+    let work_dir = fs::canonicalize(&repository.work_dir)?;
+    let dir = fs::canonicalize(dir)?;
+    let relative = dir.strip_prefix(&work_dir).map_err(io::Error::other)?;
+    Ok(relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+fn open_revision(dir: &Path, rev: &str) -> io::Result<(GitSnapshot, String)> {
+    let repository = GitRepository::open(dir)?;
+    let prefix = repository_prefix(&repository, dir)?;
+    Ok((repository.snapshot(rev)?, prefix))
+}
+
+/// Strips `prefix` from a repository path; `None` for paths outside it.
+fn strip_prefix<'a>(prefix: &str, path: &'a str) -> Option<&'a str> {
+    match prefix.is_empty() {
+        true => Some(path),
+        false => path.strip_prefix(prefix)?.strip_prefix('/'),
+    }
+}
+
+/// Paths in the git index, relative to `dir`.
+pub(crate) fn tracked_paths(dir: &Path) -> io::Result<HashSet<String>> {
+    let repository = GitRepository::open(dir)?;
+    let prefix = repository_prefix(&repository, dir)?;
+    Ok(repository
+        .tracked_paths()?
+        .iter()
+        .filter_map(|path| strip_prefix(&prefix, path))
+        .map(str::to_string)
+        .collect())
+}
+
+fn in_roots(options: &ScanOptions, path: &str) -> bool {
+    options.roots.is_empty()
+        || options
+            .roots
+            .iter()
+            .any(|root| strip_prefix(root, path).is_some())
+}
+
+/// Why the walker would have left out `path`, a file or directory relative to `base_dir`,
+/// checking the same things in the same order.
+fn prune_reason(
+    rules: &ScanRules,
+    ignore_files: &IgnoreFiles,
+    base_dir: &Path,
+    path: &Path,
+    is_dir: bool,
+) -> Option<PruneReason> {
+    let is_default_excluded = || {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| DEFAULT_EXCLUDED_DIRS.contains(&name))
+    };
+    if ignore_files.is_ignored(&base_dir.join(path), is_dir) {
+        Some(PruneReason::IgnoreRule)
+    } else if is_dir && is_default_excluded() {
+        Some(PruneReason::DefaultExclude)
+    } else if rules.is_excluded(path, is_dir) {
+        Some(PruneReason::ExcludePattern)
+    } else {
+        None
+    }
+}
+
+/// The outermost directory of `path` that the walker wouldn't have descended into.
+fn pruned_ancestor(
+    rules: &ScanRules,
+    ignore_files: &IgnoreFiles,
+    base_dir: &Path,
+    path: &Path,
+) -> Option<(PathBuf, PruneReason)> {
+    let mut ancestors: Vec<&Path> = path.ancestors().skip(1).collect();
+    ancestors.reverse();
+    ancestors
+        .into_iter()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .find_map(|ancestor| {
+            prune_reason(rules, ignore_files, base_dir, ancestor, true)
+                .map(|reason| (ancestor.to_path_buf(), reason))
+        })
+}
+
+/// A file of a revision that passed the scan rules, relative to the scanned directory.
+pub(crate) struct SnapshotFile {
+    pub path: String,
+    pub size: Result<u64, String>,
+}
+
+/// What [`list_revision`] found.
+pub(crate) struct RevisionListing {
+    pub files: Vec<SnapshotFile>,
+    pub pruned: Vec<PrunedEntry>,
+    /// Rules of the committed `.catchignore` files that applied, other than the scanned
+    /// directory's own.
+    pub nested_catchignore_rules: Vec<Rule>,
+}
+
+/// Lists the files of the revision in `options` the way [`crate::code_reader::walker::walk`]
+/// lists the working tree: default excludes, exclude patterns and the `.catchignore` files
+/// committed in the revision prune files and directories, and links are never followed.
+/// Sizes come from the object headers; no file is read but the `.catchignore` files.
+pub(crate) fn list_revision(
+    dir: &Path,
+    rules: &ScanRules,
+    options: &ScanOptions,
+    rev: &str,
+) -> io::Result<RevisionListing> {
+    let repository = GitRepository::open(dir)?;
+    let prefix = repository_prefix(&repository, dir)?;
+    let snapshot = repository.snapshot(rev)?;
+    // Paths are matched against the revision as if it were checked out in the working tree.
+    let base_dir = match prefix.is_empty() {
+        true => repository.work_dir.clone(),
+        false => repository.work_dir.join(&prefix),
+    };
+    let ignore_files = IgnoreFiles::snapshot(&repository.work_dir, &snapshot);
+
+    let mut files = Vec::new();
+    let mut pruned = Vec::new();
+    let mut pruned_dirs = HashSet::new();
+    for path in snapshot.files.keys() {
+        let Some(relative_path) = strip_prefix(&prefix, path) else {
+            continue;
+        };
+        if !in_roots(options, relative_path) {
+            continue;
+        }
+
+        if let Some((ancestor, reason)) =
+            pruned_ancestor(rules, &ignore_files, &base_dir, Path::new(relative_path))
+        {
+            if pruned_dirs.insert(ancestor.clone()) {
+                pruned.push(PrunedEntry {
+                    path: ancestor.to_string_lossy().into_owned(),
+                    is_dir: true,
+                    reason,
+                });
+            }
+            continue;
+        }
+        if !rules.is_included(Path::new(relative_path)) {
+            continue;
+        }
+        if let Some(reason) = prune_reason(
+            rules,
+            &ignore_files,
+            &base_dir,
+            Path::new(relative_path),
+            false,
+        ) {
+            pruned.push(PrunedEntry {
+                path: relative_path.to_string(),
+                is_dir: false,
+                reason,
+            });
+            continue;
+        }
+
+        let size = snapshot.size(path).map_err(|e| e.to_string());
+        if let Ok(size) = size {
+            options.progress.record(size);
+        }
+        files.push(SnapshotFile {
+            path: relative_path.to_string(),
+            size,
+        });
+    }
+
+    for path in &snapshot.symlinks {
+        match strip_prefix(&prefix, path) {
+            Some(relative_path)
+                if in_roots(options, relative_path)
+                    && rules.is_included(Path::new(relative_path)) =>
+            {
+                pruned.push(PrunedEntry {
+                    path: relative_path.to_string(),
+                    is_dir: false,
+                    reason: PruneReason::Symlink,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    pruned.sort_by(|a, b| a.path.cmp(&b.path));
+    let top_level_catchignore = base_dir.join(CATCHIGNORE_FILE_NAME);
+    let nested_catchignore_rules = ignore_files
+        .catchignore_files()
+        .into_iter()
+        .filter(|(path, _)| *path != top_level_catchignore)
+        .flat_map(|(path, content)| {
+            let path = path.strip_prefix(&base_dir).unwrap_or(&path);
+            catchignore_file_rules(&path.to_string_lossy(), &content)
+        })
+        .collect();
+
+    Ok(RevisionListing {
+        files,
+        pruned,
+        nested_catchignore_rules,
+    })
+}
@@ -0,0 +1,101 @@
+use crate::code_reader::progress::ScanProgress;
+use crate::code_reader::{
+    index_files, read_files, FileIndex, IndexedFile, ScanOptions, ScanResult,
+};
+use crate::cryptography::FileCipher;
+use crate::secret_scanner::SecretPolicy;
+use crate::terminal::RawModeGuard;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::cursor::position;
+use ratatui::layout::Rect;
+use ratatui::prelude::Stylize;
+use ratatui::Terminal;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::select;
+use tokio::task::JoinHandle;
+
+/// Indexes `dir` like [`index_files`], showing how many files have been found so far.
+pub async fn index_files_with_progress(dir: &Path, options: &ScanOptions) -> io::Result<FileIndex> {
+    let index_dir = dir.to_path_buf();
+    let index_options = options.clone();
+    let task = tokio::spawn(async move { index_files(&index_dir, &index_options).await });
+
+    run_with_progress("Scanning your project...", &options.progress, task).await
+}
+
+/// Reads and encrypts `files` like [`read_files`], showing how many have been read so far.
+///
+/// Under [`SecretPolicy::Prompt`] the scan may stop to ask about a file, so progress is only
+/// reported once it's done rather than animated over the prompts.
+pub async fn read_files_with_progress(
+    dir: &Path,
+    files: &[IndexedFile],
+    cipher: &Arc<FileCipher>,
+    options: &ScanOptions,
+) -> io::Result<ScanResult> {
+    let message = format!("Reading and encrypting {} files...", files.len());
+    if options.secret_policy == SecretPolicy::Prompt {
+        println!("{}", message);
+        let result = read_files(dir, files, cipher, options).await?;
+        println!(" {} - Completed, {}", message, options.progress);
+        return Ok(result);
+    }
+
+    let read_dir = dir.to_path_buf();
+    let read_files_list = files.to_vec();
+    let read_cipher = Arc::clone(cipher);
+    let read_options = options.clone();
+    let task = tokio::spawn(async move {
+        read_files(&read_dir, &read_files_list, &read_cipher, &read_options).await
+    });
+
+    run_with_progress(&message, &options.progress, task).await
+}
+
+/// Animates `message` and `progress` below the cursor until `task` is done. The terminal
+/// leaves raw mode however this returns.
+async fn run_with_progress<T>(
+    message: &str,
+    progress: &ScanProgress,
+    mut task: JoinHandle<io::Result<T>>,
+) -> io::Result<T> {
+    let raw_mode = RawModeGuard::enable()?;
+
+    let stdout = io::stdout();
+
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let tick_rate = Duration::from_millis(100);
+    let mut state = throbber_widgets_tui::ThrobberState::default();
+
+    let terminal_size = terminal.size()?;
+    let (_, row) = position()?;
+    let area = Rect::new(0, row, terminal_size.width, 3);
+
+    let output = loop {
+        terminal.draw(|f| {
+            let throbber = throbber_widgets_tui::Throbber::default()
+                .label(format!("{} {}", message, progress))
+                .throbber_set(throbber_widgets_tui::BRAILLE_SIX)
+                .throbber_style(ratatui::style::Style::default().bold());
+            f.render_stateful_widget(throbber, area, &mut state);
+        })?;
+
+        select! {
+            _ = tokio::time::sleep(tick_rate) => {
+                state.calc_next();
+            }
+            result = &mut task => {
+                break result.map_err(io::Error::other)??;
+            }
+        }
+    };
+    drop(raw_mode);
+
+    println!(" {} - Completed, {}", message, progress);
+    Ok(output)
+}
@@ -0,0 +1,352 @@
+use crate::code_reader::ignore_files::IgnoreFiles;
+use crate::code_reader::rules::{catchignore_file_rules, Rule, ScanRules, CATCHIGNORE_FILE_NAME};
+use crate::code_reader::ScanOptions;
+use ignore::{WalkBuilder, WalkState};
+use log::warn;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Dependency, build and VCS directories that never contain code worth uploading.
+pub const DEFAULT_EXCLUDED_DIRS: &[&str] = &[
+    ".git",
+    ".hg",
+    ".svn",
+    "node_modules",
+    "bower_components",
+    "Pods",
+    "Carthage",
+    "DerivedData",
+    ".build",
+    "build",
+    ".gradle",
+    ".cxx",
+    ".externalNativeBuild",
+    ".dart_tool",
+    ".expo",
+    "vendor",
+];
+
+/// How symbolic links met while walking are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Leave every link out.
+    Skip,
+    /// Follow links whose target is inside the scanned directory.
+    #[default]
+    FollowWithinRoot,
+    /// Follow every link, wherever it points.
+    Follow,
+}
+
+impl FromStr for SymlinkPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "skip" => Ok(SymlinkPolicy::Skip),
+            "within-root" => Ok(SymlinkPolicy::FollowWithinRoot),
+            "follow" => Ok(SymlinkPolicy::Follow),
+            _ => Err(format!(
+                "Invalid symlink policy: {} (expected skip, within-root or follow)",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PruneReason {
+    /// Matched an entry of [`DEFAULT_EXCLUDED_DIRS`].
+    DefaultExclude,
+    /// Matched a `--exclude` pattern.
+    ExcludePattern,
+    /// Matched `.gitignore`, `.catchignore`, `.git/info/exclude` or the global git excludes
+    /// file.
+    IgnoreRule,
+    /// A link left out under [`SymlinkPolicy::Skip`], or a link in a git revision.
+    Symlink,
+    /// A link whose target is outside the scanned directory.
+    SymlinkOutsideRoot,
+    /// A link to a directory that contains it.
+    SymlinkLoop,
+    /// A link whose target doesn't exist.
+    BrokenSymlink,
+    /// Not in the git index.
+    Untracked,
+}
+
+impl PruneReason {
+    pub fn is_symlink(&self) -> bool {
+        matches!(
+            self,
+            PruneReason::Symlink
+                | PruneReason::SymlinkOutsideRoot
+                | PruneReason::SymlinkLoop
+                | PruneReason::BrokenSymlink
+        )
+    }
+}
+
+impl fmt::Display for PruneReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PruneReason::DefaultExclude => write!(f, "default exclude"),
+            PruneReason::ExcludePattern => write!(f, "exclude pattern"),
+            PruneReason::IgnoreRule => write!(f, "ignore rule"),
+            PruneReason::Symlink => write!(f, "symbolic link"),
+            PruneReason::SymlinkOutsideRoot => write!(f, "links outside the project"),
+            PruneReason::SymlinkLoop => write!(f, "symbolic link loop"),
+            PruneReason::BrokenSymlink => write!(f, "broken symbolic link"),
+            PruneReason::Untracked => write!(f, "not tracked by git"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrunedEntry {
+    pub path: String,
+    pub is_dir: bool,
+    pub reason: PruneReason,
+}
+
+#[derive(Debug)]
+pub struct WalkedFile {
+    pub path: PathBuf,
+    /// Size on disk, or why it couldn't be read.
+    pub size: Result<u64, String>,
+}
+
+#[derive(Debug, Default)]
+pub struct WalkOutput {
+    /// Files matching the include globs, sorted by path.
+    pub files: Vec<WalkedFile>,
+    pub pruned: Vec<PrunedEntry>,
+    /// Rules of the `.catchignore` files met in the walk, other than the scanned directory's
+    /// own, which [`ScanRules`] lists.
+    pub nested_catchignore_rules: Vec<Rule>,
+}
+
+/// Why the link at `path` is left out under `policy`, or `None` to follow it. `canonical_root`
+/// is the canonical path of the scanned directory.
+pub fn pruned_symlink(
+    path: &Path,
+    policy: SymlinkPolicy,
+    canonical_root: &Path,
+) -> Option<PruneReason> {
+    match policy {
+        SymlinkPolicy::Skip => Some(PruneReason::Symlink),
+        SymlinkPolicy::FollowWithinRoot
+            if !fs::canonicalize(path).is_ok_and(|target| target.starts_with(canonical_root)) =>
+        {
+            Some(PruneReason::SymlinkOutsideRoot)
+        }
+        _ => None,
+    }
+}
+
+/// Prune reason for walker errors caused by links: loops, detected by comparing the device
+/// and inode of a linked directory with those of its ancestors, and dangling links.
+fn symlink_error(error: &ignore::Error) -> Option<(PathBuf, PruneReason)> {
+    match error {
+        ignore::Error::WithDepth { err, .. } => symlink_error(err),
+        ignore::Error::Loop { child, .. } => Some((child.clone(), PruneReason::SymlinkLoop)),
+        ignore::Error::WithPath { path, .. } if path.is_symlink() && !path.exists() => {
+            Some((path.clone(), PruneReason::BrokenSymlink))
+        }
+        _ => None,
+    }
+}
+
+fn relative(base_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(base_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Lists the files under `base_dir` matched by the include globs of `rules`, honoring git
+/// ignore rules, `.catchignore` files, [`DEFAULT_EXCLUDED_DIRS`] and the exclude patterns of
+/// `rules`. Only [`ScanOptions::roots`] are walked when set.
+///
+/// Links are followed according to [`ScanOptions::symlinks`]; skipped links are reported in
+/// [`WalkOutput::pruned`]. Directories are read on [`ScanOptions::jobs`] threads; every file
+/// listed is recorded in [`ScanOptions::progress`]. Ignore files are matched by
+/// [`IgnoreFiles`] rather than by the walker, so every entry left out is seen, and recorded,
+/// as the walk goes.
+///
+/// This blocks on file system access.
+pub fn walk(base_dir: &Path, rules: Arc<ScanRules>, options: &ScanOptions) -> WalkOutput {
+    let symlinks = options.symlinks;
+    let progress = &options.progress;
+    let excluded = Arc::new(Mutex::new(HashMap::new()));
+    let filter_excluded = Arc::clone(&excluded);
+    let filter_base_dir = base_dir.to_path_buf();
+    let filter_rules = Arc::clone(&rules);
+    let ignore_files = Arc::new(IgnoreFiles::working_tree(base_dir));
+    let filter_ignore_files = Arc::clone(&ignore_files);
+    let canonical_base_dir = fs::canonicalize(base_dir).unwrap_or(base_dir.to_path_buf());
+
+    let mut roots = options.roots.iter().map(|root| base_dir.join(root));
+    let mut builder = WalkBuilder::new(roots.next().unwrap_or(base_dir.to_path_buf()));
+    for root in roots {
+        builder.add(root);
+    }
+    let walker = builder
+        .hidden(false)
+        .parents(false)
+        .ignore(false)
+        .git_ignore(false)
+        .git_global(false)
+        .git_exclude(false)
+        .follow_links(symlinks != SymlinkPolicy::Skip)
+        .threads(options.jobs())
+        .filter_entry(move |entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            let relative_path = entry
+                .path()
+                .strip_prefix(&filter_base_dir)
+                .unwrap_or(entry.path());
+
+            let reason = if filter_ignore_files.is_ignored(entry.path(), is_dir) {
+                Some(PruneReason::IgnoreRule)
+            } else if let Some(reason) = entry
+                .path_is_symlink()
+                .then(|| pruned_symlink(entry.path(), symlinks, &canonical_base_dir))
+                .flatten()
+            {
+                Some(reason)
+            } else if is_dir
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| DEFAULT_EXCLUDED_DIRS.contains(&name))
+            {
+                Some(PruneReason::DefaultExclude)
+            } else if filter_rules.is_excluded(relative_path, is_dir) {
+                Some(PruneReason::ExcludePattern)
+            } else {
+                None
+            };
+
+            match reason {
+                Some(reason) => {
+                    filter_excluded
+                        .lock()
+                        .unwrap()
+                        .insert(entry.path().to_path_buf(), reason);
+                    false
+                }
+                None => true,
+            }
+        })
+        .build_parallel();
+
+    let files = Mutex::new(Vec::new());
+    walker.run(|| {
+        Box::new(|entry| {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    match symlink_error(&e) {
+                        Some((path, reason)) => {
+                            excluded.lock().unwrap().insert(path, reason);
+                        }
+                        None => warn!("Failed to read directory entry: {}", e),
+                    }
+                    return WalkState::Continue;
+                }
+            };
+            if entry.file_type().is_some_and(|t| t.is_dir()) {
+                return WalkState::Continue;
+            }
+
+            let relative_path = entry.path().strip_prefix(base_dir).unwrap_or(entry.path());
+            if !rules.is_included(relative_path) {
+                return WalkState::Continue;
+            }
+            let size = entry
+                .metadata()
+                .map(|metadata| metadata.len())
+                .map_err(|e| e.to_string());
+            if let Ok(size) = size {
+                progress.record(size);
+            }
+            files.lock().unwrap().push(WalkedFile {
+                path: entry.into_path(),
+                size,
+            });
+            WalkState::Continue
+        })
+    });
+
+    let mut output = WalkOutput {
+        files: files.into_inner().unwrap(),
+        ..Default::default()
+    };
+    output.files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // Entries are listed per directory, like a directory listing would show them.
+    let mut excluded: Vec<_> = excluded.lock().unwrap().drain().collect();
+    excluded.sort_by(|(a, _), (b, _)| (a.parent(), a).cmp(&(b.parent(), b)));
+    output.pruned = excluded
+        .into_iter()
+        .map(|(path, reason)| PrunedEntry {
+            path: relative(base_dir, &path),
+            is_dir: path.is_dir(),
+            reason,
+        })
+        .collect();
+    let top_level_catchignore = base_dir.join(CATCHIGNORE_FILE_NAME);
+    output.nested_catchignore_rules = ignore_files
+        .catchignore_files()
+        .into_iter()
+        .filter(|(path, _)| *path != top_level_catchignore)
+        .flat_map(|(path, content)| catchignore_file_rules(&relative(base_dir, &path), &content))
+        .collect();
+
+    output
+}
+
+/// One-line description of what was pruned, e.g. `2 directories (node_modules, ios/Pods)`.
+pub fn summarize_pruned(pruned: &[PrunedEntry]) -> String {
+    let directories: Vec<&str> = pruned
+        .iter()
+        .filter(|entry| entry.is_dir)
+        .map(|entry| entry.path.as_str())
+        .collect();
+    let files = pruned.len() - directories.len();
+
+    let mut parts = Vec::new();
+    if !directories.is_empty() {
+        parts.push(format!(
+            "{} {} ({})",
+            directories.len(),
+            if directories.len() == 1 {
+                "directory"
+            } else {
+                "directories"
+            },
+            directories.join(", ")
+        ));
+    }
+    if files > 0 {
+        parts.push(format!(
+            "{} {}",
+            files,
+            if files == 1 { "file" } else { "files" }
+        ));
+    }
+
+    if parts.is_empty() {
+        "nothing".to_string()
+    } else {
+        parts.join(" and ")
+    }
+}
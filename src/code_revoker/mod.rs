@@ -0,0 +1,117 @@
+use crate::api_client::cli_entity::CatchRevokeFilesResponse;
+use crate::api_client::{CatchApiClient, CatchApiResponse};
+use crate::audit_log::{AuditEvent, AuditLog, RevokeRecord, UploadRecord};
+use log::error;
+use std::io;
+
+/// What a revocation deletes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevokeScope {
+    /// The files of the named session and what was derived from them.
+    Session,
+    /// Everything uploaded to the session's integration, in any session.
+    Integration,
+}
+
+async fn perform_api_request(
+    api_client: &CatchApiClient,
+    upload: &UploadRecord,
+    scope: RevokeScope,
+) -> io::Result<CatchRevokeFilesResponse> {
+    let path = match scope {
+        RevokeScope::Session => format!(
+            "/cli/{}/files?sessionId={}",
+            upload.integration_id, upload.session_id
+        ),
+        RevokeScope::Integration => format!("/cli/{}/files", upload.integration_id),
+    };
+    let response = api_client
+        .delete::<CatchRevokeFilesResponse>(path.as_str())
+        .await;
+
+    match response {
+        Ok(CatchApiResponse::Success(response)) => Ok(response),
+        Ok(CatchApiResponse::NoContent) => Ok(CatchRevokeFilesResponse::default()),
+        Err(e) => {
+            error!("API request failed: {:?}", e);
+            Err(io::Error::other("API request failed"))
+        }
+    }
+}
+
+/// The recorded upload of `session_id`, or a `NotFound` error when there is none.
+pub fn find_upload(audit_log: &AuditLog, session_id: &str) -> io::Result<UploadRecord> {
+    audit_log.find_upload(session_id)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No upload recorded for session {}", session_id),
+        )
+    })
+}
+
+/// Sessions whose uploads a revocation of `upload` deletes: only `upload`'s own, unless
+/// `scope` takes every active upload to the same integration with it.
+pub fn affected_sessions(
+    audit_log: &AuditLog,
+    upload: &UploadRecord,
+    scope: RevokeScope,
+) -> io::Result<Vec<String>> {
+    if scope == RevokeScope::Session {
+        return Ok(vec![upload.session_id.clone()]);
+    }
+    let mut sessions: Vec<String> = audit_log
+        .active_uploads()?
+        .into_iter()
+        .filter_map(|entry| match entry.event {
+            AuditEvent::Upload(record)
+                if record.environment == upload.environment
+                    && record.integration_id == upload.integration_id =>
+            {
+                Some(record.session_id)
+            }
+            _ => None,
+        })
+        .collect();
+    if !sessions.contains(&upload.session_id) {
+        sessions.push(upload.session_id.clone());
+    }
+    Ok(sessions)
+}
+
+/// Asks the environment `upload` went to for deletion of the files and derived artifacts in
+/// `scope`, and records the confirmed revocation in the audit log.
+///
+/// The revocation is recorded for every session in [`affected_sessions`]; the returned record
+/// is the one for `upload`'s session, with the deletion counts.
+pub async fn revoke_upload(
+    audit_log: &AuditLog,
+    upload: &UploadRecord,
+    scope: RevokeScope,
+) -> io::Result<RevokeRecord> {
+    let sessions = affected_sessions(audit_log, upload, scope)?;
+
+    let api_client = CatchApiClient::new(upload.environment.clone());
+    let response = perform_api_request(&api_client, upload, scope).await?;
+
+    let record = RevokeRecord {
+        environment: upload.environment.clone(),
+        session_id: upload.session_id.clone(),
+        integration_id: upload.integration_id.clone(),
+        deleted_files: response.deleted_files,
+        deleted_artifacts: response.deleted_artifacts,
+    };
+    for session_id in sessions {
+        let event = match session_id == record.session_id {
+            true => record.clone(),
+            false => RevokeRecord {
+                session_id,
+                deleted_files: None,
+                deleted_artifacts: None,
+                ..record.clone()
+            },
+        };
+        audit_log.append(AuditEvent::Revoke(event))?;
+    }
+
+    Ok(record)
+}
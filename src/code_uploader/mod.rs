@@ -1,30 +1,47 @@
+use crate::api_client::CatchApiClient;
 use crate::code_reader::CatchCLICodeFile;
 use crate::code_uploader::ui::CodeUploader;
+use crate::cryptography::{FileCipher, SealedMetadata};
+use crate::upload_manifest::SignedUploadManifest;
+use serde::Serialize;
 use std::io;
 
 mod ui;
 
+/// Structured metadata about the repository, sent along with the files of an upload.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency_summary: Option<SealedMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_manifests: Option<SealedMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xcode_targets: Option<SealedMetadata>,
+}
+
 pub async fn upload_codes(
     integration_id: String,
     session_id: String,
     code_files: Vec<CatchCLICodeFile>,
-    key: [u8; 32],
-    iv: [u8; 16],
-    public_key_pem: String,
+    cipher: &FileCipher,
+    public_key_pem: &str,
+    manifest: &SignedUploadManifest,
+    metadata: UploadMetadata,
 ) -> io::Result<()> {
+    let request = ui::build_upload_request(
+        session_id,
+        code_files,
+        cipher,
+        public_key_pem,
+        manifest,
+        metadata,
+    )?;
+
     let terminal = ratatui::init();
 
-    let code_files_clone = code_files.clone();
     let upload_result = CodeUploader::default()
-        .run(
-            terminal,
-            integration_id,
-            session_id,
-            code_files_clone,
-            key,
-            iv,
-            public_key_pem,
-        )
+        .run(terminal, integration_id, request)
         .await;
 
     match upload_result {
@@ -32,3 +49,24 @@ pub async fn upload_codes(
         Err(e) => Err(e),
     }
 }
+
+/// Uploads `code_files` without drawing anything, for callers with a UI of their own.
+pub async fn send_codes(
+    api_client: &CatchApiClient,
+    integration_id: String,
+    session_id: String,
+    code_files: Vec<CatchCLICodeFile>,
+    cipher: &FileCipher,
+    public_key_pem: &str,
+    manifest: &SignedUploadManifest,
+) -> io::Result<()> {
+    let request = ui::build_upload_request(
+        session_id,
+        code_files,
+        cipher,
+        public_key_pem,
+        manifest,
+        UploadMetadata::default(),
+    )?;
+    ui::perform_api_request(api_client, integration_id, request).await
+}
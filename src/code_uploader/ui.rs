@@ -1,8 +1,10 @@
 use crate::api_client::request_entity::CatchCLIUploadFilesRequest;
 use crate::api_client::{CatchApiClient, CatchApiResponse};
 use crate::code_reader::CatchCLICodeFile;
-use crate::cryptography::encrypt_rsa4096_base64_bytes;
+use crate::code_uploader::UploadMetadata;
+use crate::cryptography::{encrypt_rsa4096_base64_bytes, FileCipher};
 use crate::terminal::finalize_terminal;
+use crate::upload_manifest::SignedUploadManifest;
 use log::error;
 use ratatui::crossterm::cursor::position;
 use ratatui::layout::Rect;
@@ -12,27 +14,46 @@ use std::io;
 use std::time::Duration;
 use tokio::select;
 
-async fn perform_api_request(
-    integration_id: String,
+/// Wraps the key of `cipher`, and the IV of legacy schemes, with the server's public key.
+/// Fails rather than sending files nobody could decrypt.
+pub(super) fn build_upload_request(
     session_id: String,
     code_files: Vec<CatchCLICodeFile>,
-    key: [u8; 32],
-    iv: [u8; 16],
-    public_key_pem: String,
-) -> io::Result<()> {
-    let api_client = CatchApiClient::default();
+    cipher: &FileCipher,
+    public_key_pem: &str,
+    manifest: &SignedUploadManifest,
+    metadata: UploadMetadata,
+) -> io::Result<CatchCLIUploadFilesRequest> {
+    let wrap = |secret: &[u8], name: &str| {
+        encrypt_rsa4096_base64_bytes(public_key_pem, secret)
+            .map_err(|e| io::Error::other(format!("Failed to encrypt the {}: {}", name, e)))
+    };
+    let client_encrypted_iv = match cipher.scheme().is_legacy() {
+        true => Some(wrap(cipher.iv(), "file IV")?),
+        false => None,
+    };
 
+    Ok(CatchCLIUploadFilesRequest {
+        session_id,
+        files: code_files,
+        client_encrypted_iv,
+        client_encrypted_key: wrap(cipher.key(), "file key")?,
+        encryption_scheme: cipher.scheme(),
+        envelope_version: cipher.envelope_version(),
+        manifest: manifest.clone(),
+        metadata,
+    })
+}
+
+pub(super) async fn perform_api_request(
+    api_client: &CatchApiClient,
+    integration_id: String,
+    request: CatchCLIUploadFilesRequest,
+) -> io::Result<()> {
     let response = api_client
         .post::<(), CatchCLIUploadFilesRequest>(
             format!("/cli/{}/files", integration_id).as_str(),
-            &CatchCLIUploadFilesRequest {
-                session_id,
-                files: code_files,
-                client_encrypted_iv: encrypt_rsa4096_base64_bytes(&public_key_pem, &iv)
-                    .unwrap_or("".to_string()),
-                client_encrypted_key: encrypt_rsa4096_base64_bytes(&public_key_pem, &key)
-                    .unwrap_or("".to_string()),
-            },
+            &request,
         )
         .await;
 
@@ -40,14 +61,14 @@ async fn perform_api_request(
         Ok(response) => response,
         Err(e) => {
             error!("API request failed: {}", format!("{:?}", e));
-            return Err(io::Error::new(io::ErrorKind::Other, "API request failed"));
+            return Err(io::Error::other("API request failed"));
         }
     };
 
     match response {
         CatchApiResponse::Success(_) => {
             error!("API request failed");
-            Err(io::Error::new(io::ErrorKind::Other, "API request failed"))
+            Err(io::Error::other("API request failed"))
         }
         CatchApiResponse::NoContent => Ok(()),
     }
@@ -63,16 +84,11 @@ impl CodeUploader {
         self.state.calc_next();
     }
 
-    #[allow(clippy::too_many_arguments)]
     pub async fn run(
         mut self,
         mut terminal: DefaultTerminal,
         integration_id: String,
-        session_id: String,
-        code_files: Vec<CatchCLICodeFile>,
-        key: [u8; 32],
-        iv: [u8; 16],
-        public_key_pem: String,
+        request: CatchCLIUploadFilesRequest,
     ) -> io::Result<()> {
         let tick_rate = Duration::from_millis(100);
 
@@ -80,14 +96,9 @@ impl CodeUploader {
         let (_, row) = position()?;
         let area = Rect::new(0, row, terminal_size.width, 3);
 
-        let mut api_future = tokio::spawn(perform_api_request(
-            integration_id.clone(),
-            session_id.clone(),
-            code_files.clone(),
-            key,
-            iv,
-            public_key_pem.clone(),
-        ));
+        let mut api_future = tokio::spawn(async move {
+            perform_api_request(&CatchApiClient::default(), integration_id, request).await
+        });
 
         loop {
             terminal.draw(|frame| self.draw(frame, area))?;
@@ -98,13 +109,14 @@ impl CodeUploader {
                 }
                 api_resp = &mut api_future => {
                     match api_resp {
-                        Ok(_result) => {
+                        Ok(result) => {
                             finalize_terminal(&mut terminal)?;
+                            result?;
                             break;
                         }
                         Err(e) => {
                             finalize_terminal(&mut terminal)?;
-                            return Err(io::Error::new(io::ErrorKind::Other, format!("API request failed: {}", e)));
+                            return Err(io::Error::other(format!("API request failed: {}", e)));
                         }
                     }
                 }
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Per-user CLI settings persisted in the config directory.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CatchConfig {
+    /// Server key fingerprints (hex SHA-256) pinned on first use, keyed by API base URL.
+    #[serde(default)]
+    pub pinned_server_keys: BTreeMap<String, String>,
+    /// Hex-encoded Ed25519 keys allowed to sign a rotated server key, in addition to the ones
+    /// built into the CLI.
+    #[serde(default)]
+    pub root_public_keys: Vec<String>,
+}
+
+/// `$CATCH_CLI_CONFIG_DIR`, or `catch` inside the platform config directory.
+pub fn config_dir() -> io::Result<PathBuf> {
+    if let Ok(dir) = std::env::var("CATCH_CLI_CONFIG_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    dirs::config_dir()
+        .map(|dir| dir.join("catch"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No config directory found"))
+}
+
+/// `$CATCH_CLI_STATE_DIR`, or `catch` inside the platform state (or local data) directory.
+pub fn state_dir() -> io::Result<PathBuf> {
+    if let Ok(dir) = std::env::var("CATCH_CLI_STATE_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("catch"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No state directory found"))
+}
+
+impl CatchConfig {
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        match fs::read_to_string(dir.join(CONFIG_FILE_NAME)) {
+            Ok(content) => serde_json::from_str(&content).map_err(io::Error::other),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(dir.join(CONFIG_FILE_NAME), content)
+    }
+}
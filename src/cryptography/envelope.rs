@@ -0,0 +1,247 @@
+//! Versioned envelope for uploaded file content.
+//!
+//! An envelope is serialized as follows (integers are big-endian):
+//!
+//! | offset | size | field                                                     |
+//! |--------|------|-----------------------------------------------------------|
+//! | 0      | 4    | magic, ASCII `CCEV`                                       |
+//! | 4      | 1    | envelope version, currently `1`                           |
+//! | 5      | 1    | content algorithm: `1` AES-256-CBC, `2` AES-256-GCM       |
+//! | 6      | 1    | key wrapping: `1` RSA-OAEP with SHA-256                   |
+//! | 7      | 1    | flags: bit 0 set when the plaintext was raw-DEFLATE'd     |
+//! | 8      | 32   | server key id: SHA-256 of the public key's SPKI DER       |
+//! | 40     | 1    | nonce length `n`                                          |
+//! | 41     | n    | nonce (GCM, 12 bytes) or IV (CBC, 16 bytes)               |
+//! | 41 + n | 8    | plaintext length in bytes, before compression             |
+//! | 49 + n | rest | ciphertext (GCM ciphertext includes the 16-byte tag)      |
+//!
+//! For AES-256-GCM the associated data is the header (bytes `0..49 + n`) followed by the
+//! UTF-8 file path, so neither the header nor the file's location can be altered unnoticed.
+//!
+//! AES-256-CBC envelopes carry no MAC: the header, the path and the ciphertext are not
+//! authenticated, and tampering is only noticed if it breaks the padding. CBC is kept for
+//! backends that predate GCM; anything that needs integrity must use GCM.
+
+use crate::cryptography::{encrypt_aes_256, encrypt_aes_256_gcm, EncryptionScheme};
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::fmt;
+use std::io::Write;
+use zeroize::Zeroizing;
+
+pub const ENVELOPE_MAGIC: &[u8; 4] = b"CCEV";
+pub const ENVELOPE_VERSION: u8 = 1;
+
+const FLAG_COMPRESSED: u8 = 0b0000_0001;
+const FIXED_HEADER_LEN: usize = 41;
+
+#[derive(Debug)]
+pub enum EnvelopeError {
+    Truncated,
+    InvalidMagic,
+    UnsupportedVersion(u8),
+    UnknownAlgorithm(u8),
+    UnknownKeyWrapping(u8),
+    InvalidNonceLength { expected: usize, found: usize },
+    Encryption(String),
+    Compression(std::io::Error),
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::Truncated => write!(f, "Envelope is truncated"),
+            EnvelopeError::InvalidMagic => write!(f, "Envelope has an invalid magic"),
+            EnvelopeError::UnsupportedVersion(v) => write!(f, "Unsupported envelope version {}", v),
+            EnvelopeError::UnknownAlgorithm(id) => write!(f, "Unknown content algorithm {}", id),
+            EnvelopeError::UnknownKeyWrapping(id) => write!(f, "Unknown key wrapping {}", id),
+            EnvelopeError::InvalidNonceLength { expected, found } => write!(
+                f,
+                "Nonce is {} bytes long, the content algorithm needs {}",
+                found, expected
+            ),
+            EnvelopeError::Encryption(e) => write!(f, "{}", e),
+            EnvelopeError::Compression(e) => write!(f, "Compression failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyWrapping {
+    RsaOaepSha256,
+}
+
+impl KeyWrapping {
+    pub fn id(&self) -> u8 {
+        match self {
+            KeyWrapping::RsaOaepSha256 => 1,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(KeyWrapping::RsaOaepSha256),
+            _ => None,
+        }
+    }
+}
+
+impl EncryptionScheme {
+    pub fn envelope_id(&self) -> u8 {
+        match self {
+            EncryptionScheme::Aes256Cbc => 1,
+            EncryptionScheme::Aes256Gcm => 2,
+        }
+    }
+
+    pub fn from_envelope_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(EncryptionScheme::Aes256Cbc),
+            2 => Some(EncryptionScheme::Aes256Gcm),
+            _ => None,
+        }
+    }
+
+    /// Length of the envelope nonce: the IV for CBC, the nonce for GCM.
+    pub fn nonce_len(&self) -> usize {
+        match self {
+            EncryptionScheme::Aes256Cbc => 16,
+            EncryptionScheme::Aes256Gcm => 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptionEnvelope {
+    pub version: u8,
+    pub algorithm: EncryptionScheme,
+    pub key_wrapping: KeyWrapping,
+    pub key_id: [u8; 32],
+    pub compressed: bool,
+    pub nonce: Vec<u8>,
+    pub plaintext_length: u64,
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptionEnvelope {
+    /// Encrypts `plaintext` for `path`, compressing it first when that makes it smaller.
+    pub fn seal(
+        algorithm: EncryptionScheme,
+        key: &[u8; 32],
+        key_id: [u8; 32],
+        path: &str,
+        plaintext: &[u8],
+    ) -> Result<Self, EnvelopeError> {
+        let compressed = deflate(plaintext).map_err(EnvelopeError::Compression)?;
+        let is_compressed = compressed.len() < plaintext.len();
+        let body = if is_compressed {
+            &compressed
+        } else {
+            plaintext
+        };
+
+        let nonce = match algorithm {
+            EncryptionScheme::Aes256Cbc => rand::random::<[u8; 16]>().to_vec(),
+            EncryptionScheme::Aes256Gcm => rand::random::<[u8; 12]>().to_vec(),
+        };
+
+        let mut envelope = Self {
+            version: ENVELOPE_VERSION,
+            algorithm,
+            key_wrapping: KeyWrapping::RsaOaepSha256,
+            key_id,
+            compressed: is_compressed,
+            nonce,
+            plaintext_length: plaintext.len() as u64,
+            ciphertext: Vec::new(),
+        };
+
+        envelope.ciphertext = match algorithm {
+            EncryptionScheme::Aes256Cbc => {
+                let iv: [u8; 16] = envelope.nonce[..].try_into().unwrap();
+                encrypt_aes_256(key, &iv, body)
+            }
+            EncryptionScheme::Aes256Gcm => {
+                let nonce: [u8; 12] = envelope.nonce[..].try_into().unwrap();
+                let mut associated_data = envelope.header_bytes();
+                associated_data.extend_from_slice(path.as_bytes());
+                encrypt_aes_256_gcm(key, &nonce, body, &associated_data)
+                    .map_err(EnvelopeError::Encryption)?
+            }
+        };
+
+        Ok(envelope)
+    }
+
+    pub fn header_bytes(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(FIXED_HEADER_LEN + self.nonce.len() + 8);
+        header.extend_from_slice(ENVELOPE_MAGIC);
+        header.push(self.version);
+        header.push(self.algorithm.envelope_id());
+        header.push(self.key_wrapping.id());
+        header.push(if self.compressed { FLAG_COMPRESSED } else { 0 });
+        header.extend_from_slice(&self.key_id);
+        header.push(self.nonce.len() as u8);
+        header.extend_from_slice(&self.nonce);
+        header.extend_from_slice(&self.plaintext_length.to_be_bytes());
+        header
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header_bytes();
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        if bytes.len() < FIXED_HEADER_LEN {
+            return Err(EnvelopeError::Truncated);
+        }
+        if &bytes[0..4] != ENVELOPE_MAGIC {
+            return Err(EnvelopeError::InvalidMagic);
+        }
+        if bytes[4] != ENVELOPE_VERSION {
+            return Err(EnvelopeError::UnsupportedVersion(bytes[4]));
+        }
+        let algorithm = EncryptionScheme::from_envelope_id(bytes[5])
+            .ok_or(EnvelopeError::UnknownAlgorithm(bytes[5]))?;
+        let key_wrapping =
+            KeyWrapping::from_id(bytes[6]).ok_or(EnvelopeError::UnknownKeyWrapping(bytes[6]))?;
+        let compressed = bytes[7] & FLAG_COMPRESSED != 0;
+        let key_id: [u8; 32] = bytes[8..40].try_into().unwrap();
+
+        let nonce_len = bytes[40] as usize;
+        if nonce_len != algorithm.nonce_len() {
+            return Err(EnvelopeError::InvalidNonceLength {
+                expected: algorithm.nonce_len(),
+                found: nonce_len,
+            });
+        }
+        let length_offset = FIXED_HEADER_LEN + nonce_len;
+        if bytes.len() < length_offset + 8 {
+            return Err(EnvelopeError::Truncated);
+        }
+        let nonce = bytes[FIXED_HEADER_LEN..length_offset].to_vec();
+        let plaintext_length =
+            u64::from_be_bytes(bytes[length_offset..length_offset + 8].try_into().unwrap());
+
+        Ok(Self {
+            version: bytes[4],
+            algorithm,
+            key_wrapping,
+            key_id,
+            compressed,
+            nonce,
+            plaintext_length,
+            ciphertext: bytes[length_offset + 8..].to_vec(),
+        })
+    }
+}
+
+fn deflate(data: &[u8]) -> std::io::Result<Zeroizing<Vec<u8>>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish().map(Zeroizing::new)
+}
@@ -1,14 +1,209 @@
+pub mod envelope;
+pub mod secret;
+
+use crate::cryptography::envelope::{EncryptionEnvelope, ENVELOPE_VERSION};
+use crate::cryptography::secret::{SecretIv, SecretKey};
+use aes::cipher::BlockEncrypt;
+use aes::{Aes256, Block};
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use base64::Engine;
-use libaes::Cipher;
-use pkcs8::DecodePublicKey;
+use pkcs8::{DecodePublicKey, EncodePublicKey};
 use rand::rngs::OsRng;
-use rsa::sha2::Sha256;
+use rsa::sha2::{Digest, Sha256};
 use rsa::traits::PublicKeyParts;
 use rsa::{Oaep, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const AES_BLOCK_SIZE: usize = 16;
+
+/// Content encryption schemes the CLI can use for uploaded files.
+///
+/// `Aes256Cbc` is the legacy scheme: one key and one IV for the whole upload.
+/// `Aes256Gcm` uses a fresh nonce per file and binds the file path as associated data.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EncryptionScheme {
+    #[default]
+    #[serde(rename = "aes-256-cbc")]
+    Aes256Cbc,
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+}
+
+impl EncryptionScheme {
+    /// Schemes offered to the server, most preferred first.
+    pub const SUPPORTED: [EncryptionScheme; 2] =
+        [EncryptionScheme::Aes256Gcm, EncryptionScheme::Aes256Cbc];
+
+    pub fn is_legacy(&self) -> bool {
+        *self == EncryptionScheme::Aes256Cbc
+    }
+}
+
+pub struct EncryptedContent {
+    pub ciphertext: Vec<u8>,
+    pub nonce: Option<[u8; 12]>,
+}
+
+/// Structured metadata sent along with an upload, encrypted like an uploaded file.
+#[derive(Serialize, Debug, Clone)]
+pub struct SealedMetadata {
+    /// Base64 ciphertext of the JSON metadata.
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+impl SealedMetadata {
+    /// Hex SHA-256 of the ciphertext, as listed in the upload manifest.
+    pub fn ciphertext_sha256(&self) -> String {
+        hex::encode(Sha256::digest(
+            base64::engine::general_purpose::STANDARD
+                .decode(&self.content)
+                .unwrap_or_default(),
+        ))
+    }
+}
+
+/// Key material and scheme used to encrypt the files of one upload.
+///
+/// The key and IV are wiped when the cipher is dropped; pass the cipher by reference.
+#[derive(Debug)]
+pub struct FileCipher {
+    scheme: EncryptionScheme,
+    key: SecretKey,
+    iv: SecretIv,
+    envelope_key_id: Option<[u8; 32]>,
+}
+
+impl FileCipher {
+    pub fn new(scheme: EncryptionScheme, key: SecretKey, iv: SecretIv) -> Self {
+        Self {
+            scheme,
+            key,
+            iv,
+            envelope_key_id: None,
+        }
+    }
+
+    /// Wraps every file in an [`EncryptionEnvelope`] addressed to the server key `key_id`.
+    pub fn with_envelope(mut self, key_id: [u8; 32]) -> Self {
+        self.envelope_key_id = Some(key_id);
+        self
+    }
+
+    pub fn envelope_version(&self) -> Option<u8> {
+        self.envelope_key_id.map(|_| ENVELOPE_VERSION)
+    }
+
+    pub fn generate(scheme: EncryptionScheme) -> Self {
+        Self::new(scheme, SecretKey::random(), SecretIv::random())
+    }
 
-pub fn encrypt_aes_256(key: &[u8; 32], iv: &[u8; 16], message: &str) -> Vec<u8> {
-    let cipher = Cipher::new_256(key);
-    cipher.cbc_encrypt(iv, message.as_bytes())
+    pub fn scheme(&self) -> EncryptionScheme {
+        self.scheme
+    }
+
+    pub fn key(&self) -> &[u8; 32] {
+        self.key.expose()
+    }
+
+    pub fn iv(&self) -> &[u8; 16] {
+        self.iv.expose()
+    }
+
+    /// Encrypts a file for upload, as an envelope when one was negotiated.
+    pub fn seal(
+        &self,
+        path: &str,
+        content: &[u8],
+    ) -> Result<EncryptedContent, Box<dyn std::error::Error + Send + Sync>> {
+        match self.envelope_key_id {
+            Some(key_id) => {
+                let envelope =
+                    EncryptionEnvelope::seal(self.scheme, self.key(), key_id, path, content)?;
+                Ok(EncryptedContent {
+                    ciphertext: envelope.to_bytes(),
+                    nonce: None,
+                })
+            }
+            None => self.encrypt(path, content),
+        }
+    }
+
+    /// Encrypts `metadata` as JSON, bound to the reserved `path` it's stored under.
+    pub fn seal_json<T: Serialize>(&self, path: &str, metadata: &T) -> io::Result<SealedMetadata> {
+        let json = serde_json::to_vec(metadata).map_err(io::Error::other)?;
+        let encrypted = self.seal(path, &json).map_err(io::Error::other)?;
+        let engine = base64::engine::general_purpose::STANDARD;
+
+        Ok(SealedMetadata {
+            content: engine.encode(encrypted.ciphertext),
+            nonce: encrypted.nonce.map(|nonce| engine.encode(nonce)),
+        })
+    }
+
+    pub fn encrypt(
+        &self,
+        path: &str,
+        content: &[u8],
+    ) -> Result<EncryptedContent, Box<dyn std::error::Error + Send + Sync>> {
+        match self.scheme {
+            EncryptionScheme::Aes256Cbc => Ok(EncryptedContent {
+                ciphertext: encrypt_aes_256(self.key(), self.iv(), content),
+                nonce: None,
+            }),
+            EncryptionScheme::Aes256Gcm => {
+                let nonce = rand::random::<[u8; 12]>();
+                let ciphertext = encrypt_aes_256_gcm(self.key(), &nonce, content, path.as_bytes())?;
+                Ok(EncryptedContent {
+                    ciphertext,
+                    nonce: Some(nonce),
+                })
+            }
+        }
+    }
+}
+
+/// AES-256-CBC with PKCS#7 padding. The message is padded and encrypted in place in the
+/// returned buffer, so no padded plaintext copy is left behind, and the key schedule is
+/// zeroized when the cipher is dropped.
+pub fn encrypt_aes_256(key: &[u8; 32], iv: &[u8; 16], message: &[u8]) -> Vec<u8> {
+    let cipher = Aes256::new(key.into());
+    let padding = AES_BLOCK_SIZE - message.len() % AES_BLOCK_SIZE;
+    let mut buffer = Vec::with_capacity(message.len() + padding);
+    buffer.extend_from_slice(message);
+    buffer.resize(message.len() + padding, padding as u8);
+
+    let mut previous = *iv;
+    for block in buffer.chunks_exact_mut(AES_BLOCK_SIZE) {
+        for (byte, chained) in block.iter_mut().zip(previous) {
+            *byte ^= chained;
+        }
+        let block = Block::from_mut_slice(block);
+        cipher.encrypt_block(block);
+        previous.copy_from_slice(block);
+    }
+    buffer
+}
+
+pub fn encrypt_aes_256_gcm(
+    key: &[u8; 32],
+    nonce: &[u8; 12],
+    message: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>, String> {
+    let cipher = Aes256Gcm::new(key.into());
+    cipher
+        .encrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: message,
+                aad: associated_data,
+            },
+        )
+        .map_err(|e| format!("AES-256-GCM encryption failed: {}", e))
 }
 
 pub fn encrypt_rsa4096_base64(
@@ -26,6 +221,19 @@ pub fn encrypt_rsa4096_base64_bytes(
     encrypt_rsa4096_base64_internal(public_key_pem, message)
 }
 
+/// DER-encoded SubjectPublicKeyInfo of an RSA public key.
+pub fn public_key_der(public_key_pem: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let public_key = RsaPublicKey::from_public_key_pem(public_key_pem)?;
+    Ok(public_key.to_public_key_der()?.into_vec())
+}
+
+/// SHA-256 over the DER-encoded SubjectPublicKeyInfo of an RSA public key.
+pub fn public_key_fingerprint(
+    public_key_pem: &str,
+) -> Result<[u8; 32], Box<dyn std::error::Error>> {
+    Ok(Sha256::digest(public_key_der(public_key_pem)?).into())
+}
+
 fn encrypt_rsa4096_base64_internal(
     public_key_pem: &str,
     encoded_message: &[u8],
@@ -0,0 +1,47 @@
+use std::fmt;
+use zeroize::Zeroize;
+
+/// Holds key material that is wiped when dropped and never shown by `Debug`.
+///
+/// `Secret` deliberately implements neither `Clone` nor `Copy`, so the value can only be
+/// borrowed through [`Secret::expose`] instead of being duplicated along the pipeline.
+#[repr(transparent)]
+pub struct Secret<T: Zeroize>(T);
+
+pub type SecretKey = Secret<[u8; 32]>;
+pub type SecretIv = Secret<[u8; 16]>;
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn expose_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> Secret<[u8; N]> {
+    /// Fills a new secret from the OS random number generator without an intermediate copy.
+    pub fn random() -> Self {
+        let mut secret = Self([0u8; N]);
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut secret.0);
+        secret
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
@@ -0,0 +1,92 @@
+use crate::dependency_summary::{Dependency, ManifestContents};
+use toml::{Table, Value};
+
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Dependencies of a `Cargo.toml`, including platform-specific and workspace dependencies,
+/// with the binaries as targets and the `rust-version` as platform.
+pub fn parse_cargo_toml(content: &str) -> Option<ManifestContents> {
+    let manifest: Table = content.parse().ok()?;
+    let mut contents = ManifestContents::default();
+
+    push_dependencies(&mut contents, &manifest, None);
+    if let Some(targets) = manifest.get("target").and_then(Value::as_table) {
+        for (platform, target) in targets {
+            if let Some(target) = target.as_table() {
+                push_dependencies(&mut contents, target, Some(platform));
+            }
+        }
+    }
+    if let Some(dependencies) = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("dependencies"))
+        .and_then(Value::as_table)
+    {
+        for (name, source) in dependencies {
+            contents.dependencies.push(Dependency::new(
+                name,
+                requirement(source).as_deref(),
+                Some("workspace.dependencies"),
+            ));
+        }
+    }
+
+    if let Some(binaries) = manifest.get("bin").and_then(Value::as_array) {
+        contents.targets = binaries
+            .iter()
+            .filter_map(|binary| binary.get("name")?.as_str())
+            .map(str::to_string)
+            .collect();
+    }
+    if let Some(rust_version) = manifest
+        .get("package")
+        .and_then(|package| package.get("rust-version"))
+        .and_then(Value::as_str)
+    {
+        contents
+            .platforms
+            .insert("rust".to_string(), rust_version.to_string());
+    }
+
+    Some(contents)
+}
+
+fn push_dependencies(contents: &mut ManifestContents, table: &Table, platform: Option<&str>) {
+    for kind in DEPENDENCY_TABLES {
+        let Some(dependencies) = table.get(kind).and_then(Value::as_table) else {
+            continue;
+        };
+        let scope = match platform {
+            Some(platform) => format!("target.{}.{}", platform, kind),
+            None => kind.to_string(),
+        };
+        for (name, source) in dependencies {
+            contents.dependencies.push(Dependency::new(
+                name,
+                requirement(source).as_deref(),
+                Some(&scope),
+            ));
+        }
+    }
+}
+
+/// The version requirement, or where the crate comes from when it isn't from a registry.
+fn requirement(source: &Value) -> Option<String> {
+    match source {
+        Value::String(version) => Some(version.clone()),
+        Value::Table(table) => {
+            if let Some(version) = table.get("version").and_then(Value::as_str) {
+                Some(version.to_string())
+            } else if table.get("workspace").and_then(Value::as_bool) == Some(true) {
+                Some("workspace".to_string())
+            } else if table.contains_key("git") {
+                Some("git".to_string())
+            } else if table.contains_key("path") {
+                Some("path".to_string())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
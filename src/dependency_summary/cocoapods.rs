@@ -0,0 +1,88 @@
+use crate::dependency_summary::{Dependency, ManifestContents};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_yaml::Value;
+
+static PLATFORM: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^platform\s+:(\w+)(?:\s*,\s*["']([^"']+)["'])?"#).unwrap());
+static TARGET: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^(?:abstract_)?target\s+["']([^"']+)["']"#).unwrap());
+static POD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^pod\s+["']([^"']+)["'](?:\s*,\s*["']([^"']+)["'])?"#).unwrap());
+/// `Alamofire (5.8.1)` in `Podfile.lock`.
+static LOCKED_POD: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\S+) \(([^)]+)\)$").unwrap());
+
+/// Pods, targets and platforms declared in a `Podfile`. Pods are scoped to the innermost
+/// target block they're declared in.
+pub fn parse_podfile(content: &str) -> Option<ManifestContents> {
+    let mut contents = ManifestContents::default();
+    // One entry per open `do` block, holding the target it opened if any.
+    let mut blocks: Vec<Option<String>> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line == "end" {
+            blocks.pop();
+            continue;
+        }
+
+        if let Some(captures) = PLATFORM.captures(line) {
+            contents.platforms.insert(
+                captures[1].to_string(),
+                captures
+                    .get(2)
+                    .map_or("", |version| version.as_str())
+                    .to_string(),
+            );
+        } else if let Some(captures) = POD.captures(line) {
+            let target = blocks.iter().rev().flatten().next();
+            contents.dependencies.push(Dependency::new(
+                &captures[1],
+                captures.get(2).map(|version| version.as_str()),
+                target.map(String::as_str),
+            ));
+        }
+
+        if line.ends_with(" do") || line.contains(" do |") {
+            let target = TARGET
+                .captures(line)
+                .map(|captures| captures[1].to_string());
+            if let Some(target) = &target {
+                contents.targets.push(target.clone());
+            }
+            blocks.push(target);
+        }
+    }
+
+    Some(contents)
+}
+
+/// Resolved pods of a `Podfile.lock`, with the CocoaPods version that wrote it.
+pub fn parse_podfile_lock(content: &str) -> Option<ManifestContents> {
+    let lock: Value = serde_yaml::from_str(content).ok()?;
+    let mut contents = ManifestContents::default();
+
+    for pod in lock.get("PODS").and_then(Value::as_sequence)? {
+        // Pods with dependencies of their own are single-key maps.
+        let entry = match pod {
+            Value::String(entry) => entry.as_str(),
+            Value::Mapping(mapping) => match mapping.keys().next().and_then(Value::as_str) {
+                Some(entry) => entry,
+                None => continue,
+            },
+            _ => continue,
+        };
+        if let Some(captures) = LOCKED_POD.captures(entry) {
+            contents
+                .dependencies
+                .push(Dependency::new(&captures[1], Some(&captures[2]), None));
+        }
+    }
+    if let Some(version) = lock.get("COCOAPODS").and_then(Value::as_str) {
+        contents
+            .platforms
+            .insert("cocoapods".to_string(), version.to_string());
+    }
+
+    Some(contents)
+}
@@ -0,0 +1,158 @@
+use crate::dependency_summary::{Dependency, ManifestContents};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+use toml::Value;
+
+/// `implementation("group:name:version")`, `api 'group:name'`, `kapt(libs.room.compiler)`
+/// and `implementation(platform("group:name:version"))`.
+static DEPENDENCY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?m)^\s*(\w+)\s*\(?\s*(?:(?:platform|enforcedPlatform)\s*\(\s*)?(?:["']([^"':]+):([^"':]+)(?::([^"'@]+))?[^"']*["']|(libs\.[\w.]+))"#,
+    )
+    .unwrap()
+});
+/// `minSdk = 24`, `minSdkVersion 24` and `compileSdk 34`.
+static SDK_VERSION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*(minSdk|targetSdk|compileSdk)(?:Version)?\s*(?:=\s*)?\(?\s*(\d+)").unwrap()
+});
+/// `applicationId = "com.example.app"`, `applicationId 'com.example.app'` and
+/// `namespace("com.example.app")`.
+static ANDROID_ID: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s*(applicationId|namespace)\s*(?:=\s*)?\(?\s*["']([^"']+)["']"#).unwrap()
+});
+
+const CONFIGURATIONS: [&str; 4] = ["Implementation", "Api", "Only", "classpath"];
+
+/// Dependencies and SDK levels declared in a `build.gradle` or `build.gradle.kts`.
+///
+/// Build scripts are programs, so this only picks up the common literal forms; dependencies
+/// computed at build time are missed.
+pub fn parse_build_script(content: &str) -> Option<ManifestContents> {
+    let mut contents = ManifestContents::default();
+
+    for captures in DEPENDENCY.captures_iter(content) {
+        let configuration = &captures[1];
+        let is_configuration = matches!(
+            configuration,
+            "implementation" | "api" | "kapt" | "ksp" | "annotationProcessor"
+        ) || CONFIGURATIONS
+            .iter()
+            .any(|suffix| configuration.ends_with(suffix));
+        if !is_configuration {
+            continue;
+        }
+
+        let dependency = match (captures.get(2), captures.get(3), captures.get(5)) {
+            (Some(group), Some(name), _) => Dependency::new(
+                &format!("{}:{}", group.as_str(), name.as_str()),
+                captures.get(4).map(|version| version.as_str()),
+                Some(configuration),
+            ),
+            (_, _, Some(alias)) => Dependency::new(alias.as_str(), None, Some(configuration)),
+            _ => continue,
+        };
+        contents.dependencies.push(dependency);
+    }
+    for captures in SDK_VERSION.captures_iter(content) {
+        contents
+            .platforms
+            .insert(captures[1].to_string(), captures[2].to_string());
+    }
+
+    Some(contents)
+}
+
+/// `applicationId`, `namespace`, `minSdk` and `targetSdk` of the Android module a
+/// `build.gradle` or `build.gradle.kts` configures, as literal values.
+///
+/// Only the first value of each is taken, which is `defaultConfig`'s in the usual layout;
+/// product flavors that override them come after it.
+pub fn parse_android_settings(content: &str) -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+    let ids = ANDROID_ID.captures_iter(content);
+    let sdk_versions = SDK_VERSION.captures_iter(content);
+    for captures in ids.chain(sdk_versions) {
+        if &captures[1] != "compileSdk" {
+            settings
+                .entry(captures[1].to_string())
+                .or_insert_with(|| captures[2].to_string());
+        }
+    }
+    settings
+}
+
+/// Libraries and plugins of a Gradle version catalog, e.g. `gradle/libs.versions.toml`, with
+/// `version.ref` resolved against its `[versions]`.
+pub fn parse_version_catalog(content: &str) -> Option<ManifestContents> {
+    let catalog: toml::Table = content.parse().ok()?;
+    let versions = catalog.get("versions").and_then(Value::as_table);
+    let version_of = |entry: &Value| -> Option<String> {
+        match entry.get("version")? {
+            Value::String(version) => Some(version.clone()),
+            Value::Table(version) => {
+                if let Some(reference) = version.get("ref").and_then(Value::as_str) {
+                    versions?.get(reference)?.as_str().map(str::to_string)
+                } else {
+                    version
+                        .get("strictly")
+                        .or_else(|| version.get("require"))
+                        .or_else(|| version.get("prefer"))?
+                        .as_str()
+                        .map(str::to_string)
+                }
+            }
+            _ => None,
+        }
+    };
+
+    let mut contents = ManifestContents::default();
+    if let Some(libraries) = catalog.get("libraries").and_then(Value::as_table) {
+        for entry in libraries.values() {
+            let (name, version) = match entry {
+                // `"group:name:version"`
+                Value::String(notation) => match notation.rsplitn(2, ':').collect::<Vec<_>>()[..] {
+                    [version, module] if module.contains(':') => {
+                        (module.to_string(), Some(version.to_string()))
+                    }
+                    _ => (notation.clone(), None),
+                },
+                Value::Table(table) => {
+                    let module = match table.get("module").and_then(Value::as_str) {
+                        Some(module) => module.to_string(),
+                        None => format!(
+                            "{}:{}",
+                            table.get("group").and_then(Value::as_str)?,
+                            table.get("name").and_then(Value::as_str)?
+                        ),
+                    };
+                    (module, version_of(entry))
+                }
+                _ => continue,
+            };
+            contents
+                .dependencies
+                .push(Dependency::new(&name, version.as_deref(), None));
+        }
+    }
+    if let Some(plugins) = catalog.get("plugins").and_then(Value::as_table) {
+        for entry in plugins.values() {
+            let (id, version) = match entry {
+                Value::String(notation) => match notation.split_once(':') {
+                    Some((id, version)) => (id.to_string(), Some(version.to_string())),
+                    None => (notation.clone(), None),
+                },
+                Value::Table(table) => (
+                    table.get("id").and_then(Value::as_str)?.to_string(),
+                    version_of(entry),
+                ),
+                _ => continue,
+            };
+            contents
+                .dependencies
+                .push(Dependency::new(&id, version.as_deref(), Some("plugin")));
+        }
+    }
+
+    Some(contents)
+}
@@ -0,0 +1,150 @@
+use crate::dependency_summary::{Dependency, ManifestContents};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+const DEPENDENCY_FIELDS: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// Direct dependencies of a `package.json`, with the `engines` it requires as platforms.
+pub fn parse_package_json(content: &str) -> Option<ManifestContents> {
+    let package: Value = serde_json::from_str(content).ok()?;
+    let mut contents = ManifestContents::default();
+
+    for field in DEPENDENCY_FIELDS {
+        let Some(dependencies) = package.get(field).and_then(Value::as_object) else {
+            continue;
+        };
+        for (name, version) in dependencies {
+            contents
+                .dependencies
+                .push(Dependency::new(name, version.as_str(), Some(field)));
+        }
+    }
+    if let Some(engines) = package.get("engines").and_then(Value::as_object) {
+        for (engine, version) in engines {
+            if let Some(version) = version.as_str() {
+                contents
+                    .platforms
+                    .insert(engine.clone(), version.to_string());
+            }
+        }
+    }
+
+    Some(contents)
+}
+
+/// Resolved packages of a `package-lock.json`, in the `packages` layout of lockfile v2 and
+/// v3 or the `dependencies` layout of v1.
+pub fn parse_package_lock(content: &str) -> Option<ManifestContents> {
+    let lock: Value = serde_json::from_str(content).ok()?;
+    let mut contents = ManifestContents::default();
+
+    if let Some(packages) = lock.get("packages").and_then(Value::as_object) {
+        for (path, package) in packages {
+            // The root package has an empty path; nested copies keep their own entry.
+            let Some((_, name)) = path.rsplit_once("node_modules/") else {
+                continue;
+            };
+            contents.dependencies.push(locked_dependency(name, package));
+        }
+    } else if let Some(dependencies) = lock.get("dependencies").and_then(Value::as_object) {
+        for (name, package) in dependencies {
+            contents.dependencies.push(locked_dependency(name, package));
+        }
+    }
+
+    Some(contents)
+}
+
+fn locked_dependency(name: &str, package: &Value) -> Dependency {
+    let dev = package.get("dev").and_then(Value::as_bool) == Some(true);
+    Dependency::new(
+        name,
+        package.get("version").and_then(Value::as_str),
+        dev.then_some("dev"),
+    )
+}
+
+/// Resolved packages of a `yarn.lock`, in the classic format or the YAML format of Yarn 2+.
+pub fn parse_yarn_lock(content: &str) -> Option<ManifestContents> {
+    let mut contents = ManifestContents::default();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            // `"@babel/core@^7.0.0", "@babel/core@^7.1.0":` lists every range resolved to
+            // the entry; the name is the same in all of them.
+            current = line
+                .trim_end_matches(':')
+                .split(',')
+                .next()
+                .map(|specifier| specifier.trim().trim_matches('"'))
+                .filter(|specifier| *specifier != "__metadata")
+                .map(|specifier| package_name(specifier).to_string());
+            continue;
+        }
+
+        let Some(name) = &current else {
+            continue;
+        };
+        let line = line.trim();
+        let version = line
+            .strip_prefix("version:")
+            .or_else(|| line.strip_prefix("version "))
+            .map(|version| version.trim().trim_matches('"'));
+        if let Some(version) = version {
+            contents
+                .dependencies
+                .push(Dependency::new(name, Some(version), None));
+            current = None;
+        }
+    }
+
+    Some(contents)
+}
+
+/// Resolved packages of a `pnpm-lock.yaml`. Keys are `/name/1.0.0` in lockfile v5,
+/// `/name@1.0.0` in v6 and `name@1.0.0` from v9 on, optionally followed by the resolved peer
+/// dependencies in parentheses.
+pub fn parse_pnpm_lock(content: &str) -> Option<ManifestContents> {
+    let lock: serde_yaml::Value = serde_yaml::from_str(content).ok()?;
+    let mut contents = ManifestContents::default();
+    let Some(packages) = lock.get("packages").and_then(serde_yaml::Value::as_mapping) else {
+        return Some(contents);
+    };
+
+    let mut seen = BTreeSet::new();
+    for key in packages.keys().filter_map(serde_yaml::Value::as_str) {
+        let key = key.trim_start_matches('/');
+        let key = key.split_once('(').map_or(key, |(key, _)| key);
+        let (name, version) = match key[1..].find('@') {
+            Some(index) => (&key[..index + 1], &key[index + 2..]),
+            None => match key.rsplit_once('/') {
+                Some(split) => split,
+                None => continue,
+            },
+        };
+        if seen.insert((name.to_string(), version.to_string())) {
+            contents
+                .dependencies
+                .push(Dependency::new(name, Some(version), None));
+        }
+    }
+
+    Some(contents)
+}
+
+/// `@scope/name` of a `@scope/name@range` specifier.
+fn package_name(specifier: &str) -> &str {
+    match specifier.get(1..).and_then(|rest| rest.find('@')) {
+        Some(index) => &specifier[..index + 1],
+        None => specifier,
+    }
+}
@@ -0,0 +1,260 @@
+pub mod cargo;
+pub mod cocoapods;
+pub mod gradle;
+pub mod javascript;
+pub mod pubspec;
+pub mod swiftpm;
+
+use crate::code_reader::{PlaintextFiles, ScanOptions};
+use crate::cryptography::{FileCipher, SealedMetadata};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Path the summary is encrypted under; the server uses it to authenticate the content.
+pub const DEPENDENCY_SUMMARY_PATH: &str = ".catch/dependencies.json";
+
+/// Lockfiles of large apps easily exceed the per-file limit for source files.
+pub const MAX_MANIFEST_SIZE: u64 = 16 * 1024 * 1024;
+
+pub const MANIFEST_GLOBS: &[&str] = &[
+    "**/package.json",
+    "**/package-lock.json",
+    "**/yarn.lock",
+    "**/pnpm-lock.yaml",
+    "**/build.gradle",
+    "**/build.gradle.kts",
+    "**/*.versions.toml",
+    "**/Podfile",
+    "**/Podfile.lock",
+    "**/Package.swift",
+    "**/Package.resolved",
+    "**/pubspec.yaml",
+    "**/Cargo.toml",
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ecosystem {
+    Npm,
+    Gradle,
+    CocoaPods,
+    SwiftPm,
+    Pub,
+    Cargo,
+}
+
+impl fmt::Display for Ecosystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ecosystem::Npm => write!(f, "npm"),
+            Ecosystem::Gradle => write!(f, "Gradle"),
+            Ecosystem::CocoaPods => write!(f, "CocoaPods"),
+            Ecosystem::SwiftPm => write!(f, "Swift Package Manager"),
+            Ecosystem::Pub => write!(f, "pub"),
+            Ecosystem::Cargo => write!(f, "Cargo"),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependency {
+    pub name: String,
+    /// The requirement as declared in a manifest, or the resolved version in a lockfile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Where the dependency is declared, e.g. `devDependencies` or `implementation`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+impl Dependency {
+    pub fn new(name: &str, version: Option<&str>, scope: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            scope: scope.map(str::to_string),
+        }
+    }
+}
+
+/// What one manifest or lockfile declares.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestContents {
+    pub dependencies: Vec<Dependency>,
+    /// Build targets, e.g. Podfile or Swift package targets.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+    /// Platform and toolchain versions, e.g. `ios` → `13.0` or `minSdk` → `24`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub platforms: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSummary {
+    /// Path relative to the scanned directory.
+    pub path: String,
+    pub ecosystem: Ecosystem,
+    /// Lockfiles list resolved versions of every package, not just direct dependencies.
+    pub lockfile: bool,
+    #[serde(flatten)]
+    pub contents: ManifestContents,
+}
+
+impl ManifestSummary {
+    /// One line for logs, e.g. `npm, 12 dependencies, node >=18`.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            self.ecosystem.to_string(),
+            format!(
+                "{} {}",
+                self.contents.dependencies.len(),
+                match self.lockfile {
+                    true => "locked packages",
+                    false => "dependencies",
+                }
+            ),
+        ];
+        parts.extend(
+            self.contents
+                .platforms
+                .iter()
+                .map(|(platform, version)| format!("{} {}", platform, version)),
+        );
+        if !self.contents.targets.is_empty() {
+            parts.push(format!("targets: {}", self.contents.targets.join(", ")));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Dependencies and build settings of every manifest found in a scan.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencySummary {
+    pub manifests: Vec<ManifestSummary>,
+}
+
+impl DependencySummary {
+    pub fn is_empty(&self) -> bool {
+        self.manifests.is_empty()
+    }
+
+    /// Human-readable listing of every manifest and its direct dependencies.
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return "No dependency manifests found.\n".to_string();
+        }
+
+        let mut output = String::new();
+        for manifest in &self.manifests {
+            output.push_str(&format!("{} ({})\n", manifest.path, manifest.describe()));
+            if manifest.lockfile {
+                continue;
+            }
+            for dependency in &manifest.contents.dependencies {
+                output.push_str(&format!("  {}", dependency.name));
+                if let Some(version) = &dependency.version {
+                    output.push_str(&format!(" {}", version));
+                }
+                if let Some(scope) = &dependency.scope {
+                    output.push_str(&format!("  [{}]", scope));
+                }
+                output.push('\n');
+            }
+        }
+        output
+    }
+
+    pub fn seal(&self, cipher: &FileCipher) -> io::Result<SealedMetadata> {
+        cipher.seal_json(DEPENDENCY_SUMMARY_PATH, self)
+    }
+
+    /// Parses the manifests among `files`, read with at least [`MANIFEST_GLOBS`]. Manifests
+    /// that can't be parsed are left out.
+    pub fn from_files(files: &PlaintextFiles) -> io::Result<Self> {
+        let manifests = files
+            .matching(MANIFEST_GLOBS, MAX_MANIFEST_SIZE)?
+            .into_iter()
+            .filter_map(|(path, content)| parse_manifest(path, std::str::from_utf8(content).ok()?))
+            .collect();
+        Ok(Self { manifests })
+    }
+}
+
+/// Parses `content` according to the file name of `path`. Returns `None` for files that
+/// aren't dependency manifests, or can't be parsed.
+pub fn parse_manifest(path: &str, content: &str) -> Option<ManifestSummary> {
+    let file_name = Path::new(path).file_name()?.to_str()?;
+    let (ecosystem, lockfile, contents) = match file_name {
+        "package.json" => (
+            Ecosystem::Npm,
+            false,
+            javascript::parse_package_json(content),
+        ),
+        "package-lock.json" => (
+            Ecosystem::Npm,
+            true,
+            javascript::parse_package_lock(content),
+        ),
+        "yarn.lock" => (Ecosystem::Npm, true, javascript::parse_yarn_lock(content)),
+        "pnpm-lock.yaml" => (Ecosystem::Npm, true, javascript::parse_pnpm_lock(content)),
+        "build.gradle" | "build.gradle.kts" => (
+            Ecosystem::Gradle,
+            false,
+            gradle::parse_build_script(content),
+        ),
+        name if name.ends_with(".versions.toml") => (
+            Ecosystem::Gradle,
+            false,
+            gradle::parse_version_catalog(content),
+        ),
+        "Podfile" => (
+            Ecosystem::CocoaPods,
+            false,
+            cocoapods::parse_podfile(content),
+        ),
+        "Podfile.lock" => (
+            Ecosystem::CocoaPods,
+            true,
+            cocoapods::parse_podfile_lock(content),
+        ),
+        "Package.swift" => (
+            Ecosystem::SwiftPm,
+            false,
+            swiftpm::parse_package_swift(content),
+        ),
+        "Package.resolved" => (
+            Ecosystem::SwiftPm,
+            true,
+            swiftpm::parse_package_resolved(content),
+        ),
+        "pubspec.yaml" => (Ecosystem::Pub, false, pubspec::parse_pubspec(content)),
+        "Cargo.toml" => (Ecosystem::Cargo, false, cargo::parse_cargo_toml(content)),
+        _ => return None,
+    };
+
+    Some(ManifestSummary {
+        path: path.to_string(),
+        ecosystem,
+        lockfile,
+        contents: contents?,
+    })
+}
+
+/// Finds the dependency manifests under `dir` and parses them.
+///
+/// The manifests are found with the exclude patterns, roots and [`ScanOptions::source`] of
+/// `options`; see [`PlaintextFiles::read`]. Manifests that can't be parsed are left out.
+pub async fn summarize_dependencies(
+    dir: &Path,
+    options: &ScanOptions,
+) -> io::Result<DependencySummary> {
+    let files = PlaintextFiles::read(dir, MANIFEST_GLOBS, MAX_MANIFEST_SIZE, options).await?;
+    DependencySummary::from_files(&files)
+}
@@ -0,0 +1,62 @@
+use crate::dependency_summary::{Dependency, ManifestContents};
+use serde_yaml::Value;
+
+const DEPENDENCY_FIELDS: [&str; 3] = ["dependencies", "dev_dependencies", "dependency_overrides"];
+
+/// Dependencies of a `pubspec.yaml`, with the Dart and Flutter SDK constraints as platforms.
+pub fn parse_pubspec(content: &str) -> Option<ManifestContents> {
+    let pubspec: Value = serde_yaml::from_str(content).ok()?;
+    let mut contents = ManifestContents::default();
+
+    for field in DEPENDENCY_FIELDS {
+        let Some(dependencies) = pubspec.get(field).and_then(Value::as_mapping) else {
+            continue;
+        };
+        for (name, source) in dependencies {
+            let Some(name) = name.as_str() else {
+                continue;
+            };
+            contents.dependencies.push(Dependency::new(
+                name,
+                requirement(source).as_deref(),
+                Some(field),
+            ));
+        }
+    }
+    if let Some(environment) = pubspec.get("environment").and_then(Value::as_mapping) {
+        for (sdk, constraint) in environment {
+            if let (Some(sdk), Some(constraint)) = (sdk.as_str(), constraint.as_str()) {
+                let sdk = match sdk {
+                    "sdk" => "dart",
+                    sdk => sdk,
+                };
+                contents
+                    .platforms
+                    .insert(sdk.to_string(), constraint.to_string());
+            }
+        }
+    }
+
+    Some(contents)
+}
+
+/// `^1.0.0` for hosted packages; the source for SDK, git and path dependencies.
+fn requirement(source: &Value) -> Option<String> {
+    match source {
+        Value::String(version) => Some(version.clone()),
+        Value::Mapping(_) => {
+            if let Some(version) = source.get("version").and_then(Value::as_str) {
+                Some(version.to_string())
+            } else if let Some(sdk) = source.get("sdk").and_then(Value::as_str) {
+                Some(format!("sdk {}", sdk))
+            } else if source.get("git").is_some() {
+                Some("git".to_string())
+            } else if source.get("path").is_some() {
+                Some("path".to_string())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,128 @@
+use crate::dependency_summary::{Dependency, ManifestContents};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+static TOOLS_VERSION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^//\s*swift-tools-version\s*:\s*([\d.]+)").unwrap());
+static PACKAGE_LOCATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?:url|path)\s*:\s*"([^"]+)""#).unwrap());
+/// `from: "1.0.0"`, `exact: "1.0.0"`, `branch: "main"`, `revision: "abc"` and the same inside
+/// `.upToNextMajor(...)` or `.upToNextMinor(...)`.
+static REQUIREMENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\b(from|exact|branch|revision)\s*:\s*"([^"]+)""#).unwrap());
+static RANGE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#""([^"]+)"\s*(\.\.<|\.\.\.)\s*"([^"]+)""#).unwrap());
+static TARGET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"\.(?:target|executableTarget|testTarget|macro|plugin)\s*\(\s*name\s*:\s*"([^"]+)""#,
+    )
+    .unwrap()
+});
+/// `.iOS(.v15)`, `.macOS(.v10_15)` and `.iOS("15.0")`.
+static PLATFORM: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\.(iOS|macOS|macCatalyst|tvOS|watchOS|visionOS|driverKit)\s*\(\s*(?:\.v([\d_]+)|"([^"]+)")"#)
+        .unwrap()
+});
+
+/// Package dependencies, targets and platforms declared in a `Package.swift`.
+pub fn parse_package_swift(content: &str) -> Option<ManifestContents> {
+    let mut contents = ManifestContents::default();
+
+    if let Some(captures) = content
+        .lines()
+        .next()
+        .and_then(|line| TOOLS_VERSION.captures(line))
+    {
+        contents
+            .platforms
+            .insert("swift-tools".to_string(), captures[1].to_string());
+    }
+    for declaration in calls(content, ".package(") {
+        let Some(location) = PACKAGE_LOCATION.captures(declaration) else {
+            continue;
+        };
+        let name = location[1]
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches(".git");
+        let requirement = if let Some(captures) = RANGE.captures(declaration) {
+            Some(format!("{}{}{}", &captures[1], &captures[2], &captures[3]))
+        } else {
+            REQUIREMENT
+                .captures(declaration)
+                .map(|captures| match &captures[1] {
+                    "exact" => captures[2].to_string(),
+                    kind => format!("{} {}", kind, &captures[2]),
+                })
+        };
+        contents
+            .dependencies
+            .push(Dependency::new(name, requirement.as_deref(), None));
+    }
+    contents.targets = TARGET
+        .captures_iter(content)
+        .map(|captures| captures[1].to_string())
+        .collect();
+    for captures in PLATFORM.captures_iter(content) {
+        let version = match captures.get(2) {
+            Some(version) => version.as_str().replace('_', "."),
+            None => captures[3].to_string(),
+        };
+        contents.platforms.insert(captures[1].to_string(), version);
+    }
+
+    Some(contents)
+}
+
+/// The argument lists of every call to `function` in `content`, up to the matching `)`.
+fn calls<'a>(content: &'a str, function: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    content
+        .match_indices(function)
+        .filter_map(move |(start, _)| {
+            let arguments = &content[start + function.len()..];
+            let mut depth = 1;
+            for (index, character) in arguments.char_indices() {
+                match character {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    return Some(&arguments[..index]);
+                }
+            }
+            None
+        })
+}
+
+/// Resolved packages of a `Package.resolved`, in format version 1 or versions 2 and 3.
+pub fn parse_package_resolved(content: &str) -> Option<ManifestContents> {
+    let resolved: Value = serde_json::from_str(content).ok()?;
+    let pins = resolved
+        .get("pins")
+        .or_else(|| resolved.get("object")?.get("pins"))?
+        .as_array()?;
+
+    let mut contents = ManifestContents::default();
+    for pin in pins {
+        let Some(name) = pin
+            .get("identity")
+            .or_else(|| pin.get("package"))
+            .and_then(Value::as_str)
+        else {
+            continue;
+        };
+        let state = pin.get("state");
+        let version = ["version", "branch", "revision"]
+            .iter()
+            .find_map(|field| state?.get(*field)?.as_str());
+        contents
+            .dependencies
+            .push(Dependency::new(name, version, None));
+    }
+
+    Some(contents)
+}
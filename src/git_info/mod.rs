@@ -1,50 +1,44 @@
+pub mod repository;
 mod ui;
 
+use crate::git_info::repository::GitRepository;
 use crate::git_info::ui::prompt_git_info_form;
-use std::fs::File;
 use std::io;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-
-pub fn get_repo_info() -> io::Result<(String, String)> {
-    if Path::new(".git").exists() {
-        match get_git_remote_url() {
-            Ok(url) => {
-                let (org, repo) =
-                    parse_github_url(&url).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-                prompt_git_info_form(&org, &repo)
-            }
-            Err(_) => prompt_git_info_form("", ""),
+use std::path::{Path, PathBuf};
+
+/// Asks for the organization and repository names, prefilled from the `origin` remote of
+/// the repository at `repo_root` when it's on GitHub.
+pub fn get_repo_info(repo_root: &Path) -> io::Result<(String, String)> {
+    match get_git_remote_url(repo_root) {
+        Ok(url) => {
+            let (org, repo) = parse_github_url(&url).map_err(io::Error::other)?;
+            prompt_git_info_form(&org, &repo)
         }
-    } else {
-        prompt_git_info_form("", "")
+        Err(_) => prompt_git_info_form("", ""),
     }
 }
 
-pub fn get_git_remote_url() -> io::Result<String> {
-    let file = File::open(".git/config")?;
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
-
-    let origin_start = lines
-        .iter()
-        .position(|line| line.trim() == "[remote \"origin\"]");
+/// URL of the `origin` remote of the repository containing `dir`.
+pub fn get_git_remote_url(dir: &Path) -> io::Result<String> {
+    let repository = GitRepository::open(dir)?;
+    let output = repository
+        .git(["config", "--get", "remote.origin.url"])
+        .map_err(|_| io::Error::new(io::ErrorKind::NotFound, "Remote URL not found"))?;
+    Ok(String::from_utf8_lossy(&output).trim().to_string())
+}
 
-    if let Some(start) = origin_start {
-        for line in &lines[start + 1..] {
-            if line.trim().starts_with('[') {
-                break;
-            }
-            if line.trim().starts_with("url = ") {
-                return Ok(line.trim().trim_start_matches("url = ").to_string());
-            }
-        }
-    }
+/// Commit id `HEAD` points at in the repository containing `dir`; see [`GitRepository::head`].
+pub fn get_git_head(dir: &Path) -> io::Result<String> {
+    let repository = GitRepository::open(dir)?;
+    Ok(repository.head()?.to_hex())
+}
 
-    Err(io::Error::new(
-        io::ErrorKind::NotFound,
-        "Remote URL not found in .git/config",
-    ))
+/// Closest directory at or above `dir` that holds a `.git` directory, or a `.git` file for
+/// worktrees and submodules.
+pub fn find_repository_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .map(Path::to_path_buf)
 }
 
 pub fn parse_github_url(url: &str) -> Result<(String, String), String> {
@@ -0,0 +1,362 @@
+//! Read-only access to a git repository through the `git` executable, so that every layout
+//! git supports works: packs and multi-pack indexes, alternates, partial clones, split and
+//! sparse indexes, SHA-256 repositories and the full revision syntax.
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+const MODE_SYMLINK: &str = "120000";
+const MODE_SUBMODULE: &str = "160000";
+
+#[derive(Debug)]
+pub enum GitError {
+    NotARepository(PathBuf),
+    UnknownRevision(String),
+    NotACommit(String),
+    MissingObject(ObjectId),
+    MissingPath(String),
+    /// `git` failed; carries the command and what it printed on stderr.
+    Command(String, String),
+    /// `git` printed something other than what was asked for.
+    UnexpectedOutput(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitError::NotARepository(path) => {
+                write!(f, "{} is not in a git repository", path.display())
+            }
+            GitError::UnknownRevision(rev) => write!(f, "Unknown revision {}", rev),
+            GitError::NotACommit(rev) => write!(f, "{} is not a commit", rev),
+            GitError::MissingObject(id) => write!(f, "Object {} is missing", id),
+            GitError::MissingPath(path) => write!(f, "{} is not in the revision", path),
+            GitError::Command(command, stderr) => write!(f, "{} failed: {}", command, stderr),
+            GitError::UnexpectedOutput(output) => {
+                write!(f, "Unexpected output from git: {}", output)
+            }
+            GitError::Io(e) if e.kind() == io::ErrorKind::NotFound => {
+                write!(f, "Couldn't run git, is it installed? {}", e)
+            }
+            GitError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for GitError {}
+
+impl From<io::Error> for GitError {
+    fn from(e: io::Error) -> Self {
+        GitError::Io(e)
+    }
+}
+
+impl From<GitError> for io::Error {
+    fn from(e: GitError) -> Self {
+        match e {
+            GitError::Io(e) if e.kind() != io::ErrorKind::NotFound => e,
+            e => io::Error::other(e),
+        }
+    }
+}
+
+/// A SHA-1 or SHA-256 object id, in hex.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId(String);
+
+impl ObjectId {
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let is_id = matches!(hex.len(), 40 | 64) && hex.bytes().all(|b| b.is_ascii_hexdigit());
+        is_id.then(|| Self(hex.to_ascii_lowercase()))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A git repository with a working tree.
+#[derive(Debug, Clone)]
+pub struct GitRepository {
+    pub work_dir: PathBuf,
+    /// `.git`, or the directory a `.git` file points to for worktrees and submodules.
+    pub git_dir: PathBuf,
+    /// Where objects and shared refs live; differs from `git_dir` for linked worktrees.
+    pub common_dir: PathBuf,
+}
+
+/// A file of a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitBlob {
+    pub id: ObjectId,
+    pub size: u64,
+}
+
+/// The files of a commit.
+pub struct GitSnapshot {
+    pub commit: ObjectId,
+    /// Blobs by path relative to the repository root. Symbolic links and submodules are left
+    /// out.
+    pub files: BTreeMap<String, GitBlob>,
+    /// Paths of symbolic links in the commit.
+    pub symlinks: Vec<String>,
+    work_dir: PathBuf,
+    /// `git cat-file --batch`, started on the first read and shared by all of them.
+    cat_file: Mutex<Option<CatFile>>,
+}
+
+impl GitSnapshot {
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, GitError> {
+        let blob = self
+            .files
+            .get(path)
+            .ok_or_else(|| GitError::MissingPath(path.to_string()))?;
+
+        let mut cat_file = self.cat_file.lock().unwrap();
+        let process = match cat_file.as_mut() {
+            Some(process) => process,
+            None => cat_file.insert(CatFile::start(&self.work_dir)?),
+        };
+        let content = process.read(&blob.id);
+        // A failed exchange leaves the process's output out of step with the requests.
+        if matches!(
+            content,
+            Err(GitError::Io(_) | GitError::UnexpectedOutput(_))
+        ) {
+            *cat_file = None;
+        }
+        content
+    }
+
+    /// Size of the file at `path` in bytes, without reading its content.
+    pub fn size(&self, path: &str) -> Result<u64, GitError> {
+        self.files
+            .get(path)
+            .map(|blob| blob.size)
+            .ok_or_else(|| GitError::MissingPath(path.to_string()))
+    }
+}
+
+/// A running `git cat-file --batch`, which reads objects one request at a time.
+struct CatFile {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl CatFile {
+    fn start(work_dir: &Path) -> Result<Self, GitError> {
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(work_dir)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(GitError::UnexpectedOutput(
+                "git cat-file has no pipes".to_string(),
+            ));
+        };
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+
+    /// Content of the blob `id`. The process answers `<id> <type> <size>`, the content and a
+    /// newline, or `<id> missing`.
+    fn read(&mut self, id: &ObjectId) -> Result<Vec<u8>, GitError> {
+        writeln!(self.stdin, "{}", id)?;
+        self.stdin.flush()?;
+
+        let mut header = String::new();
+        self.stdout.read_line(&mut header)?;
+        let fields: Vec<&str> = header.split_whitespace().collect();
+        let size = match fields.as_slice() {
+            [_, "missing"] => return Err(GitError::MissingObject(id.clone())),
+            [_, "blob", size] => size
+                .parse::<usize>()
+                .map_err(|_| GitError::UnexpectedOutput(header.clone()))?,
+            _ => return Err(GitError::UnexpectedOutput(header)),
+        };
+
+        let mut content = vec![0; size];
+        self.stdout.read_exact(&mut content)?;
+        let mut newline = [0; 1];
+        self.stdout.read_exact(&mut newline)?;
+        Ok(content)
+    }
+}
+
+impl Drop for CatFile {
+    fn drop(&mut self) {
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+}
+
+impl GitRepository {
+    /// Opens the repository containing `dir`.
+    pub fn open(dir: &Path) -> Result<Self, GitError> {
+        let output = git_output(
+            dir,
+            [
+                "rev-parse",
+                "--show-toplevel",
+                "--git-dir",
+                "--git-common-dir",
+            ],
+        )
+        .map_err(|e| match e {
+            GitError::Command(..) => GitError::NotARepository(dir.to_path_buf()),
+            e => e,
+        })?;
+        let output = String::from_utf8(output).map_err(|e| {
+            GitError::UnexpectedOutput(String::from_utf8_lossy(e.as_bytes()).into_owned())
+        })?;
+        let lines: Vec<&str> = output.lines().collect();
+        let [work_dir, git_dir, common_dir] = lines.as_slice() else {
+            // Bare repositories have no top level.
+            return Err(GitError::NotARepository(dir.to_path_buf()));
+        };
+
+        Ok(Self {
+            work_dir: PathBuf::from(work_dir),
+            // Relative to `dir` unless git prints them absolute.
+            git_dir: dir.join(git_dir),
+            common_dir: dir.join(common_dir),
+        })
+    }
+
+    /// Runs git in the working tree and returns what it printed.
+    pub fn git<I, S>(&self, args: I) -> Result<Vec<u8>, GitError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        git_output(&self.work_dir, args)
+    }
+
+    /// Paths in the git index, relative to the repository root. Paths with merge conflicts
+    /// are listed once, and sparse indexes are expanded.
+    pub fn tracked_paths(&self) -> Result<Vec<String>, GitError> {
+        let output = self.git(["ls-files", "-z", "--full-name"])?;
+        let mut paths: Vec<String> = output
+            .split(|&b| b == 0)
+            .filter(|path| !path.is_empty())
+            .map(|path| String::from_utf8_lossy(path).into_owned())
+            .collect();
+        // Conflicted paths have an entry per merge stage, next to each other.
+        paths.dedup();
+        Ok(paths)
+    }
+
+    /// The commit `HEAD` points at. Fails on a branch without commits.
+    pub fn head(&self) -> Result<ObjectId, GitError> {
+        self.resolve("HEAD")
+    }
+
+    /// Resolves `rev`, in any syntax `git rev-parse` takes, to a commit. Annotated tags are
+    /// peeled.
+    pub fn resolve(&self, rev: &str) -> Result<ObjectId, GitError> {
+        // Anything starting with a dash would be taken for an option.
+        if rev.is_empty() || rev.starts_with('-') {
+            return Err(GitError::UnknownRevision(rev.to_string()));
+        }
+        let verify = |expression: &str| {
+            self.git([
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                "--end-of-options",
+                expression,
+            ])
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output).trim().to_string())
+        };
+        // Peeled separately, as `^{commit}` would become part of expressions like `:/message`.
+        let id = verify(rev).ok_or_else(|| GitError::UnknownRevision(rev.to_string()))?;
+        let commit =
+            verify(&format!("{}^{{commit}}", id)).ok_or(GitError::NotACommit(rev.to_string()))?;
+        ObjectId::from_hex(&commit).ok_or(GitError::UnexpectedOutput(commit))
+    }
+
+    /// Lists the files of `rev` without touching the working tree.
+    pub fn snapshot(&self, rev: &str) -> Result<GitSnapshot, GitError> {
+        let commit = self.resolve(rev)?;
+        let output = self.git(["ls-tree", "-r", "-z", "-l", "--full-tree", &commit.to_hex()])?;
+
+        let mut snapshot = GitSnapshot {
+            commit,
+            files: BTreeMap::new(),
+            symlinks: Vec::new(),
+            work_dir: self.work_dir.clone(),
+            cat_file: Mutex::new(None),
+        };
+        for entry in output.split(|&b| b == 0).filter(|entry| !entry.is_empty()) {
+            let entry = String::from_utf8_lossy(entry);
+            // `<mode> <type> <id> <size>\t<path>`, the size padded with spaces.
+            let unexpected = || GitError::UnexpectedOutput(entry.to_string());
+            let (info, path) = entry.split_once('\t').ok_or_else(unexpected)?;
+            let fields: Vec<&str> = info.split_whitespace().collect();
+            let [mode, _, id, size] = fields.as_slice() else {
+                return Err(unexpected());
+            };
+            match *mode {
+                MODE_SYMLINK => snapshot.symlinks.push(path.to_string()),
+                MODE_SUBMODULE => {}
+                _ => {
+                    let blob = GitBlob {
+                        id: ObjectId::from_hex(id).ok_or_else(unexpected)?,
+                        size: size.parse().map_err(|_| unexpected())?,
+                    };
+                    snapshot.files.insert(path.to_string(), blob);
+                }
+            }
+        }
+        snapshot.symlinks.sort();
+        Ok(snapshot)
+    }
+}
+
+/// Runs git in `dir` and returns what it printed, or [`GitError::Command`] when it fails.
+fn git_output<I, S>(dir: &Path, args: I) -> Result<Vec<u8>, GitError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args: Vec<S> = args.into_iter().collect();
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(&args)
+        .stdin(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        let command = args
+            .iter()
+            .map(|arg| arg.as_ref().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        return Err(GitError::Command(
+            format!("git {}", command),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(output.stdout)
+}
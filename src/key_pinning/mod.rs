@@ -0,0 +1,147 @@
+use crate::config::CatchConfig;
+use crate::cryptography::{public_key_der, public_key_fingerprint};
+use base64::engine::general_purpose;
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use std::fmt;
+
+/// Hex-encoded Ed25519 keys Catch signs rotated server keys with, comma-separated. Release
+/// builds set `CATCH_ROOT_PUBLIC_KEYS` at compile time so the trust anchor can't be swapped
+/// by editing the config, whose `rootPublicKeys` only add to these.
+const BUILT_IN_ROOT_PUBLIC_KEYS: &str = match option_env!("CATCH_ROOT_PUBLIC_KEYS") {
+    Some(keys) => keys,
+    None => "",
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyPinStatus {
+    /// No key was pinned for this environment; the received key is now pinned.
+    FirstUse {
+        fingerprint: String,
+    },
+    Matched {
+        fingerprint: String,
+    },
+    /// The key changed and the new one is signed by a trusted root key.
+    Rotated {
+        previous: String,
+        fingerprint: String,
+    },
+}
+
+impl KeyPinStatus {
+    pub fn fingerprint(&self) -> &str {
+        match self {
+            KeyPinStatus::FirstUse { fingerprint }
+            | KeyPinStatus::Matched { fingerprint }
+            | KeyPinStatus::Rotated { fingerprint, .. } => fingerprint,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum KeyPinError {
+    InvalidPublicKey(String),
+    KeyChanged { pinned: String, received: String },
+}
+
+impl fmt::Display for KeyPinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyPinError::InvalidPublicKey(e) => write!(f, "Invalid server public key: {}", e),
+            KeyPinError::KeyChanged { pinned, received } => write!(
+                f,
+                "Server public key changed (pinned {}, received {}) and is not signed by a trusted root key",
+                format_fingerprint(pinned),
+                format_fingerprint(received)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeyPinError {}
+
+/// Groups a hex fingerprint into colon-separated bytes for display.
+pub fn format_fingerprint(fingerprint: &str) -> String {
+    let bytes: Vec<&str> = fingerprint
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| std::str::from_utf8(pair).unwrap_or(""))
+        .collect();
+    format!("SHA256:{}", bytes.join(":"))
+}
+
+/// Checks `public_key_pem` against the key pinned for `environment`, pinning it on first use.
+///
+/// A changed key is only accepted, and re-pinned, when `signature` is a base64 Ed25519
+/// signature over `environment` followed by the key's DER-encoded SubjectPublicKeyInfo, by
+/// one of the built-in root keys or the config's. Binding the environment keeps a key signed
+/// for one deployment from being accepted for another.
+pub fn check_server_key(
+    config: &mut CatchConfig,
+    environment: &str,
+    public_key_pem: &str,
+    signature: Option<&str>,
+) -> Result<KeyPinStatus, KeyPinError> {
+    let der =
+        public_key_der(public_key_pem).map_err(|e| KeyPinError::InvalidPublicKey(e.to_string()))?;
+    let fingerprint = public_key_fingerprint(public_key_pem)
+        .map(hex::encode)
+        .map_err(|e| KeyPinError::InvalidPublicKey(e.to_string()))?;
+
+    let pinned = match config.pinned_server_keys.get(environment) {
+        None => {
+            config
+                .pinned_server_keys
+                .insert(environment.to_string(), fingerprint.clone());
+            return Ok(KeyPinStatus::FirstUse { fingerprint });
+        }
+        Some(pinned) if *pinned == fingerprint => {
+            return Ok(KeyPinStatus::Matched { fingerprint });
+        }
+        Some(pinned) => pinned.clone(),
+    };
+
+    let mut message = environment.as_bytes().to_vec();
+    message.extend_from_slice(&der);
+    let is_signed = signature
+        .is_some_and(|signature| is_signed_by_root(&config.root_public_keys, &message, signature));
+    if !is_signed {
+        return Err(KeyPinError::KeyChanged {
+            pinned,
+            received: fingerprint,
+        });
+    }
+
+    config
+        .pinned_server_keys
+        .insert(environment.to_string(), fingerprint.clone());
+    Ok(KeyPinStatus::Rotated {
+        previous: pinned,
+        fingerprint,
+    })
+}
+
+fn is_signed_by_root(root_public_keys: &[String], message: &[u8], signature: &str) -> bool {
+    let signature = match general_purpose::STANDARD
+        .decode(signature)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+    {
+        Some(signature) => signature,
+        None => return false,
+    };
+
+    let built_in = BUILT_IN_ROOT_PUBLIC_KEYS
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty());
+    let configured = root_public_keys.iter().map(String::as_str);
+    built_in.chain(configured).any(|root_key| {
+        hex::decode(root_key)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+            .is_some_and(|key| key.verify_strict(message, &signature).is_ok())
+    })
+}
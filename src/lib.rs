@@ -1,9 +1,21 @@
 pub mod api_client;
+pub mod app_manifest;
+pub mod audit_log;
 pub mod code_analyzer;
 pub mod code_candidate_selector;
 pub mod code_reader;
+pub mod code_revoker;
 pub mod code_uploader;
+pub mod config;
 pub mod cryptography;
+pub mod dependency_summary;
 pub mod git_info;
+pub mod key_pinning;
 pub mod ongoing_session;
+pub mod project_roots;
+pub mod secret_scanner;
+pub mod session_sync;
 pub mod terminal;
+pub mod upload_cache;
+pub mod upload_manifest;
+pub mod xcode_project;
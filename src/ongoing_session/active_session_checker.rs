@@ -67,7 +67,7 @@ pub async fn is_session_valid(session_id: String) -> io::Result<bool> {
             }
             CatchApiResponse::NoContent => {
                 error!("API request failed");
-                Err(io::Error::new(io::ErrorKind::Other, "API request failed"))
+                Err(io::Error::other("API request failed"))
             }
         },
         Err(e) => match e {
@@ -75,22 +75,19 @@ pub async fn is_session_valid(session_id: String) -> io::Result<bool> {
                 let status_code = e.status();
                 if status_code == Some(StatusCode::NOT_FOUND) {
                     error!("Session not found");
-                    Err(io::Error::new(io::ErrorKind::Other, e))
+                    Err(io::Error::other(e))
                 } else {
                     error!("API request failed");
-                    Err(io::Error::new(io::ErrorKind::Other, e))
+                    Err(io::Error::other(e))
                 }
             }
             CatchApiError::ResponseParseError(e) => {
                 error!("API response parse error: {}", e);
-                Err(io::Error::new(io::ErrorKind::Other, e))
+                Err(io::Error::other(e))
             }
             CatchApiError::InvalidResponse => {
                 error!("API response is invalid");
-                Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    "API response is invalid",
-                ))
+                Err(io::Error::other("API response is invalid"))
             }
         },
     }
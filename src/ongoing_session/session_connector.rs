@@ -1,6 +1,8 @@
 use crate::api_client::cli_entity::CatchConnectCLIResponse;
 use crate::api_client::request_entity::CatchConnectCLIRequest;
 use crate::api_client::{CatchApiClient, CatchApiResponse};
+use crate::cryptography::envelope::ENVELOPE_VERSION;
+use crate::cryptography::EncryptionScheme;
 use crate::terminal::finalize_terminal;
 use log::{error, info, warn};
 use ratatui::backend::CrosstermBackend;
@@ -40,6 +42,8 @@ async fn perform_api_request(
                 session_id,
                 repo_owner: org_name,
                 repo_name,
+                supported_encryption_schemes: EncryptionScheme::SUPPORTED.to_vec(),
+                supported_envelope_versions: vec![ENVELOPE_VERSION],
             },
         )
         .await
@@ -49,7 +53,7 @@ async fn perform_api_request(
         CatchApiResponse::Success(response) => Ok(response),
         CatchApiResponse::NoContent => {
             error!("API request failed");
-            Err(io::Error::new(io::ErrorKind::Other, "API request failed"))
+            Err(io::Error::other("API request failed"))
         }
     }
 }
@@ -121,7 +125,7 @@ pub async fn connect_cli_to_session(
                     }
                     Err(e) => {
                         info!("API request unsuccessful");
-                        return Err(io::Error::new(io::ErrorKind::Other, format!("API request failed: {}", e)));
+                        return Err(io::Error::other(format!("API request failed: {}", e)));
                     }
                 }
             }
@@ -140,7 +144,7 @@ pub async fn connect_cli_to_session(
                 println!(" {} - Failed", message.clone());
             }
 
-            Err(io::Error::new(io::ErrorKind::Other, "API request failed"))
+            Err(io::Error::other("API request failed"))
         }
     }
 }
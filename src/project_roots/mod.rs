@@ -0,0 +1,265 @@
+use crate::code_reader::walker::DEFAULT_EXCLUDED_DIRS;
+use crate::project_roots::ui::ProjectRootPicker;
+use ignore::WalkBuilder;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+mod ui;
+
+/// How deep below the repository root project markers are looked for.
+pub const MAX_DETECTION_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProjectKind {
+    /// Holds an `.xcodeproj` or `.xcworkspace`.
+    Xcode,
+    /// Holds `settings.gradle` or `settings.gradle.kts`.
+    Gradle,
+    /// Included from a Gradle settings file.
+    GradleModule,
+    /// `package.json` declaring workspaces.
+    JsWorkspace,
+    /// Any other `package.json`.
+    JsPackage,
+}
+
+impl fmt::Display for ProjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectKind::Xcode => write!(f, "Xcode project"),
+            ProjectKind::Gradle => write!(f, "Gradle build"),
+            ProjectKind::GradleModule => write!(f, "Gradle module"),
+            ProjectKind::JsWorkspace => write!(f, "JS workspace"),
+            ProjectKind::JsPackage => write!(f, "JS package"),
+        }
+    }
+}
+
+/// A directory that looks like a project of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectRoot {
+    /// Path relative to the repository root; empty for the repository root itself.
+    pub path: String,
+    pub kinds: Vec<ProjectKind>,
+}
+
+impl ProjectRoot {
+    pub fn display_path(&self) -> &str {
+        if self.path.is_empty() {
+            "."
+        } else {
+            &self.path
+        }
+    }
+
+    pub fn describe_kinds(&self) -> String {
+        self.kinds
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+static GRADLE_INCLUDE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*include\b\s*(?:\(([^)]*)\)|([^\n]*))").unwrap());
+static QUOTED: Lazy<Regex> = Lazy::new(|| Regex::new(r#"["']([^"']+)["']"#).unwrap());
+
+/// Module directories included by a Gradle settings file, e.g. `feature/login` for
+/// `include(":feature:login")`.
+pub fn parse_gradle_includes(settings: &str) -> Vec<String> {
+    GRADLE_INCLUDE
+        .captures_iter(settings)
+        .flat_map(|captures| {
+            QUOTED
+                .captures_iter(captures.get(1).or(captures.get(2)).unwrap().as_str())
+                .map(|quoted| {
+                    quoted[1]
+                        .trim_start_matches(':')
+                        .replace(':', "/")
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|module| !module.is_empty())
+        .collect()
+}
+
+/// Workspace globs of a `package.json`, from either `"workspaces": [...]` or
+/// `"workspaces": { "packages": [...] }`.
+pub fn parse_package_workspaces(package_json: &str) -> Vec<String> {
+    let Ok(package) = serde_json::from_str::<serde_json::Value>(package_json) else {
+        return Vec::new();
+    };
+    let workspaces = match &package["workspaces"] {
+        serde_json::Value::Object(workspaces) => workspaces.get("packages"),
+        workspaces => Some(workspaces),
+    };
+    workspaces
+        .and_then(serde_json::Value::as_array)
+        .map(|globs| {
+            globs
+                .iter()
+                .filter_map(|glob| glob.as_str())
+                .map(|glob| glob.trim_end_matches('/').to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn relative(repo_root: &Path, path: &Path) -> String {
+    path.strip_prefix(repo_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn join(base: &str, path: &str) -> String {
+    if base.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", base, path)
+    }
+}
+
+/// Finds the subprojects of the repository at `repo_root`: Xcode projects, Gradle builds and
+/// the modules they include, and JS workspaces and packages.
+///
+/// Ignored and dependency directories aren't searched. This blocks on file system access.
+pub fn detect_project_roots(repo_root: &Path) -> Vec<ProjectRoot> {
+    let walker = WalkBuilder::new(repo_root)
+        .hidden(false)
+        .require_git(false)
+        .max_depth(Some(MAX_DETECTION_DEPTH))
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|entry| {
+            !entry.file_type().is_some_and(|t| t.is_dir())
+                || !entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| DEFAULT_EXCLUDED_DIRS.contains(&name))
+        })
+        .build();
+
+    let mut roots: BTreeMap<String, Vec<ProjectKind>> = BTreeMap::new();
+    let mut add = |path: String, kind| {
+        let kinds = roots.entry(path).or_default();
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+            kinds.sort();
+        }
+    };
+
+    for entry in walker.flatten() {
+        let Some(parent) = entry.path().parent() else {
+            continue;
+        };
+        let directory = relative(repo_root, parent);
+        let file_name = entry.file_name().to_string_lossy();
+
+        if file_name.ends_with(".xcodeproj") || file_name.ends_with(".xcworkspace") {
+            // Workspaces inside projects are generated; only the project counts.
+            if !directory.ends_with(".xcodeproj") {
+                add(directory, ProjectKind::Xcode);
+            }
+        } else if file_name == "settings.gradle" || file_name == "settings.gradle.kts" {
+            let settings = fs::read_to_string(entry.path()).unwrap_or_default();
+            for module in parse_gradle_includes(&settings) {
+                if parent.join(&module).is_dir() {
+                    add(join(&directory, &module), ProjectKind::GradleModule);
+                }
+            }
+            add(directory, ProjectKind::Gradle);
+        } else if file_name == "package.json" {
+            let package_json = fs::read_to_string(entry.path()).unwrap_or_default();
+            if parse_package_workspaces(&package_json).is_empty() {
+                add(directory, ProjectKind::JsPackage);
+            } else {
+                add(directory, ProjectKind::JsWorkspace);
+            }
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|(path, kinds)| ProjectRoot { path, kinds })
+        .collect()
+}
+
+/// Turns `--root` arguments, relative to `current_dir`, into directories relative to
+/// `repo_root`. Without arguments, the scan is limited to `current_dir` when it's below the
+/// repository root. An empty list means the whole repository.
+pub fn resolve_roots(
+    repo_root: &Path,
+    current_dir: &Path,
+    roots: &[PathBuf],
+) -> io::Result<Vec<String>> {
+    let roots = match roots.is_empty() {
+        true => vec![current_dir.to_path_buf()],
+        false => roots.iter().map(|root| current_dir.join(root)).collect(),
+    };
+    let repo_root = fs::canonicalize(repo_root)?;
+
+    let mut resolved = Vec::new();
+    for root in roots {
+        let canonical = fs::canonicalize(&root)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", root.display(), e)))?;
+        if !canonical.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a directory", root.display()),
+            ));
+        }
+        let relative = canonical.strip_prefix(&repo_root).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is outside the repository", root.display()),
+            )
+        })?;
+        if relative.components().next().is_none() {
+            // The repository root covers every other root.
+            return Ok(Vec::new());
+        }
+        let relative = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        resolved.push(relative);
+    }
+    Ok(outermost(resolved))
+}
+
+/// Sorts `roots` and drops those inside another one, so no directory is scanned twice.
+pub fn outermost(mut roots: Vec<String>) -> Vec<String> {
+    roots.sort();
+    roots.dedup();
+    let mut kept: Vec<String> = Vec::new();
+    for root in roots {
+        if !kept
+            .iter()
+            .any(|outer| root.starts_with(&format!("{}/", outer)))
+        {
+            kept.push(root);
+        }
+    }
+    kept
+}
+
+/// Asks which of `roots` to scan. Returns their paths; an empty list means the whole
+/// repository.
+pub fn pick_project_roots(roots: &[ProjectRoot]) -> io::Result<Vec<String>> {
+    let picker = ProjectRootPicker::new(roots);
+
+    let terminal = ratatui::init();
+
+    picker.run(terminal).map(outermost)
+}
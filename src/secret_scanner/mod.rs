@@ -0,0 +1,152 @@
+pub mod rules;
+
+use crate::secret_scanner::rules::{
+    is_high_entropy, CANDIDATE_LITERAL, HIGH_ENTROPY_RULE_ID, SECRET_RULES,
+};
+use crate::terminal::prompt_choice;
+use std::fmt;
+use std::io;
+use std::io::IsTerminal;
+use std::ops::Range;
+use zeroize::Zeroizing;
+
+/// What to do with files in which secrets were found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SecretPolicy {
+    /// Ask for every file; files are excluded when nobody can be asked.
+    #[default]
+    Prompt,
+    Exclude,
+    Redact,
+    Proceed,
+    /// Exclude the file and report the findings so the upload can be refused.
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretDecision {
+    Exclude,
+    Redact,
+    Proceed,
+}
+
+impl fmt::Display for SecretDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretDecision::Exclude => write!(f, "excluded"),
+            SecretDecision::Redact => write!(f, "redacted"),
+            SecretDecision::Proceed => write!(f, "uploaded as-is"),
+        }
+    }
+}
+
+/// A likely secret. Holds its location and the rule that matched it, never any part of the
+/// secret, since findings are logged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretFinding {
+    pub rule_id: &'static str,
+    pub description: &'static str,
+    pub line: usize,
+    pub range: Range<usize>,
+}
+
+impl fmt::Display for SecretFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {} ({})",
+            self.line, self.description, self.rule_id
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretReport {
+    pub path: String,
+    pub findings: Vec<SecretFinding>,
+    pub decision: SecretDecision,
+}
+
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].bytes().filter(|&b| b == b'\n').count() + 1
+}
+
+/// Finds likely secrets in `text`: known token formats, PEM private keys, hard-coded
+/// credentials and high-entropy string literals. Overlapping findings are reported once.
+pub fn scan(text: &str) -> Vec<SecretFinding> {
+    let mut findings: Vec<SecretFinding> = Vec::new();
+    let mut push = |rule_id, description, range: Range<usize>| {
+        if findings
+            .iter()
+            .any(|f| f.range.start < range.end && range.start < f.range.end)
+        {
+            return;
+        }
+        findings.push(SecretFinding {
+            rule_id,
+            description,
+            line: line_of(text, range.start),
+            range,
+        });
+    };
+
+    for rule in SECRET_RULES.iter() {
+        for captures in rule.regex.captures_iter(text) {
+            if let Some(secret) = captures.get(rule.group) {
+                push(rule.id, rule.description, secret.range());
+            }
+        }
+    }
+    for captures in CANDIDATE_LITERAL.captures_iter(text) {
+        let literal = captures.get(1).unwrap();
+        if is_high_entropy(literal.as_str()) {
+            push(HIGH_ENTROPY_RULE_ID, "High-entropy string", literal.range());
+        }
+    }
+
+    findings.sort_by_key(|f| f.range.start);
+    findings
+}
+
+/// Replaces every finding with `[REDACTED:<rule>]`.
+pub fn redact(text: &str, findings: &[SecretFinding]) -> Zeroizing<Vec<u8>> {
+    let mut redacted = Zeroizing::new(Vec::with_capacity(text.len()));
+    let mut position = 0;
+    for finding in findings {
+        redacted.extend_from_slice(&text.as_bytes()[position..finding.range.start]);
+        redacted.extend_from_slice(format!("[REDACTED:{}]", finding.rule_id).as_bytes());
+        position = finding.range.end;
+    }
+    redacted.extend_from_slice(&text.as_bytes()[position..]);
+    redacted
+}
+
+/// Decides what happens to a file with findings, asking on the terminal under
+/// [`SecretPolicy::Prompt`].
+pub fn decide(
+    policy: SecretPolicy,
+    path: &str,
+    findings: &[SecretFinding],
+) -> io::Result<SecretDecision> {
+    match policy {
+        SecretPolicy::Exclude | SecretPolicy::Fail => Ok(SecretDecision::Exclude),
+        SecretPolicy::Redact => Ok(SecretDecision::Redact),
+        SecretPolicy::Proceed => Ok(SecretDecision::Proceed),
+        SecretPolicy::Prompt if !io::stdin().is_terminal() => Ok(SecretDecision::Exclude),
+        SecretPolicy::Prompt => {
+            println!("\n⚠️  Possible secrets in {}:", path);
+            for finding in findings {
+                println!("    {}", finding);
+            }
+            let choice = prompt_choice(
+                "What should be uploaded?",
+                &["exclude the file", "redact the matches", "proceed as-is"],
+            )?;
+            Ok([
+                SecretDecision::Exclude,
+                SecretDecision::Redact,
+                SecretDecision::Proceed,
+            ][choice])
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub struct SecretRule {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub regex: Regex,
+    /// Capture group holding the secret itself; `0` for the whole match.
+    pub group: usize,
+}
+
+fn rule(id: &'static str, description: &'static str, pattern: &str, group: usize) -> SecretRule {
+    SecretRule {
+        id,
+        description,
+        regex: Regex::new(pattern).unwrap(),
+        group,
+    }
+}
+
+pub static SECRET_RULES: Lazy<Vec<SecretRule>> = Lazy::new(|| {
+    vec![
+        rule(
+            "private-key",
+            "Private key block",
+            r"-----BEGIN [A-Z0-9 ]*PRIVATE KEY(?: BLOCK)?-----[\s\S]*?-----END [A-Z0-9 ]*PRIVATE KEY(?: BLOCK)?-----",
+            0,
+        ),
+        rule(
+            "aws-access-key-id",
+            "AWS access key ID",
+            r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+            0,
+        ),
+        rule(
+            "google-api-key",
+            "Google API key",
+            r"\bAIza[0-9A-Za-z_\-]{35}",
+            0,
+        ),
+        rule(
+            "github-token",
+            "GitHub token",
+            r"\bgh[pousr]_[0-9A-Za-z]{36,}\b",
+            0,
+        ),
+        rule(
+            "slack-token",
+            "Slack token",
+            r"\bxox[abposr]-[0-9A-Za-z\-]{10,}",
+            0,
+        ),
+        rule(
+            "stripe-secret-key",
+            "Stripe secret key",
+            r"\b[sr]k_live_[0-9A-Za-z]{16,}\b",
+            0,
+        ),
+        rule(
+            "secret-assignment",
+            "Hard-coded credential",
+            r#"(?i)(?:secret|token|password|passwd|api[_-]?key|access[_-]?key|client[_-]?secret)[\w\-]*["']?\s*[:=]\s*["']([^"'\s]{8,})["']"#,
+            1,
+        ),
+        rule(
+            "env-secret",
+            "Credential in an environment file",
+            r"(?m)^[ \t]*(?:export[ \t]+)?[A-Z0-9_]*(?:SECRET|TOKEN|PASSWORD|API_KEY|ACCESS_KEY|PRIVATE_KEY)[A-Z0-9_]*[ \t]*=[ \t]*([^\s\x22'#]{8,})",
+            1,
+        ),
+    ]
+});
+
+/// Quoted string literals that may be keys; reported when their entropy is high enough.
+pub static CANDIDATE_LITERAL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"["']([A-Za-z0-9+/=_\-]{24,})["']"#).unwrap());
+
+pub const HIGH_ENTROPY_RULE_ID: &str = "high-entropy-string";
+
+/// Minimum Shannon entropy, in bits per character, for a hex or base64 literal to be flagged.
+pub const HEX_ENTROPY_THRESHOLD: f64 = 3.0;
+pub const BASE64_ENTROPY_THRESHOLD: f64 = 4.0;
+
+pub fn shannon_entropy(value: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    for byte in value.bytes() {
+        counts[byte as usize] += 1;
+    }
+    let len = value.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+pub fn is_high_entropy(value: &str) -> bool {
+    if value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        value.len() >= 32 && shannon_entropy(value) >= HEX_ENTROPY_THRESHOLD
+    } else {
+        // Identifiers like `com_example_really_long_name` have letters only and low entropy;
+        // keys mix digits in.
+        value.bytes().any(|b| b.is_ascii_digit())
+            && shannon_entropy(value) >= BASE64_ENTROPY_THRESHOLD
+    }
+}
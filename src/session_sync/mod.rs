@@ -0,0 +1,268 @@
+use crate::api_client::CatchApiClient;
+use crate::audit_log::{AuditEvent, AuditLog, UploadRecord};
+use crate::code_reader::content::{SkipReason, SkippedFile};
+use crate::code_reader::source::FileSource;
+use crate::code_reader::walker::{pruned_symlink, PruneReason};
+use crate::code_reader::{read_files, IndexedFile, ScanOptions};
+use crate::code_uploader::send_codes;
+use crate::cryptography::FileCipher;
+use crate::git_info;
+use crate::secret_scanner::SecretPolicy;
+use crate::session_sync::ui::SyncLogView;
+use crate::upload_cache::{UploadCache, UploadSnapshot};
+use crate::upload_manifest::UploadManifest;
+use chrono::Utc;
+use ed25519_dalek::SigningKey;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+mod ui;
+
+/// What one sync pushed to the session.
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub uploaded: Vec<String>,
+    pub deleted: Vec<String>,
+    /// Changed files that were left out, e.g. because they now contain likely secrets.
+    pub skipped: Vec<SkippedFile>,
+}
+
+impl SyncReport {
+    pub fn is_empty(&self) -> bool {
+        self.uploaded.is_empty() && self.deleted.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// Keeps the files of the last upload to a session in sync with the working tree.
+pub struct SessionSync {
+    upload: UploadRecord,
+    public_key: String,
+    cipher: Arc<FileCipher>,
+    signing_key: SigningKey,
+    repo_root: PathBuf,
+    state_dir: PathBuf,
+    snapshot: UploadSnapshot,
+    /// The files of the upload the sync started from; deleted files stay watched in case
+    /// they come back.
+    watched: BTreeSet<String>,
+    options: ScanOptions,
+}
+
+impl SessionSync {
+    /// Picks up where `upload` left off, using the files the upload cache recorded for it.
+    ///
+    /// Changed files are always read from the working tree. Secrets can't be asked about
+    /// while the sync log is shown, so files with likely secrets are excluded under
+    /// [`SecretPolicy::Prompt`].
+    pub fn new(
+        upload: UploadRecord,
+        public_key: String,
+        cipher: Arc<FileCipher>,
+        signing_key: SigningKey,
+        repo_root: &Path,
+        state_dir: &Path,
+        mut options: ScanOptions,
+    ) -> io::Result<Self> {
+        let snapshot = UploadCache::new(state_dir, &upload.environment, &upload.integration_id)
+            .load(&upload.org_name, &upload.repo_name)?
+            .filter(|snapshot| snapshot.session_id == upload.session_id)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "No files recorded for the last upload to session {}",
+                        upload.session_id
+                    ),
+                )
+            })?;
+
+        options.source = FileSource::WorkingTree;
+        if options.secret_policy == SecretPolicy::Prompt {
+            options.secret_policy = SecretPolicy::Exclude;
+        }
+
+        Ok(Self {
+            upload,
+            public_key,
+            cipher,
+            signing_key,
+            repo_root: repo_root.to_path_buf(),
+            state_dir: state_dir.to_path_buf(),
+            watched: snapshot.files.keys().cloned().collect(),
+            snapshot,
+            options,
+        })
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.upload.session_id
+    }
+
+    /// Paths of the synced files, relative to the repository root.
+    pub fn files(&self) -> impl Iterator<Item = &String> {
+        self.watched.iter()
+    }
+
+    /// Directories holding synced files. Editors often save by replacing a file, which a
+    /// watch on the file itself would stop following.
+    pub fn watched_dirs(&self) -> BTreeSet<PathBuf> {
+        self.files()
+            .filter_map(|path| self.repo_root.join(path).parent().map(Path::to_path_buf))
+            .collect()
+    }
+
+    /// Synced files touched by `event`, relative to the repository root.
+    pub fn changed_paths(&self, event: &Event) -> Vec<String> {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return Vec::new();
+        }
+        event
+            .paths
+            .iter()
+            .filter_map(|path| path.strip_prefix(&self.repo_root).ok())
+            .map(|path| path.to_string_lossy().into_owned())
+            .filter(|path| self.watched.contains(path))
+            .collect()
+    }
+
+    /// Uploads the files in `paths` whose content changed since they were last sent, and
+    /// deletes the ones that no longer exist.
+    pub async fn sync(&mut self, paths: BTreeSet<String>) -> io::Result<SyncReport> {
+        let mut report = SyncReport::default();
+        let mut files = Vec::new();
+        let limit = self.options.size_limits.max_file_size;
+        for path in paths {
+            let full_path = self.repo_root.join(&path);
+            // Links are checked before anything is read through them.
+            let metadata = match fs::symlink_metadata(&full_path) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    if self.snapshot.files.contains_key(&path) {
+                        report.deleted.push(path);
+                    }
+                    continue;
+                }
+                metadata => metadata,
+            };
+            if let Some(reason) = self.pruned_symlink(&path) {
+                report.skipped.push(SkippedFile {
+                    path,
+                    reason: SkipReason::Symlink(reason),
+                });
+                continue;
+            }
+            match metadata.and_then(|metadata| match metadata.is_symlink() {
+                true => fs::metadata(&full_path),
+                false => Ok(metadata),
+            }) {
+                Ok(metadata) if metadata.len() > limit => report.skipped.push(SkippedFile {
+                    path,
+                    reason: SkipReason::TooLarge {
+                        size: metadata.len(),
+                        limit,
+                    },
+                }),
+                Ok(metadata) => files.push(IndexedFile {
+                    path,
+                    size: metadata.len(),
+                }),
+                Err(e) => report.skipped.push(SkippedFile {
+                    path,
+                    reason: SkipReason::Unreadable(e.to_string()),
+                }),
+            }
+        }
+
+        let scan_result = read_files(&self.repo_root, &files, &self.cipher, &self.options).await?;
+        report.skipped.extend(scan_result.skipped);
+        let changed: Vec<_> = scan_result
+            .files
+            .into_iter()
+            .filter(|file| {
+                self.snapshot.files.get(&file.path) != Some(&file.metadata.plaintext_sha256)
+            })
+            .collect();
+        if changed.is_empty() && report.deleted.is_empty() {
+            return Ok(report);
+        }
+
+        let session_id = self.upload.session_id.clone();
+        let manifest = UploadManifest::new(&session_id, &self.upload.integration_id, &changed)
+            .incremental(&session_id, report.deleted.clone())
+            .sign(&self.signing_key);
+        report.uploaded = changed.iter().map(|file| file.path.clone()).collect();
+        send_codes(
+            &CatchApiClient::new(self.upload.environment.clone()),
+            self.upload.integration_id.clone(),
+            session_id,
+            changed,
+            &self.cipher,
+            &self.public_key,
+            &manifest,
+        )
+        .await?;
+
+        for entry in &manifest.manifest.files {
+            self.snapshot
+                .files
+                .insert(entry.path.clone(), entry.plaintext_sha256.clone());
+        }
+        for path in &report.deleted {
+            self.snapshot.files.remove(path);
+        }
+        self.snapshot.uploaded_at = Utc::now();
+        UploadCache::new(
+            &self.state_dir,
+            &self.upload.environment,
+            &self.upload.integration_id,
+        )
+        .save(&self.snapshot)?;
+        manifest.save(&self.state_dir)?;
+        AuditLog::new(&self.state_dir).append(AuditEvent::Upload(UploadRecord {
+            git_head: git_info::get_git_head(&self.repo_root).ok(),
+            files: manifest.manifest.files.clone(),
+            base_session_id: manifest.manifest.base_session_id.clone(),
+            deleted_files: manifest.manifest.deleted_files.clone(),
+            ..self.upload.clone()
+        }))?;
+
+        Ok(report)
+    }
+
+    /// Why the scan would leave out `path` for being, or being under, a link; the same check
+    /// the walker makes under [`ScanOptions::symlinks`].
+    fn pruned_symlink(&self, path: &str) -> Option<PruneReason> {
+        let canonical_root = fs::canonicalize(&self.repo_root).unwrap_or(self.repo_root.clone());
+        Path::new(path)
+            .ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .map(|ancestor| self.repo_root.join(ancestor))
+            .filter(|ancestor| ancestor.is_symlink())
+            .find_map(|link| pruned_symlink(&link, self.options.symlinks, &canonical_root))
+    }
+}
+
+/// Watches the synced files and pushes changes to the session until the user quits, showing
+/// a running log of what was synced.
+pub async fn watch_session(mut sync: SessionSync) -> io::Result<()> {
+    let (sender, receiver) = flume::unbounded();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        _ = sender.send(event);
+    })
+    .map_err(io::Error::other)?;
+    for dir in sync.watched_dirs() {
+        if dir.is_dir() {
+            watcher
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .map_err(io::Error::other)?;
+        }
+    }
+
+    let terminal = ratatui::init();
+    SyncLogView::new(&sync)
+        .run(terminal, &mut sync, receiver)
+        .await
+}
@@ -0,0 +1,266 @@
+use crate::session_sync::{SessionSync, SyncReport};
+use crate::terminal::finalize_terminal;
+use chrono::Local;
+use flume::Receiver;
+use ratatui::{
+    crossterm::event::{self, Event as TerminalEvent, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph},
+    DefaultTerminal, Frame,
+};
+use std::collections::BTreeSet;
+use std::io;
+use std::time::{Duration, Instant};
+use tokio::select;
+
+const INFO_TEXT: &str = "(q) Stop watching";
+/// Editors write a file in several steps; changes are pushed once they settle.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+const MAX_LOG_LINES: usize = 500;
+/// Wait before retrying a failed sync, doubled after each failure in a row up to
+/// [`MAX_RETRY_DELAY`].
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+pub struct SyncLogView {
+    state: throbber_widgets_tui::ThrobberState,
+    title: String,
+    log: Vec<Line<'static>>,
+    pending: BTreeSet<String>,
+    last_change: Option<Instant>,
+    syncing: bool,
+    /// Syncs failed in a row; their paths are back in `pending`, retried at `retry_at`.
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl SyncLogView {
+    pub fn new(sync: &SessionSync) -> Self {
+        Self {
+            state: throbber_widgets_tui::ThrobberState::default(),
+            title: format!(
+                " Session {} - watching {} file(s) ",
+                sync.session_id(),
+                sync.files().count()
+            ),
+            log: Vec::new(),
+            pending: BTreeSet::new(),
+            last_change: None,
+            syncing: false,
+            failures: 0,
+            retry_at: None,
+        }
+    }
+
+    fn push_log(&mut self, line: Line<'static>) {
+        self.log.push(line);
+        if self.log.len() > MAX_LOG_LINES {
+            self.log.remove(0);
+        }
+    }
+
+    fn record(&mut self, symbol: &str, message: String, style: Style) {
+        let time = Local::now().format("%H:%M:%S");
+        self.push_log(Line::styled(
+            format!("{} {} {}", time, symbol, message),
+            style,
+        ));
+    }
+
+    fn record_report(&mut self, report: &SyncReport) {
+        for path in &report.uploaded {
+            self.record("↑", format!("Uploaded {}", path), Style::new().green());
+        }
+        for path in &report.deleted {
+            self.record("✕", format!("Deleted {}", path), Style::new().red());
+        }
+        for skipped in &report.skipped {
+            self.record(
+                "!",
+                format!("Skipped {}: {}", skipped.path, skipped.reason),
+                Style::new().yellow(),
+            );
+        }
+        if report.is_empty() {
+            self.record(
+                "·",
+                "No content changes".to_string(),
+                Style::new().dark_gray(),
+            );
+        }
+    }
+
+    fn is_settled(&self) -> bool {
+        self.last_change
+            .is_some_and(|last_change| last_change.elapsed() >= DEBOUNCE)
+    }
+
+    /// Whether pending changes should be synced now: they settled, and no failed sync is
+    /// waiting to be retried.
+    fn is_due(&self) -> bool {
+        !self.pending.is_empty()
+            && self.is_settled()
+            && self
+                .retry_at
+                .map_or(true, |retry_at| Instant::now() >= retry_at)
+    }
+
+    /// Puts the paths of a failed sync back in `pending` and schedules a retry.
+    fn retry_later(&mut self, paths: BTreeSet<String>, error: &io::Error) {
+        self.failures += 1;
+        let delay = RETRY_DELAY
+            .saturating_mul(2u32.saturating_pow(self.failures - 1))
+            .min(MAX_RETRY_DELAY);
+        self.pending.extend(paths);
+        self.last_change = Some(Instant::now());
+        self.retry_at = Some(Instant::now() + delay);
+        self.record(
+            "✕",
+            format!("Sync failed: {}; retrying in {}s", error, delay.as_secs()),
+            Style::new().red(),
+        );
+    }
+
+    /// Shows the sync log until the user quits or the watch ends. The terminal is restored
+    /// on every exit path, errors included.
+    pub async fn run(
+        mut self,
+        mut terminal: DefaultTerminal,
+        sync: &mut SessionSync,
+        events: Receiver<notify::Result<notify::Event>>,
+    ) -> io::Result<()> {
+        let result = self.watch(&mut terminal, sync, events).await;
+        finalize_terminal(&mut terminal)?;
+        result
+    }
+
+    async fn watch(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        sync: &mut SessionSync,
+        events: Receiver<notify::Result<notify::Event>>,
+    ) -> io::Result<()> {
+        let tick_rate = Duration::from_millis(100);
+        self.record(
+            "●",
+            "Watching for changes".to_string(),
+            Style::new().dark_gray(),
+        );
+
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(Duration::from_millis(0))? {
+                if let TerminalEvent::Key(key) = event::read()? {
+                    let is_ctrl_c = key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.code == KeyCode::Char('c');
+                    if key.kind == KeyEventKind::Press
+                        && (is_ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc))
+                    {
+                        break;
+                    }
+                }
+            }
+
+            if self.is_due() {
+                let paths = std::mem::take(&mut self.pending);
+                self.last_change = None;
+                self.syncing = true;
+                terminal.draw(|frame| self.draw(frame))?;
+
+                match sync.sync(paths.clone()).await {
+                    Ok(report) => {
+                        self.failures = 0;
+                        self.retry_at = None;
+                        self.record_report(&report);
+                    }
+                    Err(e) => self.retry_later(paths, &e),
+                }
+                self.syncing = false;
+                continue;
+            }
+
+            select! {
+                _ = tokio::time::sleep(tick_rate) => {
+                    self.state.calc_next();
+                }
+                event = events.recv_async() => {
+                    match event {
+                        Ok(Ok(event)) => {
+                            let paths = sync.changed_paths(&event);
+                            if !paths.is_empty() {
+                                self.pending.extend(paths);
+                                self.last_change = Some(Instant::now());
+                            }
+                        }
+                        Ok(Err(e)) => {
+                            self.record("!", format!("Watch error: {}", e), Style::new().yellow());
+                        }
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let vertical = &Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+        ]);
+        let rects = vertical.split(frame.area());
+
+        self.render_log(frame, rects[0]);
+        self.render_status(frame, rects[1]);
+        self.render_footer(frame, rects[2]);
+    }
+
+    fn render_log(&self, frame: &mut Frame, area: Rect) {
+        // Keep the latest lines in view.
+        let visible = usize::from(area.height.saturating_sub(2));
+        let lines = self.log[self.log.len().saturating_sub(visible)..].to_vec();
+        let log = Paragraph::new(lines)
+            .bg(tailwind::NEUTRAL.c950)
+            .block(Block::bordered().title(self.title.clone()));
+        frame.render_widget(log, area);
+    }
+
+    fn render_status(&mut self, frame: &mut Frame, area: Rect) {
+        let label = match (self.syncing, self.pending.len(), self.retry_at) {
+            (true, _, _) => "Syncing changes...".to_string(),
+            (false, 0, _) => "Waiting for changes".to_string(),
+            (false, count, Some(retry_at)) => format!(
+                "{} changed file(s) pending, retrying in {}s",
+                count,
+                retry_at.saturating_duration_since(Instant::now()).as_secs()
+            ),
+            (false, count, None) => format!("{} changed file(s) pending", count),
+        };
+        let throbber = throbber_widgets_tui::Throbber::default()
+            .label(label)
+            .throbber_set(throbber_widgets_tui::BRAILLE_SIX)
+            .throbber_style(Style::default().bold());
+        frame.render_stateful_widget(throbber, area, &mut self.state);
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let info_footer = Paragraph::new(Line::from(INFO_TEXT))
+            .style(
+                Style::new()
+                    .fg(tailwind::NEUTRAL.c200)
+                    .bg(tailwind::NEUTRAL.c950),
+            )
+            .centered()
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(tailwind::NEUTRAL.c400)),
+            );
+        frame.render_widget(info_footer, area);
+    }
+}
@@ -1,7 +1,10 @@
 use ratatui::backend::CrosstermBackend;
-use ratatui::crossterm::terminal::disable_raw_mode;
+use ratatui::crossterm::cursor::Show;
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use ratatui::Terminal;
 use std::io;
+use std::io::Write;
 
 pub fn finalize_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
     disable_raw_mode()?;
@@ -9,3 +12,68 @@ pub fn finalize_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>)
 
     Ok(())
 }
+
+/// Keeps the terminal in raw mode while it's alive. Dropping it leaves raw mode and shows the
+/// cursor, so an early return on an error doesn't leave the terminal unusable.
+pub struct RawModeGuard(());
+
+impl RawModeGuard {
+    pub fn enable() -> io::Result<Self> {
+        enable_raw_mode()?;
+        Ok(Self(()))
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        _ = disable_raw_mode();
+        _ = execute!(io::stdout(), Show);
+    }
+}
+
+/// Asks a yes/no question on stdin; anything but `y`/`yes` counts as no.
+pub fn prompt_confirmation(question: &str) -> io::Result<bool> {
+    prompt_confirmation_on(&mut io::stdout(), question)
+}
+
+/// Like [`prompt_confirmation`], but asks on `output`, e.g. stderr when stdout is reserved for
+/// machine-readable output.
+pub fn prompt_confirmation_on(output: &mut impl Write, question: &str) -> io::Result<bool> {
+    write!(output, "{} [y/N] ", question)?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Asks `question` on stdin until one of `choices` (matched by first letter) is picked.
+/// Returns the index of the chosen option.
+pub fn prompt_choice(question: &str, choices: &[&str]) -> io::Result<usize> {
+    let options = choices
+        .iter()
+        .map(|choice| format!("[{}]{}", &choice[..1], &choice[1..]))
+        .collect::<Vec<_>>()
+        .join(" / ");
+
+    loop {
+        print!("{} {} ", question, options);
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "No answer on stdin",
+            ));
+        }
+        let answer = answer.trim().to_lowercase();
+        if let Some(index) = choices
+            .iter()
+            .position(|choice| !answer.is_empty() && choice.to_lowercase().starts_with(&answer))
+        {
+            return Ok(index);
+        }
+    }
+}
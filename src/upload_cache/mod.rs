@@ -0,0 +1,200 @@
+use crate::code_reader::source::FileSource;
+use crate::code_reader::{CatchCLICodeFile, ScanOptions};
+use chrono::{DateTime, Utc};
+use rsa::sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const UPLOAD_CACHE_DIR_NAME: &str = "uploads";
+
+/// What the last upload for a repository sent: the plaintext hash of every file the server
+/// holds after it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSnapshot {
+    pub session_id: String,
+    pub org_name: String,
+    pub repo_name: String,
+    pub uploaded_at: DateTime<Utc>,
+    /// Which files the upload was selected from. Snapshots written before scopes were
+    /// recorded have the default, empty scope, which matches no scan.
+    #[serde(default)]
+    pub scope: UploadScope,
+    /// Hex SHA-256 of the plaintext, by path.
+    pub files: BTreeMap<String, String>,
+}
+
+/// What a scan selected files from. A delta is only computed against an upload of the same
+/// scope: files outside the new scope would otherwise count as deleted.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadScope {
+    /// `--root` directories; empty for the whole repository.
+    pub roots: Vec<String>,
+    /// Picked Xcode targets, as `project/target`.
+    pub targets: Vec<String>,
+    pub includes: Vec<String>,
+    pub excludes: Vec<String>,
+    /// `working-tree`, `tracked-only` or `revision`. Scans of different revisions share a
+    /// scope, so their delta is what changed between the commits.
+    pub source: String,
+}
+
+impl UploadScope {
+    /// The scope of a scan with `options`, limited to the Xcode `targets` picked.
+    pub fn new(options: &ScanOptions, targets: Vec<String>) -> Self {
+        let source = match options.source {
+            FileSource::WorkingTree => "working-tree",
+            FileSource::TrackedOnly => "tracked-only",
+            FileSource::Revision(_) => "revision",
+        };
+        Self {
+            roots: options.roots.clone(),
+            targets,
+            includes: options.includes.clone(),
+            excludes: options.excludes.clone(),
+            source: source.to_string(),
+        }
+    }
+}
+
+/// How a new selection of files differs from an [`UploadSnapshot`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadDelta {
+    pub base_session_id: String,
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub unchanged: BTreeSet<String>,
+    /// Paths in the snapshot that aren't in the new selection.
+    pub deleted: Vec<String>,
+}
+
+impl UploadSnapshot {
+    pub fn new(
+        session_id: &str,
+        org_name: &str,
+        repo_name: &str,
+        scope: UploadScope,
+        code_files: &[CatchCLICodeFile],
+    ) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            org_name: org_name.to_string(),
+            repo_name: repo_name.to_string(),
+            uploaded_at: Utc::now(),
+            scope,
+            files: code_files
+                .iter()
+                .map(|file| (file.path.clone(), file.metadata.plaintext_sha256.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn diff(&self, code_files: &[CatchCLICodeFile]) -> UploadDelta {
+        let mut delta = UploadDelta {
+            base_session_id: self.session_id.clone(),
+            ..Default::default()
+        };
+        for file in code_files {
+            let path = file.path.clone();
+            match self.files.get(&file.path) {
+                None => delta.added.push(path),
+                Some(hash) if *hash == file.metadata.plaintext_sha256 => {
+                    delta.unchanged.insert(path);
+                }
+                Some(_) => delta.modified.push(path),
+            }
+        }
+        let selected: HashSet<&str> = code_files.iter().map(|file| file.path.as_str()).collect();
+        delta.deleted = self
+            .files
+            .keys()
+            .filter(|path| !selected.contains(path.as_str()))
+            .cloned()
+            .collect();
+        delta
+    }
+}
+
+impl UploadDelta {
+    /// Number of files to upload or delete.
+    pub fn change_count(&self) -> usize {
+        self.added.len() + self.modified.len() + self.deleted.len()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} modified, {} deleted, {} unchanged",
+            self.added.len(),
+            self.modified.len(),
+            self.deleted.len(),
+            self.unchanged.len()
+        )
+    }
+
+    /// Drops the files that are unchanged since the snapshot.
+    pub fn changed_files(&self, code_files: Vec<CatchCLICodeFile>) -> Vec<CatchCLICodeFile> {
+        code_files
+            .into_iter()
+            .filter(|file| !self.unchanged.contains(&file.path))
+            .collect()
+    }
+}
+
+/// The last upload to one integration of one environment, kept in the state directory.
+pub struct UploadCache {
+    path: PathBuf,
+}
+
+impl UploadCache {
+    pub fn new(state_dir: &Path, environment: &str, integration_id: &str) -> Self {
+        let key = Sha256::new()
+            .chain_update(environment)
+            .chain_update([0])
+            .chain_update(integration_id)
+            .finalize();
+
+        Self {
+            path: state_dir
+                .join(UPLOAD_CACHE_DIR_NAME)
+                .join(format!("{}.json", hex::encode(&key[..16]))),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The last upload of `org_name/repo_name`, if this integration last received that
+    /// repository.
+    pub fn load(&self, org_name: &str, repo_name: &str) -> io::Result<Option<UploadSnapshot>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let snapshot: UploadSnapshot = serde_json::from_str(&content).map_err(io::Error::other)?;
+
+        Ok(Some(snapshot)
+            .filter(|snapshot| snapshot.org_name == org_name && snapshot.repo_name == repo_name))
+    }
+
+    pub fn save(&self, snapshot: &UploadSnapshot) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(snapshot).map_err(io::Error::other)?;
+        fs::write(&self.path, content)
+    }
+
+    /// Forgets the last upload, so the next one sends every file.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
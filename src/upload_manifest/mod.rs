@@ -0,0 +1,210 @@
+use crate::code_reader::CatchCLICodeFile;
+use crate::code_uploader::UploadMetadata;
+use crate::cryptography::secret::SecretKey;
+use crate::cryptography::SealedMetadata;
+use base64::engine::general_purpose;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use rsa::sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+pub const MANIFEST_VERSION: u8 = 1;
+
+const SIGNING_KEY_FILE_NAME: &str = "installation_key";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub plaintext_sha256: String,
+    pub ciphertext_sha256: String,
+}
+
+/// Record of exactly which files, at which content, were sent in one upload.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadManifest {
+    pub version: u8,
+    pub session_id: String,
+    pub integration_id: String,
+    pub created_at: DateTime<Utc>,
+    pub files: Vec<UploadManifestEntry>,
+    /// Session whose upload `files` are changes to; the server keeps that upload's other files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_session_id: Option<String>,
+    /// Paths of the base upload to drop.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deleted_files: Vec<String>,
+    /// Hex SHA-256 of the encrypted dependency summary sent along with the files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependency_summary_sha256: Option<String>,
+    /// Hex SHA-256 of the encrypted app manifest report sent along with the files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_manifests_sha256: Option<String>,
+    /// Hex SHA-256 of the encrypted Xcode target membership sent along with the files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xcode_targets_sha256: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedUploadManifest {
+    pub manifest: UploadManifest,
+    /// Base64 Ed25519 signature over [`UploadManifest::canonical_bytes`].
+    pub signature: String,
+    /// Hex-encoded Ed25519 public key of the installation that signed the manifest.
+    pub public_key: String,
+}
+
+impl UploadManifest {
+    pub fn new(session_id: &str, integration_id: &str, code_files: &[CatchCLICodeFile]) -> Self {
+        let files = code_files
+            .iter()
+            .map(|file| UploadManifestEntry {
+                path: file.path.clone(),
+                size: file.metadata.size,
+                plaintext_sha256: file.metadata.plaintext_sha256.clone(),
+                ciphertext_sha256: hex::encode(Sha256::digest(
+                    general_purpose::STANDARD
+                        .decode(&file.content)
+                        .unwrap_or_default(),
+                )),
+            })
+            .collect();
+
+        Self {
+            version: MANIFEST_VERSION,
+            session_id: session_id.to_string(),
+            integration_id: integration_id.to_string(),
+            created_at: Utc::now(),
+            files,
+            base_session_id: None,
+            deleted_files: Vec::new(),
+            dependency_summary_sha256: None,
+            app_manifests_sha256: None,
+            xcode_targets_sha256: None,
+        }
+    }
+
+    /// Marks the manifest as an incremental upload on top of `base_session_id`'s.
+    pub fn incremental(mut self, base_session_id: &str, deleted_files: Vec<String>) -> Self {
+        self.base_session_id = Some(base_session_id.to_string());
+        self.deleted_files = deleted_files;
+        self
+    }
+
+    /// Records the metadata sent with the upload, so the signature covers it too.
+    pub fn with_metadata(mut self, metadata: &UploadMetadata) -> Self {
+        self.dependency_summary_sha256 = metadata
+            .dependency_summary
+            .as_ref()
+            .map(SealedMetadata::ciphertext_sha256);
+        self.app_manifests_sha256 = metadata
+            .app_manifests
+            .as_ref()
+            .map(SealedMetadata::ciphertext_sha256);
+        self.xcode_targets_sha256 = metadata
+            .xcode_targets
+            .as_ref()
+            .map(SealedMetadata::ciphertext_sha256);
+        self
+    }
+
+    /// Compact JSON of the manifest, in field declaration order; this is what gets signed.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("Upload manifest is always serializable")
+    }
+
+    pub fn sign(self, signing_key: &SigningKey) -> SignedUploadManifest {
+        let signature = signing_key.sign(&self.canonical_bytes());
+
+        SignedUploadManifest {
+            manifest: self,
+            signature: general_purpose::STANDARD.encode(signature.to_bytes()),
+            public_key: hex::encode(signing_key.verifying_key().to_bytes()),
+        }
+    }
+}
+
+impl SignedUploadManifest {
+    pub fn verify(&self) -> bool {
+        let public_key = hex::decode(&self.public_key)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok());
+        let signature = general_purpose::STANDARD
+            .decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok());
+
+        match (public_key, signature) {
+            (Some(public_key), Some(signature)) => public_key
+                .verify_strict(&self.manifest.canonical_bytes(), &signature)
+                .is_ok(),
+            _ => false,
+        }
+    }
+
+    /// Writes the manifest to `<dir>/manifests/` and returns the file path.
+    pub fn save(&self, dir: &Path) -> io::Result<PathBuf> {
+        let manifests_dir = dir.join("manifests");
+        fs::create_dir_all(&manifests_dir)?;
+
+        let path = manifests_dir.join(format!(
+            "{}-{}.json",
+            self.manifest.session_id,
+            self.manifest.created_at.timestamp()
+        ));
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+}
+
+/// Loads this installation's Ed25519 signing key from `dir`, generating it on first use.
+pub fn load_or_create_signing_key(dir: &Path) -> io::Result<SigningKey> {
+    let path = dir.join(SIGNING_KEY_FILE_NAME);
+
+    match fs::read_to_string(&path).map(Zeroizing::new) {
+        Ok(content) => {
+            let bytes = Zeroizing::new(hex::decode(content.trim()).map_err(io::Error::other)?);
+            let seed: &[u8; 32] = bytes[..]
+                .try_into()
+                .map_err(|_| io::Error::other("Invalid installation key"))?;
+            Ok(SigningKey::from_bytes(seed))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let seed = SecretKey::random();
+            let signing_key = SigningKey::from_bytes(seed.expose());
+
+            fs::create_dir_all(dir)?;
+            write_private_file(&path, &Zeroizing::new(hex::encode(seed.expose())))?;
+            Ok(signing_key)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(unix)]
+fn write_private_file(path: &Path, content: &str) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(content.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private_file(path: &Path, content: &str) -> io::Result<()> {
+    fs::write(path, content)
+}
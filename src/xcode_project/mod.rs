@@ -0,0 +1,443 @@
+pub mod pbxproj;
+pub mod workspace;
+
+use crate::code_reader::{IndexedFile, PlaintextFiles, ScanOptions};
+use crate::cryptography::{FileCipher, SealedMetadata};
+use crate::xcode_project::ui::TargetPicker;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+mod ui;
+
+/// Path the membership map is encrypted under; the server uses it to authenticate the content.
+pub const XCODE_TARGETS_PATH: &str = ".catch/xcode-targets.json";
+
+/// Project files of large apps run to several megabytes.
+pub const MAX_PROJECT_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Deepest chain of build settings referring to each other that's expanded.
+const MAX_BUILD_SETTING_DEPTH: usize = 16;
+
+/// `$(NAME)`, `${NAME}` and `$(NAME:operator)`.
+static BUILD_SETTING_REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$\(([A-Za-z0-9_:]+)\)|\$\{([A-Za-z0-9_:]+)\}").unwrap());
+
+pub const XCODE_PROJECT_GLOBS: &[&str] = &[
+    "**/*.xcodeproj/project.pbxproj",
+    "**/*.xcworkspace/contents.xcworkspacedata",
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TargetKind {
+    Application,
+    /// App extensions, widgets, watch extensions and the like.
+    Extension,
+    UnitTests,
+    UiTests,
+    Framework,
+    Library,
+    Other,
+}
+
+impl TargetKind {
+    /// Kind of a target with `productType`, e.g. `com.apple.product-type.app-extension`.
+    pub fn from_product_type(product_type: Option<&str>) -> Self {
+        let Some(product_type) = product_type else {
+            return TargetKind::Other;
+        };
+        let product_type = product_type.trim_start_matches("com.apple.product-type.");
+        if product_type.contains("unit-test") {
+            TargetKind::UnitTests
+        } else if product_type.contains("ui-testing") {
+            TargetKind::UiTests
+        } else if product_type.contains("extension") {
+            TargetKind::Extension
+        } else if product_type.starts_with("application") {
+            TargetKind::Application
+        } else if product_type.starts_with("framework") {
+            TargetKind::Framework
+        } else if product_type.starts_with("library") {
+            TargetKind::Library
+        } else {
+            TargetKind::Other
+        }
+    }
+
+    /// Test and extension targets are rarely what an integration needs to look at.
+    pub fn is_selected_by_default(&self) -> bool {
+        !matches!(
+            self,
+            TargetKind::Extension | TargetKind::UnitTests | TargetKind::UiTests
+        )
+    }
+}
+
+impl fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetKind::Application => write!(f, "app"),
+            TargetKind::Extension => write!(f, "extension"),
+            TargetKind::UnitTests => write!(f, "unit tests"),
+            TargetKind::UiTests => write!(f, "UI tests"),
+            TargetKind::Framework => write!(f, "framework"),
+            TargetKind::Library => write!(f, "library"),
+            TargetKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// A target of an Xcode project, with the files that are built into it.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct XcodeTarget {
+    pub name: String,
+    pub kind: TargetKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_type: Option<String>,
+    /// Files in the target's build phases, relative to the scanned directory.
+    pub files: BTreeSet<String>,
+    /// Folders whose files all belong to the target, e.g. folder references, asset catalogs
+    /// and synchronized folders.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub folders: Vec<String>,
+    /// Files in `folders` that don't belong to the target.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub folder_exceptions: BTreeSet<String>,
+    /// Build settings of the target's default configuration, over the project's, as written;
+    /// see [`XcodeTarget::build_setting`].
+    #[serde(skip)]
+    pub build_settings: BTreeMap<String, String>,
+}
+
+impl XcodeTarget {
+    /// The build setting `name` with the `$(NAME)` and `${NAME}` references in it expanded.
+    /// References to settings the project doesn't set, e.g. ones from `.xcconfig` files, are
+    /// left as they are.
+    pub fn build_setting(&self, name: &str) -> Option<String> {
+        Some(self.expand(self.build_settings.get(name)?))
+    }
+
+    /// Expands the build setting references in `value`, including the `rfc1034identifier`,
+    /// `c99extidentifier`, `lower` and `upper` operators.
+    pub fn expand(&self, value: &str) -> String {
+        let mut value = value.to_string();
+        // Settings refer to each other; stop at cycles.
+        for _ in 0..MAX_BUILD_SETTING_DEPTH {
+            let expanded = BUILD_SETTING_REFERENCE.replace_all(&value, |captures: &Captures| {
+                let reference = captures
+                    .get(1)
+                    .or_else(|| captures.get(2))
+                    .map_or("", |reference| reference.as_str());
+                let (name, operator) = match reference.split_once(':') {
+                    Some((name, operator)) => (name, Some(operator)),
+                    None => (reference, None),
+                };
+                let setting = match name {
+                    "TARGET_NAME" => Some(self.name.clone()),
+                    "PRODUCT_NAME" => Some(
+                        self.build_settings
+                            .get(name)
+                            .cloned()
+                            .unwrap_or_else(|| self.name.clone()),
+                    ),
+                    "SRCROOT" | "PROJECT_DIR" | "SOURCE_ROOT" => Some(String::new()),
+                    _ => self.build_settings.get(name).cloned(),
+                };
+                match (setting, operator) {
+                    (None, _) => captures[0].to_string(),
+                    (Some(setting), Some("rfc1034identifier")) => setting
+                        .chars()
+                        .map(
+                            |c| match c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                                true => c,
+                                false => '-',
+                            },
+                        )
+                        .collect(),
+                    (Some(setting), Some("c99extidentifier")) => setting
+                        .chars()
+                        .map(|c| match c.is_ascii_alphanumeric() {
+                            true => c,
+                            false => '_',
+                        })
+                        .collect(),
+                    (Some(setting), Some("lower")) => setting.to_lowercase(),
+                    (Some(setting), Some("upper")) => setting.to_uppercase(),
+                    (Some(setting), _) => setting,
+                }
+            });
+            if expanded == value {
+                break;
+            }
+            value = expanded.into_owned();
+        }
+        value
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.files.contains(path)
+            || (!self.folder_exceptions.contains(path)
+                && self
+                    .folders
+                    .iter()
+                    .any(|folder| path.starts_with(&format!("{}/", folder))))
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct XcodeProject {
+    /// Path of the `.xcodeproj` relative to the scanned directory.
+    pub path: String,
+    /// Path of the `.xcworkspace` referencing the project, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    pub targets: Vec<XcodeTarget>,
+}
+
+/// Identifies a target across the Xcode projects of a scan.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TargetKey {
+    pub project: String,
+    pub target: String,
+}
+
+/// The Xcode projects found in a scan.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct XcodeProjects {
+    pub projects: Vec<XcodeProject>,
+}
+
+impl XcodeProjects {
+    /// Parses the projects and workspaces among `files`, read with at least
+    /// [`XCODE_PROJECT_GLOBS`]. Projects that can't be parsed are left out, and so are the
+    /// workspaces embedded in each project.
+    pub fn from_files(files: &PlaintextFiles) -> io::Result<Self> {
+        let mut projects = Vec::new();
+        let mut workspaces = Vec::new();
+        for (path, content) in files.matching(XCODE_PROJECT_GLOBS, MAX_PROJECT_FILE_SIZE)? {
+            if let Some(project_path) = path.strip_suffix("/project.pbxproj") {
+                projects.extend(pbxproj::parse_project(project_path, content));
+            } else if let Some(workspace_path) = path.strip_suffix("/contents.xcworkspacedata") {
+                if workspace_path.contains(".xcodeproj/") {
+                    continue;
+                }
+                let referenced = std::str::from_utf8(content)
+                    .ok()
+                    .and_then(|content| workspace::parse_workspace(workspace_path, content));
+                if let Some(referenced) = referenced {
+                    workspaces.push((workspace_path.to_string(), referenced));
+                }
+            }
+        }
+
+        workspaces.sort();
+        for (workspace_path, referenced) in workspaces {
+            for project in &mut projects {
+                if project.workspace.is_none() && referenced.contains(&project.path) {
+                    project.workspace = Some(workspace_path.clone());
+                }
+            }
+        }
+        projects.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Self { projects })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.projects
+            .iter()
+            .all(|project| project.targets.is_empty())
+    }
+
+    pub fn targets(&self) -> impl Iterator<Item = (TargetKey, &XcodeProject, &XcodeTarget)> {
+        self.projects.iter().flat_map(|project| {
+            project.targets.iter().map(move |target| {
+                let key = TargetKey {
+                    project: project.path.clone(),
+                    target: target.name.clone(),
+                };
+                (key, project, target)
+            })
+        })
+    }
+
+    /// Every target but test and extension targets.
+    pub fn default_selection(&self) -> BTreeSet<TargetKey> {
+        self.targets()
+            .filter(|(_, _, target)| target.kind.is_selected_by_default())
+            .map(|(key, _, _)| key)
+            .collect()
+    }
+
+    /// Targets named one of `names`, in any project. Fails on names no project has a target for.
+    pub fn select_by_name(&self, names: &[String]) -> io::Result<BTreeSet<TargetKey>> {
+        let selected: BTreeSet<_> = self
+            .targets()
+            .filter(|(key, _, _)| names.contains(&key.target))
+            .map(|(key, _, _)| key)
+            .collect();
+        let unknown: Vec<_> = names
+            .iter()
+            .filter(|name| !selected.iter().any(|key| &key.target == *name))
+            .map(String::as_str)
+            .collect();
+        match unknown.is_empty() {
+            true => Ok(selected),
+            false => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("No Xcode target named {}", unknown.join(", ")),
+            )),
+        }
+    }
+
+    /// Splits `files` into those to scan and those that only belong to unselected targets.
+    /// Files that belong to no target, e.g. packages and non-Apple code, are always scanned.
+    pub fn filter_files(
+        &self,
+        files: Vec<IndexedFile>,
+        selected: &BTreeSet<TargetKey>,
+    ) -> (Vec<IndexedFile>, Vec<IndexedFile>) {
+        files.into_iter().partition(|file| {
+            let mut owners = self
+                .targets()
+                .filter(|(_, _, target)| target.contains(&file.path))
+                .peekable();
+            owners.peek().is_none() || owners.any(|(key, _, _)| selected.contains(&key))
+        })
+    }
+
+    /// Which of the uploaded `paths` belong to each selected target. Unselected targets are
+    /// listed without files.
+    pub fn membership(&self, paths: &[&str], selected: &BTreeSet<TargetKey>) -> TargetMembership {
+        let targets = self
+            .targets()
+            .map(|(key, project, target)| {
+                let is_selected = selected.contains(&key);
+                TargetMembershipEntry {
+                    project: key.project,
+                    workspace: project.workspace.clone(),
+                    target: key.target,
+                    kind: target.kind,
+                    product_type: target.product_type.clone(),
+                    selected: is_selected,
+                    files: match is_selected {
+                        true => paths
+                            .iter()
+                            .filter(|path| target.contains(path))
+                            .map(|path| path.to_string())
+                            .collect(),
+                        false => Vec::new(),
+                    },
+                }
+            })
+            .collect();
+        TargetMembership { targets }
+    }
+
+    /// Lists every target; `+` marks those selected by default.
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return "No Xcode targets found.\n".to_string();
+        }
+
+        let mut output = String::new();
+        for project in &self.projects {
+            match &project.workspace {
+                Some(workspace) => {
+                    output.push_str(&format!("{} (in {})\n", project.path, workspace))
+                }
+                None => output.push_str(&format!("{}\n", project.path)),
+            }
+            for target in &project.targets {
+                let marker = match target.kind.is_selected_by_default() {
+                    true => "+",
+                    false => "-",
+                };
+                let mut contents = format!("{} file(s)", target.files.len());
+                if !target.folders.is_empty() {
+                    contents.push_str(&format!(", {} folder(s)", target.folders.len()));
+                }
+                output.push_str(&format!(
+                    "  {} {:<24} {:<12} {}\n",
+                    marker,
+                    target.name,
+                    target.kind.to_string(),
+                    contents
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Target membership of the uploaded files, sent along with the upload.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetMembership {
+    pub targets: Vec<TargetMembershipEntry>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetMembershipEntry {
+    pub project: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    pub target: String,
+    pub kind: TargetKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_type: Option<String>,
+    pub selected: bool,
+    pub files: Vec<String>,
+}
+
+impl TargetMembership {
+    pub fn seal(&self, cipher: &FileCipher) -> io::Result<SealedMetadata> {
+        cipher.seal_json(XCODE_TARGETS_PATH, self)
+    }
+}
+
+/// Finds the Xcode projects and workspaces under `dir` and parses them.
+///
+/// They're found with the exclude patterns, roots and [`ScanOptions::source`] of `options`;
+/// see [`PlaintextFiles::read`]. Projects that can't be parsed are left out, and so are the
+/// workspaces embedded in each project.
+pub async fn analyze_xcode_projects(
+    dir: &Path,
+    options: &ScanOptions,
+) -> io::Result<XcodeProjects> {
+    let files =
+        PlaintextFiles::read(dir, XCODE_PROJECT_GLOBS, MAX_PROJECT_FILE_SIZE, options).await?;
+    XcodeProjects::from_files(&files)
+}
+
+/// Asks which targets of `projects` to scan, starting from the default selection.
+pub fn pick_targets(projects: &XcodeProjects) -> io::Result<BTreeSet<TargetKey>> {
+    let picker = TargetPicker::new(projects);
+
+    let terminal = ratatui::init();
+
+    picker.run(terminal)
+}
+
+/// Joins `path` to `dir` lexically, both relative to the scanned directory with `/`
+/// separators. Returns `None` when the result would leave the scanned directory.
+pub(crate) fn join_path(dir: &str, path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = dir.split('/').filter(|part| !part.is_empty()).collect();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
@@ -0,0 +1,233 @@
+use crate::xcode_project::{join_path, TargetKind, XcodeProject, XcodeTarget};
+use plist::{Dictionary, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Cursor;
+
+/// Build phases whose files are part of the target's source; frameworks and copied products
+/// are binaries that aren't in the repository.
+const SOURCE_BUILD_PHASES: [&str; 3] = [
+    "PBXSourcesBuildPhase",
+    "PBXResourcesBuildPhase",
+    "PBXHeadersBuildPhase",
+];
+
+/// Targets of the `project.pbxproj` of the Xcode project at `project_path`, e.g.
+/// `ios/App.xcodeproj`, with the files and folders that belong to each of them.
+///
+/// Paths are resolved through the project's group tree. Files outside the scanned directory
+/// or relative to SDKs and build products are left out.
+pub fn parse_project(project_path: &str, content: &[u8]) -> Option<XcodeProject> {
+    let root = Value::from_reader_ascii(Cursor::new(content))
+        .ok()?
+        .into_dictionary()?;
+    let objects = root.get("objects")?.as_dictionary()?;
+    let project = object(objects, root.get("rootObject")?.as_string()?)?;
+
+    let project_dir = match project_path.rsplit_once('/') {
+        Some((dir, _)) => dir,
+        None => "",
+    };
+    let project_dir = match project.get("projectDirPath").and_then(Value::as_string) {
+        Some(path) if !path.is_empty() => join_path(project_dir, path)?,
+        _ => project_dir.to_string(),
+    };
+    let mut resolver = PathResolver {
+        objects,
+        project_dir: &project_dir,
+        paths: HashMap::new(),
+        build_settings: build_settings(objects, project),
+    };
+    if let Some(main_group) = project.get("mainGroup").and_then(Value::as_string) {
+        resolver.resolve(main_group, &project_dir);
+    }
+
+    let targets = strings(project, "targets")
+        .filter_map(|id| Some((id, object(objects, id)?)))
+        .filter_map(|(id, target)| resolver.target(id, target))
+        .collect();
+
+    Some(XcodeProject {
+        path: project_path.to_string(),
+        workspace: None,
+        targets,
+    })
+}
+
+struct PathResolver<'a> {
+    objects: &'a Dictionary,
+    project_dir: &'a str,
+    /// Paths of the files and groups reached from the main group, by object ID.
+    paths: HashMap<&'a str, String>,
+    /// The project's build settings, which targets inherit.
+    build_settings: BTreeMap<String, String>,
+}
+
+impl<'a> PathResolver<'a> {
+    /// Records the path of the file or group `id` and its children, relative to the scanned
+    /// directory; `parent_dir` is the path of the enclosing group.
+    fn resolve(&mut self, id: &'a str, parent_dir: &str) {
+        if self.paths.contains_key(id) {
+            return;
+        }
+        let Some(item) = object(self.objects, id) else {
+            return;
+        };
+        let base = match item.get("sourceTree").and_then(Value::as_string) {
+            Some("<group>") | None => parent_dir,
+            Some("SOURCE_ROOT") => self.project_dir,
+            _ => return,
+        };
+        let path = match item.get("path").and_then(Value::as_string) {
+            Some(path) => match join_path(base, path) {
+                Some(path) => path,
+                None => return,
+            },
+            None => base.to_string(),
+        };
+
+        for child in strings(item, "children") {
+            self.resolve(child, &path);
+        }
+        self.paths.insert(id, path);
+    }
+
+    fn target(&self, id: &str, target: &Dictionary) -> Option<XcodeTarget> {
+        let product_type = target
+            .get("productType")
+            .and_then(Value::as_string)
+            .map(str::to_string);
+        let mut xcode_target = XcodeTarget {
+            name: target.get("name")?.as_string()?.to_string(),
+            kind: TargetKind::from_product_type(product_type.as_deref()),
+            product_type,
+            files: BTreeSet::new(),
+            folders: Vec::new(),
+            folder_exceptions: BTreeSet::new(),
+            build_settings: self.build_settings.clone(),
+        };
+        for (name, value) in build_settings(self.objects, target) {
+            let inherited = self.build_settings.get(&name).map_or("", String::as_str);
+            let value = value.replace("$(inherited)", inherited).trim().to_string();
+            xcode_target.build_settings.insert(name, value);
+        }
+
+        for phase in strings(target, "buildPhases").filter_map(|id| object(self.objects, id)) {
+            let isa = phase.get("isa").and_then(Value::as_string);
+            if !isa.is_some_and(|isa| SOURCE_BUILD_PHASES.contains(&isa)) {
+                continue;
+            }
+            for build_file in strings(phase, "files").filter_map(|id| object(self.objects, id)) {
+                if let Some(file_ref) = build_file.get("fileRef").and_then(Value::as_string) {
+                    self.add_member(&mut xcode_target, file_ref);
+                }
+            }
+        }
+
+        // Xcode 16 folders whose files all belong to the target, except those listed in the
+        // folder's exception sets for this target.
+        for group_id in strings(target, "fileSystemSynchronizedGroups") {
+            let (Some(group), Some(path)) =
+                (object(self.objects, group_id), self.paths.get(group_id))
+            else {
+                continue;
+            };
+            xcode_target.folders.push(path.clone());
+            for exception in strings(group, "exceptions").filter_map(|id| object(self.objects, id))
+            {
+                if exception.get("target").and_then(Value::as_string) != Some(id) {
+                    continue;
+                }
+                for excluded in strings(exception, "membershipExceptions") {
+                    if let Some(excluded) = join_path(path, excluded) {
+                        xcode_target.folder_exceptions.insert(excluded);
+                    }
+                }
+            }
+        }
+
+        Some(xcode_target)
+    }
+
+    /// Adds the file `id` to `target`; localized variant groups add each localization, and
+    /// folder references and bundles like asset catalogs add the whole folder.
+    fn add_member(&self, target: &mut XcodeTarget, id: &str) {
+        let (Some(item), Some(path)) = (object(self.objects, id), self.paths.get(id)) else {
+            return;
+        };
+        if item.contains_key("children") {
+            for child in strings(item, "children") {
+                self.add_member(target, child);
+            }
+            return;
+        }
+
+        let file_type = item
+            .get("lastKnownFileType")
+            .or_else(|| item.get("explicitFileType"))
+            .and_then(Value::as_string)
+            .unwrap_or_default();
+        match file_type.starts_with("folder") || file_type.starts_with("wrapper") {
+            true => target.folders.push(path.clone()),
+            false => {
+                target.files.insert(path.clone());
+            }
+        }
+    }
+}
+
+/// Build settings of the default configuration of the configuration list of `item`, a project
+/// or a target, or of its first configuration. Lists are joined with spaces, as in Xcode.
+fn build_settings(objects: &Dictionary, item: &Dictionary) -> BTreeMap<String, String> {
+    let Some(list) = item
+        .get("buildConfigurationList")
+        .and_then(Value::as_string)
+        .and_then(|id| object(objects, id))
+    else {
+        return BTreeMap::new();
+    };
+    let configurations: Vec<&Dictionary> = strings(list, "buildConfigurations")
+        .filter_map(|id| object(objects, id))
+        .collect();
+    let default_name = list
+        .get("defaultConfigurationName")
+        .and_then(Value::as_string);
+    let configuration = configurations
+        .iter()
+        .find(|configuration| configuration.get("name").and_then(Value::as_string) == default_name)
+        .or(configurations.first());
+
+    configuration
+        .and_then(|configuration| configuration.get("buildSettings"))
+        .and_then(Value::as_dictionary)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                Value::Array(_) => {
+                    let values: Vec<&str> = value
+                        .as_array()?
+                        .iter()
+                        .filter_map(Value::as_string)
+                        .collect();
+                    values.join(" ")
+                }
+                _ => return None,
+            };
+            Some((name.clone(), value))
+        })
+        .collect()
+}
+
+fn object<'a>(objects: &'a Dictionary, id: &str) -> Option<&'a Dictionary> {
+    objects.get(id)?.as_dictionary()
+}
+
+/// The strings of the array at `key`, e.g. the object IDs of a group's children.
+fn strings<'a>(item: &'a Dictionary, key: &str) -> impl Iterator<Item = &'a str> {
+    item.get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_string)
+}
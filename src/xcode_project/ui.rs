@@ -0,0 +1,217 @@
+use crate::terminal::finalize_terminal;
+use crate::xcode_project::{TargetKey, XcodeProjects};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{self, Color, Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Cell, HighlightSpacing, Paragraph, Row, Table, TableState},
+    DefaultTerminal, Frame,
+};
+use std::collections::BTreeSet;
+use std::io;
+use style::palette::tailwind;
+use unicode_width::UnicodeWidthStr;
+
+const INFO_TEXT: &str = "(Enter) Submit | (↑) move up | (↓) move down | (Space) Select / Unselect";
+
+#[derive(Debug)]
+struct TableColors {
+    buffer_bg: Color,
+    header_bg: Color,
+    header_fg: Color,
+    row_fg: Color,
+    selected_style_fg: Color,
+    normal_row_color: Color,
+    alt_row_color: Color,
+    footer_border_color: Color,
+}
+
+impl TableColors {
+    const fn new(color: &tailwind::Palette) -> Self {
+        Self {
+            buffer_bg: tailwind::NEUTRAL.c950,
+            header_bg: color.c900,
+            header_fg: tailwind::NEUTRAL.c200,
+            row_fg: tailwind::NEUTRAL.c200,
+            selected_style_fg: color.c400,
+            normal_row_color: tailwind::NEUTRAL.c950,
+            alt_row_color: tailwind::NEUTRAL.c900,
+            footer_border_color: color.c400,
+        }
+    }
+}
+
+struct Data {
+    is_selected: bool,
+    key: TargetKey,
+    kind: String,
+}
+
+pub struct TargetPicker {
+    state: TableState,
+    items: Vec<Data>,
+    name_width: u16,
+    colors: TableColors,
+}
+
+impl TargetPicker {
+    pub fn new(projects: &XcodeProjects) -> Self {
+        let defaults = projects.default_selection();
+        let items: Vec<Data> = projects
+            .targets()
+            .map(|(key, _, target)| Data {
+                is_selected: defaults.contains(&key),
+                key,
+                kind: target.kind.to_string(),
+            })
+            .collect();
+
+        #[allow(clippy::cast_possible_truncation)]
+        let name_width = items
+            .iter()
+            .map(|data| data.key.target.width())
+            .max()
+            .unwrap_or(0) as u16;
+
+        Self {
+            state: TableState::default().with_selected(0),
+            items,
+            name_width,
+            colors: TableColors::new(&tailwind::NEUTRAL),
+        }
+    }
+
+    fn select(&mut self) {
+        let i = self.state.selected().unwrap_or(0);
+        self.items[i].is_selected = !self.items[i].is_selected;
+    }
+
+    fn next(&mut self) {
+        let i = match self.state.selected() {
+            Some(i) if i < self.items.len() - 1 => i + 1,
+            _ => 0,
+        };
+        self.state.select(Some(i));
+    }
+
+    fn previous(&mut self) {
+        let i = match self.state.selected() {
+            Some(0) | None => self.items.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.state.select(Some(i));
+    }
+
+    fn selected_count(&self) -> usize {
+        self.items.iter().filter(|data| data.is_selected).count()
+    }
+
+    pub fn run(mut self, mut terminal: DefaultTerminal) -> io::Result<BTreeSet<TargetKey>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match key.code {
+                        KeyCode::Enter => {
+                            finalize_terminal(&mut terminal)?;
+                            return Ok(self
+                                .items
+                                .into_iter()
+                                .filter(|data| data.is_selected)
+                                .map(|data| data.key)
+                                .collect());
+                        }
+                        KeyCode::Down => self.next(),
+                        KeyCode::Up => self.previous(),
+                        KeyCode::Char(' ') => self.select(),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(4)]);
+        let rects = vertical.split(frame.area());
+
+        self.render_table(frame, rects[0]);
+        self.render_footer(frame, rects[1]);
+    }
+
+    fn render_table(&mut self, frame: &mut Frame, area: Rect) {
+        let header_style = Style::default()
+            .fg(self.colors.header_fg)
+            .bg(self.colors.header_bg);
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(self.colors.selected_style_fg);
+
+        let header = ["", "Target", "Kind", "Project"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(header_style)
+            .height(1);
+        let rows = self.items.iter().enumerate().map(|(i, data)| {
+            let color = match i % 2 {
+                0 => self.colors.normal_row_color,
+                _ => self.colors.alt_row_color,
+            };
+            let marker = if data.is_selected { "+" } else { "-" };
+            [
+                marker,
+                data.key.target.as_str(),
+                data.kind.as_str(),
+                data.key.project.as_str(),
+            ]
+            .into_iter()
+            .map(|content| Cell::from(Text::from(content.to_string())))
+            .collect::<Row>()
+            .style(Style::new().fg(self.colors.row_fg).bg(color))
+            .height(1)
+        });
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Min(self.name_width + 1),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style)
+        .highlight_symbol(" █ ")
+        .bg(self.colors.buffer_bg)
+        .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(t, area, &mut self.state);
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let selection_line = match self.selected_count() {
+            0 => Line::from("Nothing selected - only files outside Xcode targets will be scanned"),
+            count => Line::from(format!(
+                "Scanning {} of {} targets",
+                count,
+                self.items.len()
+            )),
+        };
+
+        let info_footer = Paragraph::new(vec![Line::from(INFO_TEXT), selection_line])
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .centered()
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(info_footer, area);
+    }
+}
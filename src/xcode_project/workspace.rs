@@ -0,0 +1,61 @@
+use crate::xcode_project::join_path;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// Xcode projects referenced by the `contents.xcworkspacedata` of the workspace at
+/// `workspace_path`, e.g. `ios/App.xcworkspace`, relative to the scanned directory.
+///
+/// `group:` locations are relative to the enclosing group and `container:` locations to the
+/// directory holding the workspace; absolute locations are left out.
+pub fn parse_workspace(workspace_path: &str, content: &str) -> Option<Vec<String>> {
+    let container = match workspace_path.rsplit_once('/') {
+        Some((dir, _)) => dir,
+        None => "",
+    };
+    let mut reader = Reader::from_str(content);
+    // Directory of each open `<Group>`, innermost last.
+    let mut groups = vec![container.to_string()];
+    let mut projects = Vec::new();
+
+    loop {
+        let (element, is_empty) = match reader.read_event().ok()? {
+            Event::Start(element) => (element, false),
+            Event::Empty(element) => (element, true),
+            Event::End(element) if element.name().as_ref() == b"Group" => {
+                if groups.len() > 1 {
+                    groups.pop();
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        let group_dir = groups.last().cloned().unwrap_or_default();
+        let path = location(&element).and_then(|location| match location.split_once(':') {
+            Some(("group", path)) => join_path(&group_dir, path),
+            Some(("container", path)) => join_path(container, path),
+            _ => None,
+        });
+
+        match element.name().as_ref() {
+            b"Group" if !is_empty => groups.push(path.unwrap_or(group_dir)),
+            b"FileRef" => {
+                if let Some(path) = path.filter(|path| path.ends_with(".xcodeproj")) {
+                    projects.push(path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(projects)
+}
+
+fn location(element: &BytesStart) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.as_ref() == b"location")
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
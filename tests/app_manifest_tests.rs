@@ -0,0 +1,520 @@
+use catch_cli::app_manifest::{
+    analyze_app_manifests, parse_app_manifest, AppManifest, AppManifestKind, PrivacyEntry,
+    PrivacyEntryKind,
+};
+use catch_cli::code_reader::ScanOptions;
+use catch_cli::code_uploader::UploadMetadata;
+use catch_cli::cryptography::{EncryptionScheme, FileCipher};
+use catch_cli::dependency_summary::gradle::parse_android_settings;
+use catch_cli::upload_manifest::UploadManifest;
+use std::fs;
+use tempfile::tempdir;
+
+const ANDROID_MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.app">
+    <uses-sdk android:minSdkVersion="24" android:targetSdkVersion="34" />
+    <uses-permission android:name="android.permission.INTERNET" />
+    <uses-permission android:name="android.permission.CAMERA" />
+    <uses-permission-sdk-23 android:name="android.permission.INTERNET" />
+    <uses-feature android:name="android.hardware.camera" android:required="false" />
+    <application android:label="@string/app_name">
+        <activity android:name=".MainActivity" android:exported="true">
+            <intent-filter>
+                <action android:name="android.intent.action.VIEW" />
+                <data android:scheme="exampleapp" android:host="open" />
+                <data android:scheme="https" android:host="example.com" />
+            </intent-filter>
+        </activity>
+    </application>
+</manifest>
+"#;
+
+const INFO_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>$(PRODUCT_BUNDLE_IDENTIFIER)</string>
+    <key>MinimumOSVersion</key>
+    <string>15.0</string>
+    <key>NSCameraUsageDescription</key>
+    <string>Scan receipts</string>
+    <key>NSLocationWhenInUseUsageDescription</key>
+    <string>Find stores nearby</string>
+    <key>CFBundleURLTypes</key>
+    <array>
+        <dict>
+            <key>CFBundleURLSchemes</key>
+            <array><string>exampleapp</string></array>
+        </dict>
+    </array>
+    <key>UIBackgroundModes</key>
+    <array><string>remote-notification</string></array>
+    <key>NSAppTransportSecurity</key>
+    <dict>
+        <key>NSAllowsArbitraryLoads</key>
+        <true/>
+    </dict>
+</dict>
+</plist>
+"#;
+
+const ENTITLEMENTS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>aps-environment</key>
+    <string>development</string>
+    <key>com.apple.developer.associated-domains</key>
+    <array>
+        <string>applinks:example.com</string>
+        <string>webcredentials:example.com</string>
+    </array>
+    <key>com.apple.developer.applesignin</key>
+    <array><string>Default</string></array>
+    <key>com.apple.developer.healthkit</key>
+    <true/>
+    <key>com.apple.security.app-sandbox</key>
+    <false/>
+</dict>
+</plist>
+"#;
+
+const PRIVACY_MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>NSPrivacyTracking</key>
+    <false/>
+    <key>NSPrivacyTrackingDomains</key>
+    <array><string>tracker.example.com</string></array>
+    <key>NSPrivacyCollectedDataTypes</key>
+    <array>
+        <dict>
+            <key>NSPrivacyCollectedDataType</key>
+            <string>NSPrivacyCollectedDataTypeEmailAddress</string>
+            <key>NSPrivacyCollectedDataTypeLinked</key>
+            <true/>
+            <key>NSPrivacyCollectedDataTypeTracking</key>
+            <false/>
+            <key>NSPrivacyCollectedDataTypePurposes</key>
+            <array><string>NSPrivacyCollectedDataTypePurposeAppFunctionality</string></array>
+        </dict>
+    </array>
+    <key>NSPrivacyAccessedAPITypes</key>
+    <array>
+        <dict>
+            <key>NSPrivacyAccessedAPIType</key>
+            <string>NSPrivacyAccessedAPICategoryUserDefaults</string>
+            <key>NSPrivacyAccessedAPITypeReasons</key>
+            <array><string>CA92.1</string></array>
+        </dict>
+    </array>
+</dict>
+</plist>
+"#;
+
+#[test]
+fn test_parse_android_manifest() {
+    let manifest = parse_app_manifest(
+        "app/src/main/AndroidManifest.xml",
+        ANDROID_MANIFEST.as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(manifest.kind, AppManifestKind::AndroidManifest);
+    assert_eq!(manifest.app_id.as_deref(), Some("com.example.app"));
+    assert_eq!(manifest.min_sdk.as_deref(), Some("24"));
+    assert_eq!(manifest.target_sdk.as_deref(), Some("34"));
+    assert_eq!(
+        manifest.permissions,
+        ["android.permission.INTERNET", "android.permission.CAMERA"]
+    );
+    assert_eq!(manifest.capabilities, ["android.hardware.camera"]);
+    assert_eq!(manifest.url_schemes, ["exampleapp", "https"]);
+
+    assert!(parse_app_manifest("AndroidManifest.xml", b"<resources/>").is_none());
+    assert!(parse_app_manifest("AndroidManifest.xml", b"<manifest><unclosed").is_none());
+}
+
+#[test]
+fn test_parse_info_plist() {
+    let manifest = parse_app_manifest("ios/App/Info.plist", INFO_PLIST.as_bytes()).unwrap();
+
+    assert_eq!(manifest.kind, AppManifestKind::InfoPlist);
+    assert_eq!(
+        manifest.app_id.as_deref(),
+        Some("$(PRODUCT_BUNDLE_IDENTIFIER)")
+    );
+    assert_eq!(manifest.min_sdk.as_deref(), Some("15.0"));
+    assert_eq!(
+        manifest.permissions,
+        [
+            "NSCameraUsageDescription",
+            "NSLocationWhenInUseUsageDescription"
+        ]
+    );
+    assert_eq!(manifest.url_schemes, ["exampleapp"]);
+    assert_eq!(
+        manifest.capabilities,
+        ["background: remote-notification", "NSAllowsArbitraryLoads"]
+    );
+
+    // Binary plists are read the same way.
+    let value = plist::Value::from_reader_xml(INFO_PLIST.as_bytes()).unwrap();
+    let mut binary = Vec::new();
+    value.to_writer_binary(&mut binary).unwrap();
+    assert_eq!(
+        parse_app_manifest("ios/App/Info.plist", &binary)
+            .unwrap()
+            .permissions,
+        manifest.permissions
+    );
+
+    let google_service_info = r#"<plist version="1.0"><dict>
+        <key>BUNDLE_ID</key><string>com.example.app</string>
+    </dict></plist>"#;
+    assert!(parse_app_manifest(
+        "ios/App/GoogleService-Info.plist",
+        google_service_info.as_bytes()
+    )
+    .is_none());
+}
+
+#[test]
+fn test_parse_entitlements_and_privacy_manifest() {
+    let entitlements =
+        parse_app_manifest("ios/App/App.entitlements", ENTITLEMENTS.as_bytes()).unwrap();
+    assert_eq!(entitlements.kind, AppManifestKind::Entitlements);
+    assert_eq!(
+        entitlements.capabilities,
+        [
+            "aps-environment: development",
+            "com.apple.developer.associated-domains: applinks:example.com, webcredentials:example.com",
+            "com.apple.developer.applesignin: Default",
+            "com.apple.developer.healthkit",
+        ]
+    );
+
+    let privacy =
+        parse_app_manifest("ios/App/PrivacyInfo.xcprivacy", PRIVACY_MANIFEST.as_bytes()).unwrap();
+    assert_eq!(privacy.kind, AppManifestKind::PrivacyManifest);
+    assert_eq!(
+        privacy.privacy,
+        [
+            PrivacyEntry {
+                kind: PrivacyEntryKind::Tracking,
+                value: "false".to_string(),
+                details: vec![],
+            },
+            PrivacyEntry {
+                kind: PrivacyEntryKind::TrackingDomain,
+                value: "tracker.example.com".to_string(),
+                details: vec![],
+            },
+            PrivacyEntry {
+                kind: PrivacyEntryKind::CollectedData,
+                value: "NSPrivacyCollectedDataTypeEmailAddress".to_string(),
+                details: vec![
+                    "linked".to_string(),
+                    "NSPrivacyCollectedDataTypePurposeAppFunctionality".to_string()
+                ],
+            },
+            PrivacyEntry {
+                kind: PrivacyEntryKind::AccessedApi,
+                value: "NSPrivacyAccessedAPICategoryUserDefaults".to_string(),
+                details: vec!["CA92.1".to_string()],
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_analyze_app_manifests() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("android/app/src/main")).unwrap();
+    fs::create_dir_all(dir.path().join("ios/App")).unwrap();
+    fs::create_dir_all(dir.path().join("ios/Pods/Firebase")).unwrap();
+    fs::write(
+        dir.path().join("android/app/src/main/AndroidManifest.xml"),
+        ANDROID_MANIFEST,
+    )
+    .unwrap();
+    fs::write(dir.path().join("ios/App/Info.plist"), INFO_PLIST).unwrap();
+    fs::write(dir.path().join("ios/App/Settings.plist"), INFO_PLIST).unwrap();
+    fs::write(
+        dir.path().join("ios/Pods/Firebase/PrivacyInfo.xcprivacy"),
+        PRIVACY_MANIFEST,
+    )
+    .unwrap();
+
+    let report = analyze_app_manifests(dir.path(), &ScanOptions::default())
+        .await
+        .unwrap();
+    let mut paths: Vec<_> = report
+        .manifests
+        .iter()
+        .map(|manifest| manifest.path.as_str())
+        .collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            "android/app/src/main/AndroidManifest.xml",
+            "ios/App/Info.plist"
+        ]
+    );
+
+    let rendered = report.render();
+    assert!(rendered.contains("ios/App/Info.plist (Info.plist)\n"));
+    assert!(rendered.contains("  Permissions:     android.permission.CAMERA\n"));
+    assert!(rendered.contains("  URL schemes:     exampleapp\n"));
+
+    let cipher = FileCipher::generate(EncryptionScheme::Aes256Gcm);
+    let metadata = UploadMetadata {
+        app_manifests: Some(report.seal(&cipher).unwrap()),
+        ..Default::default()
+    };
+    let manifest = UploadManifest::new("session-1", "integration-id", &[]).with_metadata(&metadata);
+    assert!(manifest.app_manifests_sha256.is_some());
+    assert!(manifest.dependency_summary_sha256.is_none());
+    let json = serde_json::to_value(&metadata).unwrap();
+    assert!(json.get("appManifests").is_some());
+    assert!(json.get("dependencySummary").is_none());
+}
+
+const PROJECT: &str = r#"// !$*UTF8*$!
+{
+	archiveVersion = 1;
+	objectVersion = 77;
+	objects = {
+		G000 = {isa = PBXGroup; children = (); sourceTree = "<group>"; };
+		T001 /* App */ = {
+			isa = PBXNativeTarget;
+			buildConfigurationList = L002;
+			buildPhases = ();
+			name = App;
+			productType = "com.apple.product-type.application";
+		};
+		T002 /* Widget */ = {
+			isa = PBXNativeTarget;
+			buildConfigurationList = L003;
+			buildPhases = ();
+			name = Widget;
+			productType = "com.apple.product-type.app-extension";
+		};
+		T003 /* AppTests */ = {
+			isa = PBXNativeTarget;
+			buildConfigurationList = L003;
+			buildPhases = ();
+			name = AppTests;
+			productType = "com.apple.product-type.bundle.unit-test";
+		};
+		R001 /* Project object */ = {
+			isa = PBXProject;
+			buildConfigurationList = L001;
+			mainGroup = G000;
+			projectDirPath = "";
+			targets = (T001, T002, T003);
+		};
+		L001 = {isa = XCConfigurationList; buildConfigurations = (C001); defaultConfigurationName = Release; };
+		L002 = {isa = XCConfigurationList; buildConfigurations = (C002, C003); defaultConfigurationName = Release; };
+		L003 = {isa = XCConfigurationList; buildConfigurations = (C004); defaultConfigurationName = Release; };
+		C001 = {
+			isa = XCBuildConfiguration;
+			buildSettings = {
+				BUNDLE_PREFIX = com.example;
+				IPHONEOS_DEPLOYMENT_TARGET = 15.0;
+				MACOSX_DEPLOYMENT_TARGET = 13.0;
+				SDKROOT = iphoneos;
+			};
+			name = Release;
+		};
+		C002 = {
+			isa = XCBuildConfiguration;
+			buildSettings = {
+				INFOPLIST_FILE = "$(SRCROOT)/App/Info.plist";
+				PRODUCT_BUNDLE_IDENTIFIER = "$(BUNDLE_PREFIX).debug";
+			};
+			name = Debug;
+		};
+		C003 = {
+			isa = XCBuildConfiguration;
+			buildSettings = {
+				INFOPLIST_FILE = "$(SRCROOT)/App/Info.plist";
+				PRODUCT_BUNDLE_IDENTIFIER = "$(BUNDLE_PREFIX).$(PRODUCT_NAME:rfc1034identifier)";
+				PRODUCT_NAME = "My App";
+			};
+			name = Release;
+		};
+		C004 = {
+			isa = XCBuildConfiguration;
+			buildSettings = {
+				GENERATE_INFOPLIST_FILE = YES;
+				INFOPLIST_KEY_NSLocationWhenInUseUsageDescription = "Show nearby stores";
+				IPHONEOS_DEPLOYMENT_TARGET = 17.0;
+				PRODUCT_BUNDLE_IDENTIFIER = "$(BUNDLE_PREFIX).app.$(TARGET_NAME:lower)";
+			};
+			name = Release;
+		};
+	};
+	rootObject = R001 /* Project object */;
+}
+"#;
+
+const APP_INFO_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>$(PRODUCT_BUNDLE_IDENTIFIER)</string>
+    <key>CFBundleURLTypes</key>
+    <array>
+        <dict>
+            <key>CFBundleURLSchemes</key>
+            <array>
+                <string>$(PRODUCT_BUNDLE_IDENTIFIER)</string>
+                <string>$(APP_SCHEME)</string>
+            </array>
+        </dict>
+    </array>
+</dict>
+</plist>
+"#;
+
+const APP_BUILD_SCRIPT: &str = r#"
+android {
+    namespace = "com.example.app"
+    compileSdk = 35
+
+    defaultConfig {
+        applicationId = "com.example.app.prod"
+        minSdk = 26
+        targetSdk = 35
+    }
+
+    productFlavors {
+        create("staging") {
+            applicationId = "com.example.app.staging"
+        }
+    }
+}
+"#;
+
+const LIBRARY_MANIFEST: &str = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android">
+    <application>
+        <activity android:name=".RedirectActivity">
+            <intent-filter>
+                <data android:scheme="${appAuthRedirectScheme}" />
+            </intent-filter>
+        </activity>
+    </application>
+</manifest>
+"#;
+
+fn manifest<'a>(manifests: &'a [AppManifest], path: &str, target: Option<&str>) -> &'a AppManifest {
+    manifests
+        .iter()
+        .find(|manifest| manifest.path == path && manifest.target.as_deref() == target)
+        .unwrap_or_else(|| panic!("no manifest {} for {:?}", path, target))
+}
+
+#[test]
+fn test_parse_android_settings() {
+    let settings = parse_android_settings(APP_BUILD_SCRIPT);
+    assert_eq!(settings.get("namespace").unwrap(), "com.example.app");
+    assert_eq!(
+        settings.get("applicationId").unwrap(),
+        "com.example.app.prod"
+    );
+    assert_eq!(settings.get("minSdk").unwrap(), "26");
+    assert_eq!(settings.get("targetSdk").unwrap(), "35");
+    assert!(!settings.contains_key("compileSdk"));
+    assert_eq!(settings.len(), 4);
+
+    let groovy = parse_android_settings(
+        "android {\n    namespace 'com.example.core'\n    minSdkVersion 21\n}\n",
+    );
+    assert_eq!(groovy.get("namespace").unwrap(), "com.example.core");
+    assert_eq!(groovy.get("minSdk").unwrap(), "21");
+}
+
+#[tokio::test]
+async fn test_resolve_app_manifests_from_build_files() {
+    let dir = tempdir().unwrap();
+    for subdir in [
+        "android/app/src/main",
+        "android/core/src/main",
+        "ios/App.xcodeproj",
+        "ios/App",
+    ] {
+        fs::create_dir_all(dir.path().join(subdir)).unwrap();
+    }
+    fs::write(
+        dir.path().join("android/app/src/main/AndroidManifest.xml"),
+        ANDROID_MANIFEST,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("android/app/build.gradle.kts"),
+        APP_BUILD_SCRIPT,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("android/core/src/main/AndroidManifest.xml"),
+        LIBRARY_MANIFEST,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("android/core/build.gradle"),
+        "android {\n    namespace 'com.example.core'\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("ios/App.xcodeproj/project.pbxproj"),
+        PROJECT,
+    )
+    .unwrap();
+    fs::write(dir.path().join("ios/App/Info.plist"), APP_INFO_PLIST).unwrap();
+
+    let report = analyze_app_manifests(dir.path(), &ScanOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.manifests.len(), 4);
+
+    // The build script wins over the manifest's package and SDK levels.
+    let app = manifest(
+        &report.manifests,
+        "android/app/src/main/AndroidManifest.xml",
+        None,
+    );
+    assert_eq!(app.app_id.as_deref(), Some("com.example.app.prod"));
+    assert_eq!(app.namespace.as_deref(), Some("com.example.app"));
+    assert_eq!(app.min_sdk.as_deref(), Some("26"));
+    assert_eq!(app.target_sdk.as_deref(), Some("35"));
+    assert!(app.unresolved.is_empty());
+
+    let library = manifest(
+        &report.manifests,
+        "android/core/src/main/AndroidManifest.xml",
+        None,
+    );
+    assert_eq!(library.app_id.as_deref(), Some("com.example.core"));
+    assert_eq!(library.unresolved, ["${appAuthRedirectScheme}"]);
+
+    // Settings of the default configuration, over the project's.
+    let ios_app = manifest(&report.manifests, "ios/App/Info.plist", Some("App"));
+    assert_eq!(ios_app.app_id.as_deref(), Some("com.example.My-App"));
+    assert_eq!(ios_app.min_sdk.as_deref(), Some("15.0"));
+    assert_eq!(ios_app.url_schemes, ["com.example.My-App", "$(APP_SCHEME)"]);
+    assert_eq!(ios_app.unresolved, ["$(APP_SCHEME)"]);
+
+    // Generated from build settings; test targets are left out.
+    let widget = manifest(&report.manifests, "ios/App.xcodeproj", Some("Widget"));
+    assert_eq!(widget.kind, AppManifestKind::InfoPlist);
+    assert_eq!(widget.app_id.as_deref(), Some("com.example.app.widget"));
+    assert_eq!(widget.min_sdk.as_deref(), Some("17.0"));
+    assert_eq!(widget.permissions, ["NSLocationWhenInUseUsageDescription"]);
+
+    let rendered = report.render();
+    assert!(rendered.contains("  Target:          Widget\n"));
+    assert!(rendered.contains("  Unresolved:      $(APP_SCHEME)\n"));
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use catch_cli::audit_log::history::{render_history, render_session};
+use catch_cli::audit_log::{AuditEvent, AuditLog, UploadRecord};
+use catch_cli::upload_manifest::UploadManifestEntry;
+use tempfile::tempdir;
+
+fn upload_record(session_id: &str) -> UploadRecord {
+    UploadRecord {
+        git_head: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+        files: vec![UploadManifestEntry {
+            path: "ios/AppDelegate.swift".to_string(),
+            size: 42,
+            plaintext_sha256: "aa".repeat(32),
+            ciphertext_sha256: "bb".repeat(32),
+        }],
+        ..common::upload_record(session_id)
+    }
+}
+
+#[test]
+fn test_empty_audit_log() {
+    let dir = tempdir().unwrap();
+
+    let entries = AuditLog::new(dir.path()).entries().unwrap();
+
+    assert!(entries.is_empty());
+    assert_eq!(render_history(&entries), "No uploads recorded yet.\n");
+}
+
+#[test]
+fn test_append_and_read_entries() {
+    let dir = tempdir().unwrap();
+    let log = AuditLog::new(&dir.path().join("state"));
+
+    log.append(AuditEvent::Upload(upload_record("session-1")))
+        .unwrap();
+    log.append(AuditEvent::Upload(upload_record("session-2")))
+        .unwrap();
+
+    let entries = log.entries().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].event.session_id(), "session-1");
+    assert_eq!(
+        entries[1].event,
+        AuditEvent::Upload(upload_record("session-2"))
+    );
+
+    let content = std::fs::read_to_string(log.path()).unwrap();
+    assert_eq!(content.lines().count(), 2);
+    assert!(content
+        .lines()
+        .all(|line| line.contains(r#""event":"upload""#)));
+}
+
+#[test]
+fn test_unreadable_lines_are_skipped() {
+    let dir = tempdir().unwrap();
+    let log = AuditLog::new(dir.path());
+    log.append(AuditEvent::Upload(upload_record("session-1")))
+        .unwrap();
+
+    let mut content = std::fs::read_to_string(log.path()).unwrap();
+    content.push_str("{ truncated\n");
+    std::fs::write(log.path(), content).unwrap();
+    log.append(AuditEvent::Upload(upload_record("session-2")))
+        .unwrap();
+
+    let entries = log.entries().unwrap();
+    assert_eq!(entries.len(), 2);
+}
+
+#[test]
+fn test_render_history_and_session() {
+    let dir = tempdir().unwrap();
+    let log = AuditLog::new(dir.path());
+    log.append(AuditEvent::Upload(upload_record("session-1")))
+        .unwrap();
+    let entries = log.entries().unwrap();
+
+    let history = render_history(&entries);
+    assert!(history.starts_with("TIMESTAMP"));
+    assert!(history.contains("session-1"));
+    assert!(history.contains("catch-org/catch-app"));
+
+    let session = render_session(&entries, "session-1");
+    assert!(session.contains("Git HEAD:        0123456789abcdef0123456789abcdef01234567"));
+    assert!(session.contains("SHA256:ff:04:e8"));
+    assert!(session.contains("ios/AppDelegate.swift  42 bytes"));
+    assert!(!session.contains("Changes since"));
+
+    log.append(AuditEvent::Upload(UploadRecord {
+        base_session_id: Some("session-1".to_string()),
+        deleted_files: vec!["ios/Old.swift".to_string()],
+        ..upload_record("session-2")
+    }))
+    .unwrap();
+    let session = render_session(&log.entries().unwrap(), "session-2");
+    assert!(session.contains("Changes since:   session-1"));
+    assert!(session.contains("Deleted files (1):\n    ios/Old.swift\n"));
+
+    assert_eq!(
+        render_session(&entries, "unknown"),
+        "No uploads recorded for session unknown.\n"
+    );
+}
@@ -1,3 +1,4 @@
+use catch_cli::api_client::session_status_entity::StepStatus;
 use catch_cli::code_analyzer::check_rcp_status;
 use serde_json::json;
 
@@ -37,7 +38,7 @@ async fn test_check_rcp_status_success() {
 
     assert!(result.is_ok());
     let candidates_result = result.unwrap();
-    assert_eq!(candidates_result.status, "completed");
+    assert_eq!(candidates_result.status, StepStatus::Completed);
     assert_eq!(
         candidates_result.candidates,
         vec!["candidate1", "candidate2"]
@@ -67,6 +68,38 @@ async fn test_check_rcp_status_no_output() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_check_rcp_status_step_not_reported() {
+    let mut server = mockito::Server::new_async().await;
+    let _m = server
+        .mock("GET", "/session/test-session-id/process")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "process": {
+                    "id": "test-session-id",
+                    "status": "in-progress",
+                    "output": {
+                        "fetching-code": {"step": "0/0", "status": "in-progress"}
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    std::env::set_var("CATCH_CLI_BASE_API_URL", server.url());
+
+    let result = check_rcp_status("test-session-id".to_string()).await;
+
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("not been reported yet"));
+}
+
 #[tokio::test]
 async fn test_check_rcp_status_api_error() {
     let mut server = mockito::Server::new_async().await;
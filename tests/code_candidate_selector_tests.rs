@@ -1,20 +1,20 @@
 use catch_cli::code_candidate_selector::filter_code_files;
-use catch_cli::code_reader::CatchCLICodeFile;
+use catch_cli::code_reader::IndexedFile;
 
 #[test]
 fn test_filter_code_files() {
     let files = vec![
-        CatchCLICodeFile {
+        IndexedFile {
             path: String::from("/path/to/file1.rs"),
-            content: String::from("content1"),
+            size: 8,
         },
-        CatchCLICodeFile {
+        IndexedFile {
             path: String::from("/path/to/file2.rs"),
-            content: String::from("content2"),
+            size: 8,
         },
-        CatchCLICodeFile {
+        IndexedFile {
             path: String::from("/path/to/file3.rs"),
-            content: String::from("content3"),
+            size: 8,
         },
     ];
 
@@ -52,7 +52,7 @@ fn test_filter_code_files() {
     assert_eq!(result4.len(), 0);
 
     // case 5: empty file list
-    let empty_files: Vec<CatchCLICodeFile> = vec![];
+    let empty_files: Vec<IndexedFile> = vec![];
     let paths5 = vec![String::from("/path/to/file1.rs")];
     let result5 = filter_code_files(empty_files, paths5);
     assert_eq!(result5.len(), 0);
@@ -0,0 +1,215 @@
+mod common;
+
+use catch_cli::audit_log::{AuditEvent, AuditLog, UploadRecord};
+use catch_cli::code_revoker::{affected_sessions, find_upload, revoke_upload, RevokeScope};
+use serde_json::json;
+use std::io;
+use tempfile::tempdir;
+
+fn record_upload(audit_log: &AuditLog, environment: &str, session_id: &str) {
+    record_upload_to(
+        audit_log,
+        environment,
+        session_id,
+        &format!("integration-{}", session_id),
+    );
+}
+
+fn record_upload_to(
+    audit_log: &AuditLog,
+    environment: &str,
+    session_id: &str,
+    integration_id: &str,
+) {
+    audit_log
+        .append(AuditEvent::Upload(UploadRecord {
+            environment: environment.to_string(),
+            integration_id: integration_id.to_string(),
+            ..common::upload_record(session_id)
+        }))
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_revoke_upload() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/cli/integration-session-1/files")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "sessionId".to_string(),
+            "session-1".to_string(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!({"deletedFiles": 3, "deletedArtifacts": 5}).to_string())
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let audit_log = AuditLog::new(dir.path());
+    record_upload(&audit_log, &server.url(), "session-1");
+    record_upload(&audit_log, &server.url(), "session-2");
+
+    let record = revoke_upload(
+        &audit_log,
+        &find_upload(&audit_log, "session-1").unwrap(),
+        RevokeScope::Session,
+    )
+    .await
+    .unwrap();
+
+    mock.assert();
+    assert_eq!(record.integration_id, "integration-session-1");
+    assert_eq!(record.deleted_files, Some(3));
+    assert_eq!(record.deleted_artifacts, Some(5));
+
+    let entries = audit_log.entries().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[2].event, AuditEvent::Revoke(record));
+
+    let active = audit_log.active_uploads().unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].event.session_id(), "session-2");
+}
+
+#[tokio::test]
+async fn test_revoke_upload_no_content() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/cli/integration-session-1/files")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "sessionId".to_string(),
+            "session-1".to_string(),
+        ))
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let audit_log = AuditLog::new(dir.path());
+    record_upload(&audit_log, &server.url(), "session-1");
+
+    let record = revoke_upload(
+        &audit_log,
+        &find_upload(&audit_log, "session-1").unwrap(),
+        RevokeScope::Session,
+    )
+    .await
+    .unwrap();
+
+    mock.assert();
+    assert_eq!(record.deleted_files, None);
+    assert!(audit_log.active_uploads().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_revoke_upload_api_error_is_not_recorded() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/cli/integration-session-1/files")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "sessionId".to_string(),
+            "session-1".to_string(),
+        ))
+        .with_status(500)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let audit_log = AuditLog::new(dir.path());
+    record_upload(&audit_log, &server.url(), "session-1");
+
+    let result = revoke_upload(
+        &audit_log,
+        &find_upload(&audit_log, "session-1").unwrap(),
+        RevokeScope::Session,
+    )
+    .await;
+
+    mock.assert();
+    assert!(result.is_err());
+    assert_eq!(audit_log.entries().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_revoke_unknown_session() {
+    let dir = tempdir().unwrap();
+    let audit_log = AuditLog::new(dir.path());
+
+    let result = find_upload(&audit_log, "unknown");
+
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn test_revoke_upload_only_deletes_the_session() {
+    let mut server = mockito::Server::new_async().await;
+    let session_mock = server
+        .mock("DELETE", "/cli/integration-1/files")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "sessionId".to_string(),
+            "session-2".to_string(),
+        ))
+        .with_status(204)
+        .create_async()
+        .await;
+    let integration_mock = server
+        .mock("DELETE", "/cli/integration-1/files")
+        .match_query(mockito::Matcher::Missing)
+        .expect(0)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let audit_log = AuditLog::new(dir.path());
+    record_upload_to(&audit_log, &server.url(), "session-1", "integration-1");
+    record_upload_to(&audit_log, &server.url(), "session-2", "integration-1");
+    let upload = find_upload(&audit_log, "session-2").unwrap();
+
+    assert_eq!(
+        affected_sessions(&audit_log, &upload, RevokeScope::Session).unwrap(),
+        vec!["session-2"]
+    );
+
+    revoke_upload(&audit_log, &upload, RevokeScope::Session)
+        .await
+        .unwrap();
+
+    session_mock.assert();
+    integration_mock.assert();
+    let active = audit_log.active_uploads().unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].event.session_id(), "session-1");
+}
+
+#[tokio::test]
+async fn test_revoke_upload_covers_the_whole_integration() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/cli/integration-1/files")
+        .match_query(mockito::Matcher::Missing)
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let audit_log = AuditLog::new(dir.path());
+    record_upload_to(&audit_log, &server.url(), "session-1", "integration-1");
+    record_upload_to(&audit_log, &server.url(), "session-2", "integration-1");
+    record_upload_to(&audit_log, &server.url(), "session-3", "integration-2");
+    let upload = find_upload(&audit_log, "session-2").unwrap();
+
+    assert_eq!(
+        affected_sessions(&audit_log, &upload, RevokeScope::Integration).unwrap(),
+        vec!["session-1", "session-2"]
+    );
+
+    revoke_upload(&audit_log, &upload, RevokeScope::Integration)
+        .await
+        .unwrap();
+
+    mock.assert();
+    let active = audit_log.active_uploads().unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].event.session_id(), "session-3");
+}
@@ -0,0 +1,34 @@
+use catch_cli::api_client::CatchApiClient;
+use catch_cli::code_uploader::send_codes;
+use catch_cli::cryptography::{EncryptionScheme, FileCipher};
+use catch_cli::upload_manifest::{load_or_create_signing_key, UploadManifest};
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_send_codes_fails_when_the_key_cant_be_wrapped() {
+    let dir = tempdir().unwrap();
+    let signing_key = load_or_create_signing_key(dir.path()).unwrap();
+    let manifest = UploadManifest::new("session-id", "integration-id", &[]).sign(&signing_key);
+
+    for scheme in [EncryptionScheme::Aes256Gcm, EncryptionScheme::Aes256Cbc] {
+        let cipher = FileCipher::generate(scheme);
+        // Nothing listens here; the request must fail before it's sent.
+        let api_client = CatchApiClient::new("http://127.0.0.1:9".to_string());
+        let error = send_codes(
+            &api_client,
+            "integration-id".to_string(),
+            "session-id".to_string(),
+            vec![],
+            &cipher,
+            "not a public key",
+            &manifest,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            error.to_string().starts_with("Failed to encrypt the file"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Fixtures shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use catch_cli::audit_log::UploadRecord;
+use catch_cli::code_reader::{CatchCLICodeFile, CodeFileMetadata};
+use std::path::Path;
+use std::process::Command;
+
+const GIT_CONFIG: [&str; 8] = [
+    "-c",
+    "user.name=Catch",
+    "-c",
+    "user.email=catch@example.com",
+    "-c",
+    "init.defaultBranch=main",
+    "-c",
+    "commit.gpgsign=false",
+];
+
+/// Runs git in `dir` with a fixed identity and no commit signing, and returns its trimmed
+/// output.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(GIT_CONFIG)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("git is installed");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// An upload of `catch-org/catch-app` with no files to `integration-id` on production.
+pub fn upload_record(session_id: &str) -> UploadRecord {
+    UploadRecord {
+        environment: "https://api.trycatch.ai".to_string(),
+        session_id: session_id.to_string(),
+        integration_id: "integration-id".to_string(),
+        org_name: "catch-org".to_string(),
+        repo_name: "catch-app".to_string(),
+        git_head: None,
+        server_key_fingerprint: "ff04e8".to_string(),
+        files: vec![],
+        base_session_id: None,
+        deleted_files: vec![],
+    }
+}
+
+/// A read file with no content, identified by its plaintext hash.
+pub fn code_file(path: &str, plaintext_sha256: &str) -> CatchCLICodeFile {
+    CatchCLICodeFile {
+        path: path.to_string(),
+        content: String::new(),
+        nonce: None,
+        metadata: CodeFileMetadata {
+            plaintext_sha256: plaintext_sha256.to_string(),
+            ..Default::default()
+        },
+    }
+}
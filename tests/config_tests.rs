@@ -0,0 +1,34 @@
+use catch_cli::config::CatchConfig;
+use tempfile::tempdir;
+
+#[test]
+fn test_load_missing_config() {
+    let dir = tempdir().unwrap();
+
+    let config = CatchConfig::load(dir.path()).unwrap();
+
+    assert_eq!(config, CatchConfig::default());
+}
+
+#[test]
+fn test_save_and_load_config() {
+    let dir = tempdir().unwrap();
+    let config_dir = dir.path().join("nested").join("catch");
+
+    let mut config = CatchConfig::default();
+    config
+        .pinned_server_keys
+        .insert("https://api.trycatch.ai".to_string(), "ab".repeat(32));
+    config.save(&config_dir).unwrap();
+
+    let loaded = CatchConfig::load(&config_dir).unwrap();
+    assert_eq!(loaded, config);
+}
+
+#[test]
+fn test_load_invalid_config() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("config.json"), "not json").unwrap();
+
+    assert!(CatchConfig::load(dir.path()).is_err());
+}
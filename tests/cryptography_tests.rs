@@ -1,4 +1,10 @@
-use catch_cli::cryptography::encrypt_rsa4096_base64;
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use catch_cli::api_client::cli_entity::{CatchConnectCLIResponse, ServerEncryptionScheme};
+use catch_cli::cryptography::{
+    encrypt_aes_256, encrypt_aes_256_gcm, encrypt_rsa4096_base64, EncryptionScheme, FileCipher,
+};
+use libaes::Cipher;
 use rand::rngs::OsRng;
 use rsa::{pkcs8::EncodePublicKey, RsaPrivateKey, RsaPublicKey};
 
@@ -73,3 +79,125 @@ fn test_encrypt_rsa4096_base64_empty_message() {
         "Encryption should succeed with empty message"
     );
 }
+
+#[test]
+fn test_encrypt_aes_256_gcm_round_trip() {
+    let key = rand::random::<[u8; 32]>();
+    let nonce = rand::random::<[u8; 12]>();
+
+    let ciphertext = encrypt_aes_256_gcm(&key, &nonce, b"Hello, World!", b"src/main.swift")
+        .expect("Encryption should succeed");
+    assert_ne!(ciphertext, b"Hello, World!");
+
+    let cipher = Aes256Gcm::new(&key.into());
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: b"src/main.swift",
+            },
+        )
+        .expect("Decryption should succeed with the same associated data");
+    assert_eq!(plaintext, b"Hello, World!");
+
+    let wrong_path = cipher.decrypt(
+        Nonce::from_slice(&nonce),
+        Payload {
+            msg: &ciphertext,
+            aad: b"src/other.swift",
+        },
+    );
+    assert!(
+        wrong_path.is_err(),
+        "Decryption should fail for another path"
+    );
+
+    let mut tampered = ciphertext.clone();
+    tampered[0] ^= 0x01;
+    let tampered_result = cipher.decrypt(
+        Nonce::from_slice(&nonce),
+        Payload {
+            msg: &tampered,
+            aad: b"src/main.swift",
+        },
+    );
+    assert!(tampered_result.is_err(), "Tampering should be detected");
+}
+
+#[test]
+fn test_encrypt_aes_256_matches_reference_cbc() {
+    let key: [u8; 32] = rand::random();
+    let iv: [u8; 16] = rand::random();
+
+    for length in [0, 1, 15, 16, 17, 64, 1000] {
+        let message: Vec<u8> = (0..length).map(|i| i as u8).collect();
+
+        let ciphertext = encrypt_aes_256(&key, &iv, &message);
+
+        assert_eq!(ciphertext, Cipher::new_256(&key).cbc_encrypt(&iv, &message));
+    }
+}
+
+#[test]
+fn test_file_cipher_gcm_uses_fresh_nonces() {
+    let cipher = FileCipher::generate(EncryptionScheme::Aes256Gcm);
+
+    let first = cipher.encrypt("a.js", b"same content").unwrap();
+    let second = cipher.encrypt("a.js", b"same content").unwrap();
+
+    assert_ne!(first.nonce, second.nonce);
+    assert_ne!(first.ciphertext, second.ciphertext);
+}
+
+#[test]
+fn test_file_cipher_cbc_has_no_nonce() {
+    let cipher = FileCipher::generate(EncryptionScheme::Aes256Cbc);
+
+    let encrypted = cipher.encrypt("a.js", b"content").unwrap();
+
+    assert!(encrypted.nonce.is_none());
+    assert_eq!(encrypted.ciphertext.len() % 16, 0);
+}
+
+#[test]
+fn test_encryption_scheme_serialization() {
+    assert_eq!(
+        serde_json::to_string(&EncryptionScheme::SUPPORTED).unwrap(),
+        r#"["aes-256-gcm","aes-256-cbc"]"#
+    );
+    let scheme: EncryptionScheme = serde_json::from_str(r#""aes-256-cbc""#).unwrap();
+    assert!(scheme.is_legacy());
+}
+
+#[test]
+fn test_connect_response_encryption_scheme() {
+    let response: CatchConnectCLIResponse = serde_json::from_str(
+        r#"{"publicKey": "key", "integrationId": "id", "encryptionScheme": "aes-256-gcm"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        response.encryption_scheme.supported().unwrap(),
+        EncryptionScheme::Aes256Gcm
+    );
+
+    let response: CatchConnectCLIResponse =
+        serde_json::from_str(r#"{"publicKey": "key", "integrationId": "id"}"#).unwrap();
+    assert!(response.encryption_scheme.supported().unwrap().is_legacy());
+
+    // A scheme from a newer server still parses, and asks for an upgrade instead.
+    let response: CatchConnectCLIResponse = serde_json::from_str(
+        r#"{"publicKey": "key", "integrationId": "id", "encryptionScheme": "xchacha20-poly1305"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        response.encryption_scheme,
+        ServerEncryptionScheme::Unsupported("xchacha20-poly1305".to_string())
+    );
+    assert!(response
+        .encryption_scheme
+        .supported()
+        .unwrap_err()
+        .to_string()
+        .contains("upgrade the CLI"));
+}
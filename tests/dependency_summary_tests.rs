@@ -0,0 +1,423 @@
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::engine::general_purpose;
+use base64::Engine;
+use catch_cli::code_reader::ScanOptions;
+use catch_cli::code_uploader::UploadMetadata;
+use catch_cli::cryptography::secret::Secret;
+use catch_cli::cryptography::{EncryptionScheme, FileCipher};
+use catch_cli::dependency_summary::{
+    parse_manifest, summarize_dependencies, Dependency, Ecosystem, DEPENDENCY_SUMMARY_PATH,
+};
+use catch_cli::upload_manifest::UploadManifest;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+fn names(dependencies: &[Dependency]) -> Vec<&str> {
+    dependencies
+        .iter()
+        .map(|dependency| dependency.name.as_str())
+        .collect()
+}
+
+#[test]
+fn test_parse_javascript_manifests() {
+    let package = parse_manifest(
+        "web/package.json",
+        r#"{
+            "name": "app",
+            "engines": { "node": ">=18" },
+            "dependencies": { "react": "^18.2.0", "react-native": "0.74.1" },
+            "devDependencies": { "@babel/core": "^7.24.0" }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(package.ecosystem, Ecosystem::Npm);
+    assert!(!package.lockfile);
+    assert_eq!(
+        package.contents.dependencies[2],
+        Dependency::new("@babel/core", Some("^7.24.0"), Some("devDependencies"))
+    );
+    assert_eq!(package.contents.platforms["node"], ">=18");
+    assert_eq!(package.describe(), "npm, 3 dependencies, node >=18");
+
+    let lock = parse_manifest(
+        "package-lock.json",
+        r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "name": "app" },
+                "node_modules/react": { "version": "18.2.0" },
+                "node_modules/@babel/core": { "version": "7.24.0", "dev": true },
+                "node_modules/a/node_modules/react": { "version": "17.0.2" }
+            }
+        }"#,
+    )
+    .unwrap();
+    assert!(lock.lockfile);
+    assert_eq!(
+        lock.contents.dependencies,
+        [
+            Dependency::new("@babel/core", Some("7.24.0"), Some("dev")),
+            Dependency::new("react", Some("17.0.2"), None),
+            Dependency::new("react", Some("18.2.0"), None),
+        ]
+    );
+
+    let classic = parse_manifest(
+        "yarn.lock",
+        "# yarn lockfile v1\n\n\"@babel/core@^7.0.0\", \"@babel/core@^7.1.0\":\n  version \"7.24.0\"\n  resolved \"https://registry.yarnpkg.com\"\n\nreact@^18.2.0:\n  version \"18.2.0\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        classic.contents.dependencies,
+        [
+            Dependency::new("@babel/core", Some("7.24.0"), None),
+            Dependency::new("react", Some("18.2.0"), None),
+        ]
+    );
+
+    let berry = parse_manifest(
+        "yarn.lock",
+        "__metadata:\n  version: 8\n\n\"react@npm:^18.2.0\":\n  version: 18.2.0\n  resolution: \"react@npm:18.2.0\"\n",
+    )
+    .unwrap();
+    assert_eq!(names(&berry.contents.dependencies), ["react"]);
+
+    let pnpm = parse_manifest(
+        "pnpm-lock.yaml",
+        "lockfileVersion: '9.0'\npackages:\n  '@babel/core@7.24.0':\n    resolution: {}\n  react-dom@18.2.0(react@18.2.0):\n    resolution: {}\n  /lodash/4.17.21:\n    resolution: {}\n",
+    )
+    .unwrap();
+    assert_eq!(
+        pnpm.contents.dependencies,
+        [
+            Dependency::new("@babel/core", Some("7.24.0"), None),
+            Dependency::new("react-dom", Some("18.2.0"), None),
+            Dependency::new("lodash", Some("4.17.21"), None),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_gradle_manifests() {
+    let script = parse_manifest(
+        "app/build.gradle.kts",
+        r#"
+android {
+    compileSdk = 34
+    defaultConfig {
+        minSdk = 24
+        targetSdkVersion(34)
+    }
+}
+
+dependencies {
+    implementation("androidx.core:core-ktx:1.13.1")
+    implementation(platform("androidx.compose:compose-bom:2024.05.00"))
+    testImplementation 'junit:junit:4.13.2'
+    ksp(libs.room.compiler)
+    version("not:a:dependency")
+}
+"#,
+    )
+    .unwrap();
+    assert_eq!(script.ecosystem, Ecosystem::Gradle);
+    assert_eq!(
+        script.contents.dependencies,
+        [
+            Dependency::new(
+                "androidx.core:core-ktx",
+                Some("1.13.1"),
+                Some("implementation")
+            ),
+            Dependency::new(
+                "androidx.compose:compose-bom",
+                Some("2024.05.00"),
+                Some("implementation")
+            ),
+            Dependency::new("junit:junit", Some("4.13.2"), Some("testImplementation")),
+            Dependency::new("libs.room.compiler", None, Some("ksp")),
+        ]
+    );
+    assert_eq!(script.contents.platforms["minSdk"], "24");
+    assert_eq!(script.contents.platforms["targetSdk"], "34");
+    assert_eq!(script.contents.platforms["compileSdk"], "34");
+
+    let catalog = parse_manifest(
+        "gradle/libs.versions.toml",
+        r#"
+[versions]
+room = "2.6.1"
+
+[libraries]
+room-runtime = { module = "androidx.room:room-runtime", version.ref = "room" }
+okhttp = { group = "com.squareup.okhttp3", name = "okhttp", version = "4.12.0" }
+junit = "junit:junit:4.13.2"
+
+[plugins]
+android-application = { id = "com.android.application", version = "8.4.0" }
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        catalog.contents.dependencies,
+        [
+            Dependency::new("junit:junit", Some("4.13.2"), None),
+            Dependency::new("com.squareup.okhttp3:okhttp", Some("4.12.0"), None),
+            Dependency::new("androidx.room:room-runtime", Some("2.6.1"), None),
+            Dependency::new("com.android.application", Some("8.4.0"), Some("plugin")),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_cocoapods_manifests() {
+    let podfile = parse_manifest(
+        "ios/Podfile",
+        r#"
+platform :ios, '13.0'
+use_frameworks!
+
+target 'App' do
+  pod 'Alamofire', '~> 5.8'
+  pod 'SnapKit' # layout
+
+  target 'AppTests' do
+    inherit! :search_paths
+    pod 'Quick'
+  end
+end
+
+post_install do |installer|
+  installer.pods_project.targets.each do |target|
+  end
+end
+"#,
+    )
+    .unwrap();
+    assert_eq!(podfile.ecosystem, Ecosystem::CocoaPods);
+    assert_eq!(
+        podfile.contents.dependencies,
+        [
+            Dependency::new("Alamofire", Some("~> 5.8"), Some("App")),
+            Dependency::new("SnapKit", None, Some("App")),
+            Dependency::new("Quick", None, Some("AppTests")),
+        ]
+    );
+    assert_eq!(podfile.contents.targets, ["App", "AppTests"]);
+    assert_eq!(podfile.contents.platforms["ios"], "13.0");
+
+    let lock = parse_manifest(
+        "ios/Podfile.lock",
+        "PODS:\n  - Alamofire (5.8.1)\n  - Quick (7.4.0):\n    - Nimble\n  - SnapKit (5.7.1)\n\nCOCOAPODS: 1.15.2\n",
+    )
+    .unwrap();
+    assert!(lock.lockfile);
+    assert_eq!(
+        lock.contents.dependencies,
+        [
+            Dependency::new("Alamofire", Some("5.8.1"), None),
+            Dependency::new("Quick", Some("7.4.0"), None),
+            Dependency::new("SnapKit", Some("5.7.1"), None),
+        ]
+    );
+    assert_eq!(lock.contents.platforms["cocoapods"], "1.15.2");
+}
+
+#[test]
+fn test_parse_swift_package_manifests() {
+    let package = parse_manifest(
+        "Package.swift",
+        r#"// swift-tools-version:5.9
+import PackageDescription
+
+let package = Package(
+    name: "App",
+    platforms: [.iOS(.v15), .macOS(.v10_15)],
+    dependencies: [
+        .package(url: "https://github.com/apple/swift-log.git", from: "1.5.0"),
+        .package(url: "https://github.com/pointfreeco/swift-composable-architecture", .upToNextMinor(from: "1.10.0")),
+        .package(url: "https://github.com/onevcat/Kingfisher.git", "7.0.0"..<"8.0.0"),
+        .package(url: "https://github.com/realm/SwiftLint", branch: "main"),
+        .package(path: "../Shared"),
+    ],
+    targets: [
+        .target(name: "App", dependencies: [.product(name: "Logging", package: "swift-log")]),
+        .testTarget(name: "AppTests", dependencies: ["App"]),
+    ]
+)
+"#,
+    )
+    .unwrap();
+    assert_eq!(package.ecosystem, Ecosystem::SwiftPm);
+    assert_eq!(
+        package.contents.dependencies,
+        [
+            Dependency::new("swift-log", Some("from 1.5.0"), None),
+            Dependency::new("swift-composable-architecture", Some("from 1.10.0"), None),
+            Dependency::new("Kingfisher", Some("7.0.0..<8.0.0"), None),
+            Dependency::new("SwiftLint", Some("branch main"), None),
+            Dependency::new("Shared", None, None),
+        ]
+    );
+    assert_eq!(package.contents.targets, ["App", "AppTests"]);
+    assert_eq!(package.contents.platforms["swift-tools"], "5.9");
+    assert_eq!(package.contents.platforms["iOS"], "15");
+    assert_eq!(package.contents.platforms["macOS"], "10.15");
+
+    let v1 = parse_manifest(
+        "App.xcodeproj/project.xcworkspace/xcshareddata/swiftpm/Package.resolved",
+        r#"{"object": {"pins": [{"package": "swift-log", "state": {"version": "1.5.4"}}]}, "version": 1}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        v1.contents.dependencies,
+        [Dependency::new("swift-log", Some("1.5.4"), None)]
+    );
+
+    let v2 = parse_manifest(
+        "Package.resolved",
+        r#"{"pins": [{"identity": "swiftlint", "state": {"branch": "main", "revision": "abc"}}], "version": 2}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        v2.contents.dependencies,
+        [Dependency::new("swiftlint", Some("main"), None)]
+    );
+}
+
+#[test]
+fn test_parse_pubspec_and_cargo_manifests() {
+    let pubspec = parse_manifest(
+        "pubspec.yaml",
+        r#"
+name: app
+environment:
+  sdk: ">=3.0.0 <4.0.0"
+  flutter: ">=3.10.0"
+dependencies:
+  flutter:
+    sdk: flutter
+  http: ^1.2.0
+  shared:
+    path: ../shared
+dev_dependencies:
+  flutter_lints: ^3.0.0
+"#,
+    )
+    .unwrap();
+    assert_eq!(pubspec.ecosystem, Ecosystem::Pub);
+    assert_eq!(
+        pubspec.contents.dependencies,
+        [
+            Dependency::new("flutter", Some("sdk flutter"), Some("dependencies")),
+            Dependency::new("http", Some("^1.2.0"), Some("dependencies")),
+            Dependency::new("shared", Some("path"), Some("dependencies")),
+            Dependency::new("flutter_lints", Some("^3.0.0"), Some("dev_dependencies")),
+        ]
+    );
+    assert_eq!(pubspec.contents.platforms["dart"], ">=3.0.0 <4.0.0");
+    assert_eq!(pubspec.contents.platforms["flutter"], ">=3.10.0");
+
+    let cargo = parse_manifest(
+        "Cargo.toml",
+        r#"
+[package]
+name = "app"
+rust-version = "1.80"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+core = { path = "../core" }
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+
+[[bin]]
+name = "app"
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        cargo.contents.dependencies,
+        [
+            Dependency::new("core", Some("path"), Some("dependencies")),
+            Dependency::new("serde", Some("1.0"), Some("dependencies")),
+            Dependency::new("libc", Some("0.2"), Some("target.cfg(unix).dependencies")),
+        ]
+    );
+    assert_eq!(cargo.contents.targets, ["app"]);
+    assert_eq!(cargo.contents.platforms["rust"], "1.80");
+
+    assert!(parse_manifest("Cargo.toml", "[dependencies").is_none());
+    assert!(parse_manifest("src/main.rs", "fn main() {}").is_none());
+}
+
+#[tokio::test]
+async fn test_summarize_and_seal_dependencies() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("ios")).unwrap();
+    fs::create_dir_all(dir.path().join("node_modules/react")).unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"dependencies": {"react": "^18.2.0"}}"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("node_modules/react/package.json"),
+        r#"{"dependencies": {"loose-envify": "^1.1.0"}}"#,
+    )
+    .unwrap();
+    fs::write(dir.path().join("ios/Podfile"), "pod 'Alamofire'\n").unwrap();
+    fs::write(dir.path().join("index.js"), "let app = 1;").unwrap();
+
+    let summary = summarize_dependencies(dir.path(), &ScanOptions::default())
+        .await
+        .unwrap();
+    let mut paths: Vec<_> = summary
+        .manifests
+        .iter()
+        .map(|manifest| manifest.path.as_str())
+        .collect();
+    paths.sort();
+    assert_eq!(paths, ["ios/Podfile", "package.json"]);
+    assert!(summary
+        .render()
+        .contains("  react ^18.2.0  [dependencies]\n"));
+
+    let key = rand::random::<[u8; 32]>();
+    let cipher = FileCipher::new(
+        EncryptionScheme::Aes256Gcm,
+        Secret::new(key),
+        Secret::new(rand::random::<[u8; 16]>()),
+    );
+    let sealed = summary.seal(&cipher).unwrap();
+    let ciphertext = general_purpose::STANDARD.decode(&sealed.content).unwrap();
+    let nonce = general_purpose::STANDARD
+        .decode(sealed.nonce.as_ref().unwrap())
+        .unwrap();
+    let plaintext = Aes256Gcm::new(&key.into())
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: DEPENDENCY_SUMMARY_PATH.as_bytes(),
+            },
+        )
+        .unwrap();
+    let json: Value = serde_json::from_slice(&plaintext).unwrap();
+    assert_eq!(json["manifests"].as_array().unwrap().len(), 2);
+    assert!(json["manifests"][0]["ecosystem"].is_string());
+
+    let metadata = UploadMetadata {
+        dependency_summary: Some(sealed.clone()),
+        ..Default::default()
+    };
+    let manifest = UploadManifest::new("session-1", "integration-id", &[]).with_metadata(&metadata);
+    assert_eq!(
+        manifest.dependency_summary_sha256,
+        Some(sealed.ciphertext_sha256())
+    );
+}
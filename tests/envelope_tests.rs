@@ -0,0 +1,230 @@
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use catch_cli::cryptography::envelope::{
+    EncryptionEnvelope, EnvelopeError, KeyWrapping, ENVELOPE_VERSION,
+};
+use catch_cli::cryptography::{public_key_fingerprint, EncryptionScheme, FileCipher};
+use flate2::read::DeflateDecoder;
+use libaes::Cipher;
+use std::io::Read;
+
+const SERVER_PUBLIC_KEY_PEM: &str = include_str!("fixtures/server_public_key.pem");
+const SERVER_KEY_FINGERPRINT: &str =
+    "ff04e89a59d0d934bb6558271be1367d594d1f4a40d656d82a166ae8bcadf2b3";
+
+/// Decrypts an envelope following the documented layout only, without the crate's parser.
+fn reference_decrypt(key: &[u8; 32], path: &str, bytes: &[u8]) -> Result<Vec<u8>, String> {
+    if &bytes[0..4] != b"CCEV" || bytes[4] != 1 {
+        return Err("not a v1 envelope".to_string());
+    }
+    let algorithm = bytes[5];
+    let flags = bytes[7];
+    let nonce_len = bytes[40] as usize;
+    let nonce = &bytes[41..41 + nonce_len];
+    let header_len = 41 + nonce_len + 8;
+    let plaintext_len = u64::from_be_bytes(bytes[41 + nonce_len..header_len].try_into().unwrap());
+    let ciphertext = &bytes[header_len..];
+
+    let body = match algorithm {
+        1 => Cipher::new_256(key).cbc_decrypt(nonce, ciphertext),
+        2 => {
+            let mut aad = bytes[..header_len].to_vec();
+            aad.extend_from_slice(path.as_bytes());
+            Aes256Gcm::new(key.into())
+                .decrypt(
+                    Nonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|e| e.to_string())?
+        }
+        other => return Err(format!("unknown algorithm {}", other)),
+    };
+
+    let plaintext = if flags & 1 == 1 {
+        let mut inflated = Vec::new();
+        DeflateDecoder::new(&body[..])
+            .read_to_end(&mut inflated)
+            .map_err(|e| e.to_string())?;
+        inflated
+    } else {
+        body
+    };
+
+    if plaintext.len() as u64 != plaintext_len {
+        return Err("plaintext length mismatch".to_string());
+    }
+    Ok(plaintext)
+}
+
+fn key_id() -> [u8; 32] {
+    public_key_fingerprint(SERVER_PUBLIC_KEY_PEM).unwrap()
+}
+
+#[test]
+fn test_public_key_fingerprint() {
+    let fingerprint = public_key_fingerprint(SERVER_PUBLIC_KEY_PEM).unwrap();
+    let hex: String = fingerprint.iter().map(|b| format!("{:02x}", b)).collect();
+
+    assert_eq!(hex, SERVER_KEY_FINGERPRINT);
+}
+
+#[test]
+fn test_gcm_envelope_round_trip_compressed() {
+    let key = rand::random::<[u8; 32]>();
+    let plaintext = "import UIKit\n".repeat(200);
+
+    let envelope = EncryptionEnvelope::seal(
+        EncryptionScheme::Aes256Gcm,
+        &key,
+        key_id(),
+        "ios/App/AppDelegate.swift",
+        plaintext.as_bytes(),
+    )
+    .unwrap();
+
+    assert!(envelope.compressed);
+    assert_eq!(envelope.nonce.len(), 12);
+    assert_eq!(envelope.plaintext_length, plaintext.len() as u64);
+
+    let decrypted =
+        reference_decrypt(&key, "ios/App/AppDelegate.swift", &envelope.to_bytes()).unwrap();
+    assert_eq!(decrypted, plaintext.as_bytes());
+}
+
+#[test]
+fn test_cbc_envelope_round_trip_uncompressed() {
+    let key = rand::random::<[u8; 32]>();
+    let plaintext: Vec<u8> = (0..64).map(|_| rand::random::<u8>()).collect();
+
+    let envelope = EncryptionEnvelope::seal(
+        EncryptionScheme::Aes256Cbc,
+        &key,
+        key_id(),
+        "build.gradle",
+        &plaintext,
+    )
+    .unwrap();
+
+    assert!(!envelope.compressed);
+    assert_eq!(envelope.nonce.len(), 16);
+
+    let decrypted = reference_decrypt(&key, "build.gradle", &envelope.to_bytes()).unwrap();
+    assert_eq!(decrypted, plaintext);
+}
+
+#[test]
+fn test_envelope_serialization_round_trip() {
+    let key = rand::random::<[u8; 32]>();
+    let envelope = EncryptionEnvelope::seal(
+        EncryptionScheme::Aes256Gcm,
+        &key,
+        key_id(),
+        "a.js",
+        b"console.log('catch')",
+    )
+    .unwrap();
+
+    let bytes = envelope.to_bytes();
+    let parsed = EncryptionEnvelope::from_bytes(&bytes).unwrap();
+
+    assert_eq!(parsed, envelope);
+    assert_eq!(parsed.version, ENVELOPE_VERSION);
+    assert_eq!(parsed.key_wrapping, KeyWrapping::RsaOaepSha256);
+    assert_eq!(parsed.key_id, key_id());
+    assert_eq!(&bytes[8..40], &key_id());
+}
+
+#[test]
+fn test_gcm_envelope_detects_header_and_path_tampering() {
+    let key = rand::random::<[u8; 32]>();
+    let envelope = EncryptionEnvelope::seal(
+        EncryptionScheme::Aes256Gcm,
+        &key,
+        key_id(),
+        "a.js",
+        b"console.log('catch')",
+    )
+    .unwrap();
+    let bytes = envelope.to_bytes();
+
+    assert!(reference_decrypt(&key, "b.js", &bytes).is_err());
+
+    let mut tampered = bytes.clone();
+    tampered[8] ^= 0xff;
+    assert!(reference_decrypt(&key, "a.js", &tampered).is_err());
+}
+
+#[test]
+fn test_envelope_parse_errors() {
+    assert!(matches!(
+        EncryptionEnvelope::from_bytes(b"CCEV"),
+        Err(EnvelopeError::Truncated)
+    ));
+
+    let key = rand::random::<[u8; 32]>();
+    let bytes = EncryptionEnvelope::seal(EncryptionScheme::Aes256Gcm, &key, key_id(), "a", b"a")
+        .unwrap()
+        .to_bytes();
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(matches!(
+        EncryptionEnvelope::from_bytes(&bad_magic),
+        Err(EnvelopeError::InvalidMagic)
+    ));
+
+    let mut bad_version = bytes.clone();
+    bad_version[4] = 9;
+    assert!(matches!(
+        EncryptionEnvelope::from_bytes(&bad_version),
+        Err(EnvelopeError::UnsupportedVersion(9))
+    ));
+
+    let mut bad_algorithm = bytes.clone();
+    bad_algorithm[5] = 7;
+    assert!(matches!(
+        EncryptionEnvelope::from_bytes(&bad_algorithm),
+        Err(EnvelopeError::UnknownAlgorithm(7))
+    ));
+
+    let mut bad_nonce_length = bytes.clone();
+    bad_nonce_length[40] = 4;
+    assert!(matches!(
+        EncryptionEnvelope::from_bytes(&bad_nonce_length),
+        Err(EnvelopeError::InvalidNonceLength {
+            expected: 12,
+            found: 4
+        })
+    ));
+
+    // A GCM nonce relabeled as CBC is too short for an IV.
+    let mut relabeled = bytes.clone();
+    relabeled[5] = EncryptionScheme::Aes256Cbc.envelope_id();
+    assert!(matches!(
+        EncryptionEnvelope::from_bytes(&relabeled),
+        Err(EnvelopeError::InvalidNonceLength {
+            expected: 16,
+            found: 12
+        })
+    ));
+
+    assert!(matches!(
+        EncryptionEnvelope::from_bytes(&bytes[..45]),
+        Err(EnvelopeError::Truncated)
+    ));
+}
+
+#[test]
+fn test_file_cipher_seals_envelopes() {
+    let cipher = FileCipher::generate(EncryptionScheme::Aes256Gcm).with_envelope(key_id());
+    assert_eq!(cipher.envelope_version(), Some(ENVELOPE_VERSION));
+
+    let sealed = cipher.seal("src/index.ts", b"export {}").unwrap();
+
+    assert!(sealed.nonce.is_none());
+    let decrypted = reference_decrypt(cipher.key(), "src/index.ts", &sealed.ciphertext).unwrap();
+    assert_eq!(decrypted, b"export {}");
+}
@@ -1,27 +1,30 @@
-use std::fs::File;
-use std::io::Write;
+mod common;
+
+use common::git;
 use tempfile::tempdir;
 
-use catch_cli::git_info::{get_git_remote_url, parse_github_url};
+use catch_cli::git_info::{
+    find_repository_root, get_git_head, get_git_remote_url, parse_github_url,
+};
 #[test]
 fn test_get_git_remote_url() {
     let dir = tempdir().unwrap();
-    let git_dir = dir.path().join(".git");
-    std::fs::create_dir(&git_dir).unwrap();
-
-    let config_path = git_dir.join("config");
-    let mut config_file = File::create(&config_path).unwrap();
-    writeln!(config_file, "[core]").unwrap();
-    writeln!(config_file, "\trepositoryformatversion = 0").unwrap();
-    writeln!(config_file, "\tfilemode = false").unwrap();
-    writeln!(config_file, "\tbare = false").unwrap();
-    writeln!(config_file, "[remote \"origin\"]").unwrap();
-    writeln!(config_file, "\turl = https://github.com/user/repo.git").unwrap();
-    writeln!(config_file, "\tfetch = +refs/heads/*:refs/remotes/origin/*").unwrap();
-
-    std::env::set_current_dir(&dir).unwrap();
-
-    let result = get_git_remote_url();
+    git(dir.path(), &["init", "-q"]);
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/user/repo.git",
+        ],
+    );
+    git(
+        dir.path(),
+        &["remote", "add", "upstream", "git@github.com:other/repo.git"],
+    );
+
+    let result = get_git_remote_url(dir.path());
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), "https://github.com/user/repo.git");
 }
@@ -29,9 +32,8 @@ fn test_get_git_remote_url() {
 #[test]
 fn test_get_git_remote_url_no_git_dir() {
     let dir = tempdir().unwrap();
-    std::env::set_current_dir(&dir).unwrap();
 
-    let result = get_git_remote_url();
+    let result = get_git_remote_url(dir.path());
     assert!(result.is_err());
 }
 
@@ -74,3 +76,92 @@ fn test_parse_github_url_no_git_extension() {
     assert_eq!(org, "user");
     assert_eq!(repo, "repo");
 }
+
+#[test]
+fn test_get_git_head() {
+    let dir = tempdir().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    assert!(get_git_head(dir.path()).is_err());
+
+    git(
+        dir.path(),
+        &["commit", "-q", "--allow-empty", "-m", "first"],
+    );
+    git(dir.path(), &["branch", "release"]);
+    git(
+        dir.path(),
+        &["commit", "-q", "--allow-empty", "-m", "second"],
+    );
+    assert_eq!(
+        get_git_head(dir.path()).unwrap(),
+        git(dir.path(), &["rev-parse", "main"])
+    );
+
+    // Packed refs are read too.
+    git(dir.path(), &["pack-refs", "--all"]);
+    git(dir.path(), &["checkout", "-q", "release"]);
+    assert_eq!(
+        get_git_head(dir.path()).unwrap(),
+        git(dir.path(), &["rev-parse", "main~1"])
+    );
+}
+
+#[test]
+fn test_find_repository_root() {
+    let dir = tempdir().unwrap();
+    let nested = dir.path().join("apps/ios/Sources");
+    std::fs::create_dir_all(&nested).unwrap();
+    assert_eq!(find_repository_root(&nested), None);
+
+    std::fs::create_dir(dir.path().join(".git")).unwrap();
+    assert_eq!(find_repository_root(&nested).as_deref(), Some(dir.path()));
+
+    // Submodules and worktrees have a `.git` file instead.
+    std::fs::write(
+        dir.path().join("apps/.git"),
+        "gitdir: ../.git/modules/apps\n",
+    )
+    .unwrap();
+    assert_eq!(find_repository_root(&nested), Some(dir.path().join("apps")));
+}
+
+#[test]
+fn test_git_info_in_worktree() {
+    let dir = tempdir().unwrap();
+    let main = dir.path().join("main");
+    std::fs::create_dir(&main).unwrap();
+    git(&main, &["init", "-q"]);
+    git(
+        &main,
+        &["remote", "add", "origin", "git@github.com:user/repo.git"],
+    );
+    git(&main, &["commit", "-q", "--allow-empty", "-m", "first"]);
+    let worktree = dir.path().join("feature");
+    git(
+        &main,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature",
+            worktree.to_str().unwrap(),
+        ],
+    );
+    git(
+        &worktree,
+        &["commit", "-q", "--allow-empty", "-m", "feature"],
+    );
+    std::fs::create_dir(worktree.join("src")).unwrap();
+
+    let nested = worktree.join("src");
+    assert_eq!(
+        get_git_remote_url(&nested).unwrap(),
+        "git@github.com:user/repo.git"
+    );
+    assert_eq!(
+        get_git_head(&nested).unwrap(),
+        git(&main, &["rev-parse", "feature"])
+    );
+    assert_ne!(get_git_head(&nested).unwrap(), get_git_head(&main).unwrap());
+}
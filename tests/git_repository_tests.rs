@@ -0,0 +1,228 @@
+mod common;
+
+use catch_cli::git_info::repository::{GitError, GitRepository};
+use common::git;
+use std::fs;
+use tempfile::TempDir;
+
+/// A repository with two commits on `main`, a `feature` branch and an annotated tag.
+fn create_repository() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    git(root, &["init", "-q"]);
+
+    fs::create_dir_all(root.join("src/nested")).unwrap();
+    let lines: String = (0..200)
+        .map(|i| format!("let line{} = {};\n", i, i))
+        .collect();
+    fs::write(root.join("src/app.js"), &lines).unwrap();
+    fs::write(root.join("src/nested/util.js"), "export {}\n").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink("app.js", root.join("src/link.js")).unwrap();
+    git(root, &["add", "-A"]);
+    git(root, &["commit", "-q", "-m", "first"]);
+    git(root, &["tag", "-a", "v1", "-m", "first release"]);
+
+    fs::write(root.join("src/app.js"), format!("{}let last = 1;\n", lines)).unwrap();
+    git(root, &["commit", "-q", "-am", "second"]);
+    git(root, &["branch", "feature", "HEAD~1"]);
+    temp_dir
+}
+
+#[test]
+fn test_resolve_revisions() {
+    let temp_dir = create_repository();
+    let root = temp_dir.path();
+    let repository = GitRepository::open(&root.join("src/nested")).unwrap();
+    assert_eq!(repository.work_dir, root);
+
+    let head = git(root, &["rev-parse", "HEAD"]);
+    let first = git(root, &["rev-parse", "HEAD~1"]);
+    let resolve = |rev: &str| repository.resolve(rev).map(|id| id.to_hex());
+    assert_eq!(resolve("HEAD").unwrap(), head);
+    assert_eq!(resolve("main").unwrap(), head);
+    assert_eq!(resolve("refs/heads/feature").unwrap(), first);
+    assert_eq!(resolve("feature").unwrap(), first);
+    // Annotated tags are peeled to their commit.
+    assert_eq!(resolve("v1").unwrap(), first);
+    assert_eq!(resolve(&head[..10]).unwrap(), head);
+    assert_eq!(resolve(&head).unwrap(), head);
+
+    // Anything `git rev-parse` takes.
+    assert_eq!(resolve("HEAD~1").unwrap(), first);
+    assert_eq!(resolve("main^").unwrap(), first);
+    assert_eq!(resolve("HEAD@{0}").unwrap(), head);
+    assert_eq!(resolve("v1^{}").unwrap(), first);
+    assert_eq!(resolve(":/second").unwrap(), head);
+
+    for rev in ["missing", "HEAD~5", "", "--all"] {
+        assert!(
+            matches!(resolve(rev), Err(GitError::UnknownRevision(_))),
+            "{}",
+            rev
+        );
+    }
+    assert!(matches!(
+        resolve("HEAD:src/app.js"),
+        Err(GitError::NotACommit(_))
+    ));
+    let tree = git(root, &["rev-parse", "HEAD^{tree}"]);
+    assert!(matches!(resolve(&tree), Err(GitError::NotACommit(_))));
+}
+
+#[test]
+fn test_snapshot_reads_loose_and_packed_objects() {
+    let temp_dir = create_repository();
+    let root = temp_dir.path();
+
+    let check = |rev: &str| {
+        let snapshot = GitRepository::open(root).unwrap().snapshot(rev).unwrap();
+        assert_eq!(
+            snapshot.commit.to_hex(),
+            git(root, &["rev-parse", &format!("{}^{{commit}}", rev)])
+        );
+        assert_eq!(
+            snapshot.files.keys().collect::<Vec<_>>(),
+            ["src/app.js", "src/nested/util.js"]
+        );
+        #[cfg(unix)]
+        assert_eq!(snapshot.symlinks, ["src/link.js"]);
+        for path in snapshot.files.keys() {
+            let expected = git(root, &["show", &format!("{}:{}", rev, path)]);
+            let content = String::from_utf8(snapshot.read(path).unwrap()).unwrap();
+            assert_eq!(content.trim_end(), expected);
+            assert_eq!(snapshot.size(path).unwrap(), content.len() as u64);
+        }
+        assert!(matches!(
+            snapshot.read("src/missing.js"),
+            Err(GitError::MissingPath(_))
+        ));
+    };
+
+    check("HEAD");
+    check("v1");
+
+    // Packing stores one version of `src/app.js` as a delta of the other, and moves refs
+    // to `packed-refs`.
+    git(root, &["gc", "-q", "--aggressive"]);
+    assert!(!root.join(".git/refs/tags/v1").exists());
+    check("HEAD");
+    check("v1");
+    check("feature");
+}
+
+#[test]
+fn test_linked_worktree() {
+    let temp_dir = create_repository();
+    let root = temp_dir.path();
+    let worktree_dir = TempDir::new().unwrap();
+    let worktree = worktree_dir.path().join("feature");
+    git(
+        root,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            worktree.to_str().unwrap(),
+            "feature",
+        ],
+    );
+
+    let repository = GitRepository::open(&worktree).unwrap();
+    assert_eq!(repository.work_dir, worktree);
+    assert_ne!(repository.git_dir, repository.common_dir);
+    assert_eq!(
+        repository.resolve("HEAD").unwrap().to_hex(),
+        git(root, &["rev-parse", "feature"])
+    );
+    assert!(repository
+        .tracked_paths()
+        .unwrap()
+        .contains(&"src/app.js".to_string()));
+}
+
+#[test]
+fn test_split_and_sparse_indexes() {
+    let temp_dir = create_repository();
+    let root = temp_dir.path();
+    let repository = GitRepository::open(root).unwrap();
+    let expected = ["src/app.js", "src/link.js", "src/nested/util.js"];
+    #[cfg(not(unix))]
+    let expected = ["src/app.js", "src/nested/util.js"];
+
+    // Entries added after the split live in the shared index's companion file.
+    git(root, &["update-index", "--split-index"]);
+    fs::write(root.join("top.js"), "").unwrap();
+    git(root, &["add", "top.js"]);
+    let mut paths = expected.to_vec();
+    paths.push("top.js");
+    assert_eq!(repository.tracked_paths().unwrap(), paths);
+    git(root, &["update-index", "--no-split-index"]);
+
+    // A sparse index keeps directories outside the cone as single tree entries.
+    git(root, &["commit", "-q", "-m", "top"]);
+    git(
+        root,
+        &["sparse-checkout", "init", "--cone", "--sparse-index"],
+    );
+    git(root, &["sparse-checkout", "set", "docs"]);
+    assert!(!root.join("src/app.js").exists());
+    assert_eq!(repository.tracked_paths().unwrap(), paths);
+}
+
+#[test]
+fn test_conflicted_paths_are_listed_once() {
+    let temp_dir = create_repository();
+    let root = temp_dir.path();
+    git(root, &["checkout", "-q", "feature"]);
+    fs::write(root.join("src/app.js"), "let feature = 1;\n").unwrap();
+    git(root, &["commit", "-q", "-am", "feature"]);
+    let output = std::process::Command::new("git")
+        .args(["merge", "-q", "main"])
+        .current_dir(root)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let paths = GitRepository::open(root).unwrap().tracked_paths().unwrap();
+    assert_eq!(paths.iter().filter(|path| *path == "src/app.js").count(), 1);
+}
+
+#[test]
+fn test_snapshot_through_alternates() {
+    let temp_dir = create_repository();
+    let root = temp_dir.path();
+    let clone_dir = TempDir::new().unwrap();
+    let clone = clone_dir.path().join("clone");
+    // A shared clone has no objects of its own; they're found through
+    // `objects/info/alternates`.
+    git(
+        root,
+        &["clone", "-q", "--shared", ".", clone.to_str().unwrap()],
+    );
+    assert!(clone.join(".git/objects/info/alternates").exists());
+
+    let snapshot = GitRepository::open(&clone)
+        .unwrap()
+        .snapshot("origin/feature")
+        .unwrap();
+    let content = snapshot.read("src/nested/util.js").unwrap();
+    assert_eq!(content, b"export {}\n");
+}
+
+#[test]
+fn test_sha256_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    git(root, &["init", "-q", "--object-format=sha256"]);
+    fs::write(root.join("app.js"), "let app = 1;\n").unwrap();
+    git(root, &["add", "-A"]);
+    git(root, &["commit", "-q", "-m", "first"]);
+
+    let repository = GitRepository::open(root).unwrap();
+    let head = repository.head().unwrap();
+    assert_eq!(head.to_hex(), git(root, &["rev-parse", "HEAD"]));
+    assert_eq!(head.to_hex().len(), 64);
+    let snapshot = repository.snapshot("HEAD").unwrap();
+    assert_eq!(snapshot.read("app.js").unwrap(), b"let app = 1;\n");
+}
@@ -0,0 +1,167 @@
+use base64::Engine;
+use catch_cli::config::CatchConfig;
+use catch_cli::cryptography::public_key_der;
+use catch_cli::key_pinning::{check_server_key, format_fingerprint, KeyPinError, KeyPinStatus};
+use ed25519_dalek::{Signer, SigningKey};
+
+const SERVER_PUBLIC_KEY_PEM: &str = include_str!("fixtures/server_public_key.pem");
+const ROTATED_SERVER_PUBLIC_KEY_PEM: &str = include_str!("fixtures/rotated_server_public_key.pem");
+const SERVER_KEY_FINGERPRINT: &str =
+    "ff04e89a59d0d934bb6558271be1367d594d1f4a40d656d82a166ae8bcadf2b3";
+const ENVIRONMENT: &str = "https://api.trycatch.ai";
+
+fn sign_key(signing_key: &SigningKey, environment: &str, public_key_pem: &str) -> String {
+    let mut message = environment.as_bytes().to_vec();
+    message.extend_from_slice(&public_key_der(public_key_pem).unwrap());
+    base64::engine::general_purpose::STANDARD.encode(signing_key.sign(&message).to_bytes())
+}
+
+#[test]
+fn test_first_use_pins_key() {
+    let mut config = CatchConfig::default();
+
+    let status = check_server_key(&mut config, ENVIRONMENT, SERVER_PUBLIC_KEY_PEM, None).unwrap();
+
+    assert_eq!(
+        status,
+        KeyPinStatus::FirstUse {
+            fingerprint: SERVER_KEY_FINGERPRINT.to_string()
+        }
+    );
+    assert_eq!(
+        config.pinned_server_keys.get(ENVIRONMENT).unwrap(),
+        SERVER_KEY_FINGERPRINT
+    );
+}
+
+#[test]
+fn test_matching_key() {
+    let mut config = CatchConfig::default();
+    check_server_key(&mut config, ENVIRONMENT, SERVER_PUBLIC_KEY_PEM, None).unwrap();
+
+    let status = check_server_key(&mut config, ENVIRONMENT, SERVER_PUBLIC_KEY_PEM, None).unwrap();
+
+    assert!(matches!(status, KeyPinStatus::Matched { .. }));
+}
+
+#[test]
+fn test_pins_are_per_environment() {
+    let mut config = CatchConfig::default();
+    check_server_key(&mut config, ENVIRONMENT, SERVER_PUBLIC_KEY_PEM, None).unwrap();
+
+    let status = check_server_key(
+        &mut config,
+        "https://api.dev.trycatch.ai",
+        ROTATED_SERVER_PUBLIC_KEY_PEM,
+        None,
+    )
+    .unwrap();
+
+    assert!(matches!(status, KeyPinStatus::FirstUse { .. }));
+    assert_eq!(config.pinned_server_keys.len(), 2);
+}
+
+#[test]
+fn test_changed_key_is_refused() {
+    let mut config = CatchConfig::default();
+    check_server_key(&mut config, ENVIRONMENT, SERVER_PUBLIC_KEY_PEM, None).unwrap();
+
+    let result = check_server_key(
+        &mut config,
+        ENVIRONMENT,
+        ROTATED_SERVER_PUBLIC_KEY_PEM,
+        None,
+    );
+
+    assert!(matches!(result, Err(KeyPinError::KeyChanged { .. })));
+    assert_eq!(
+        config.pinned_server_keys.get(ENVIRONMENT).unwrap(),
+        SERVER_KEY_FINGERPRINT
+    );
+}
+
+#[test]
+fn test_changed_key_signed_by_untrusted_key_is_refused() {
+    let mut config = CatchConfig::default();
+    check_server_key(&mut config, ENVIRONMENT, SERVER_PUBLIC_KEY_PEM, None).unwrap();
+
+    let root_key = SigningKey::from_bytes(&rand::random());
+    let other_key = SigningKey::from_bytes(&rand::random());
+    config
+        .root_public_keys
+        .push(hex::encode(root_key.verifying_key().to_bytes()));
+    let signature = sign_key(&other_key, ENVIRONMENT, ROTATED_SERVER_PUBLIC_KEY_PEM);
+
+    let result = check_server_key(
+        &mut config,
+        ENVIRONMENT,
+        ROTATED_SERVER_PUBLIC_KEY_PEM,
+        Some(&signature),
+    );
+
+    assert!(matches!(result, Err(KeyPinError::KeyChanged { .. })));
+}
+
+#[test]
+fn test_changed_key_signed_for_another_environment_is_refused() {
+    let mut config = CatchConfig::default();
+    check_server_key(&mut config, ENVIRONMENT, SERVER_PUBLIC_KEY_PEM, None).unwrap();
+
+    let root_key = SigningKey::from_bytes(&rand::random());
+    config
+        .root_public_keys
+        .push(hex::encode(root_key.verifying_key().to_bytes()));
+    let signature = sign_key(
+        &root_key,
+        "https://staging.trycatch.ai",
+        ROTATED_SERVER_PUBLIC_KEY_PEM,
+    );
+
+    let result = check_server_key(
+        &mut config,
+        ENVIRONMENT,
+        ROTATED_SERVER_PUBLIC_KEY_PEM,
+        Some(&signature),
+    );
+
+    assert!(matches!(result, Err(KeyPinError::KeyChanged { .. })));
+}
+
+#[test]
+fn test_changed_key_signed_by_root_key_is_rotated() {
+    let mut config = CatchConfig::default();
+    check_server_key(&mut config, ENVIRONMENT, SERVER_PUBLIC_KEY_PEM, None).unwrap();
+
+    let root_key = SigningKey::from_bytes(&rand::random());
+    config
+        .root_public_keys
+        .push(hex::encode(root_key.verifying_key().to_bytes()));
+    let signature = sign_key(&root_key, ENVIRONMENT, ROTATED_SERVER_PUBLIC_KEY_PEM);
+
+    let status = check_server_key(
+        &mut config,
+        ENVIRONMENT,
+        ROTATED_SERVER_PUBLIC_KEY_PEM,
+        Some(&signature),
+    )
+    .unwrap();
+
+    match status {
+        KeyPinStatus::Rotated {
+            previous,
+            fingerprint,
+        } => {
+            assert_eq!(previous, SERVER_KEY_FINGERPRINT);
+            assert_eq!(
+                config.pinned_server_keys.get(ENVIRONMENT).unwrap(),
+                &fingerprint
+            );
+        }
+        other => panic!("Expected Rotated, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_format_fingerprint() {
+    assert_eq!(format_fingerprint("ff04e8"), "SHA256:ff:04:e8");
+}
@@ -0,0 +1,134 @@
+use catch_cli::project_roots::{
+    detect_project_roots, outermost, parse_gradle_includes, parse_package_workspaces,
+    resolve_roots, ProjectKind, ProjectRoot,
+};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+#[test]
+fn test_parse_gradle_includes() {
+    let groovy = "rootProject.name = 'Shop'\ninclude ':app', ':feature:login'\ninclude ':core'\n";
+    assert_eq!(
+        parse_gradle_includes(groovy),
+        ["app", "feature/login", "core"]
+    );
+
+    let kotlin = "include(\":app\")\ninclude(\n    \":data\",\n)\n// include(\":old\")\n";
+    assert_eq!(parse_gradle_includes(kotlin), ["app", "data"]);
+}
+
+#[test]
+fn test_parse_package_workspaces() {
+    assert_eq!(
+        parse_package_workspaces(r#"{"workspaces": ["packages/*", "apps/mobile/"]}"#),
+        ["packages/*", "apps/mobile"]
+    );
+    assert_eq!(
+        parse_package_workspaces(r#"{"workspaces": {"packages": ["libs/**"]}}"#),
+        ["libs/**"]
+    );
+    assert!(parse_package_workspaces(r#"{"name": "app"}"#).is_empty());
+    assert!(parse_package_workspaces("not json").is_empty());
+}
+
+#[test]
+fn test_detect_project_roots() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    for dir in [
+        "apps/ios/Shop.xcodeproj/project.xcworkspace",
+        "apps/android/app",
+        "apps/android/feature/login",
+        "packages/ui",
+        "node_modules/react",
+    ] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    fs::write(
+        root.join("package.json"),
+        r#"{"workspaces": ["packages/*"]}"#,
+    )
+    .unwrap();
+    fs::write(root.join("packages/ui/package.json"), r#"{"name": "ui"}"#).unwrap();
+    fs::write(root.join("node_modules/react/package.json"), "{}").unwrap();
+    fs::write(
+        root.join("apps/android/settings.gradle.kts"),
+        "include(\":app\", \":feature:login\", \":missing\")\n",
+    )
+    .unwrap();
+
+    let project = |path: &str, kinds: &[ProjectKind]| ProjectRoot {
+        path: path.to_string(),
+        kinds: kinds.to_vec(),
+    };
+    let roots = detect_project_roots(root);
+    assert_eq!(
+        roots,
+        [
+            project("", &[ProjectKind::JsWorkspace]),
+            project("apps/android", &[ProjectKind::Gradle]),
+            project("apps/android/app", &[ProjectKind::GradleModule]),
+            project("apps/android/feature/login", &[ProjectKind::GradleModule]),
+            project("apps/ios", &[ProjectKind::Xcode]),
+            project("packages/ui", &[ProjectKind::JsPackage]),
+        ]
+    );
+    assert_eq!(roots[0].display_path(), ".");
+    assert_eq!(roots[1].describe_kinds(), "Gradle build");
+}
+
+#[test]
+fn test_resolve_roots() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_root = temp_dir.path();
+    let outside_dir = TempDir::new().unwrap();
+    fs::create_dir_all(repo_root.join("apps/ios")).unwrap();
+    fs::create_dir_all(repo_root.join("apps/android/app")).unwrap();
+    fs::write(repo_root.join("README.md"), "").unwrap();
+
+    // Without --root, the scan covers the current directory.
+    assert!(resolve_roots(repo_root, repo_root, &[]).unwrap().is_empty());
+    assert_eq!(
+        resolve_roots(repo_root, &repo_root.join("apps/ios"), &[]).unwrap(),
+        ["apps/ios"]
+    );
+
+    // --root is relative to the current directory, the result to the repository root.
+    let apps = repo_root.join("apps");
+    assert_eq!(
+        resolve_roots(
+            repo_root,
+            &apps,
+            &[
+                PathBuf::from("ios"),
+                PathBuf::from("android/app"),
+                PathBuf::from("./android"),
+            ]
+        )
+        .unwrap(),
+        ["apps/android", "apps/ios"]
+    );
+    assert!(resolve_roots(repo_root, &apps, &[PathBuf::from("..")])
+        .unwrap()
+        .is_empty());
+
+    assert!(resolve_roots(repo_root, repo_root, &[PathBuf::from("missing")]).is_err());
+    assert!(resolve_roots(repo_root, repo_root, &[PathBuf::from("README.md")]).is_err());
+    assert!(resolve_roots(repo_root, repo_root, &[outside_dir.path().to_path_buf()]).is_err());
+}
+
+#[test]
+fn test_outermost() {
+    assert_eq!(
+        outermost(vec![
+            "ios".to_string(),
+            "android/app".to_string(),
+            "android".to_string(),
+            "ios".to_string(),
+            "android-tv".to_string(),
+        ]),
+        ["android", "android-tv", "ios"]
+    );
+}
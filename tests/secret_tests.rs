@@ -0,0 +1,43 @@
+use catch_cli::cryptography::secret::{Secret, SecretKey};
+use catch_cli::cryptography::{EncryptionScheme, FileCipher};
+use std::mem::ManuallyDrop;
+
+#[test]
+fn test_secret_debug_is_redacted() {
+    let secret = Secret::new([0x42u8; 32]);
+
+    let printed = format!("{:?}", secret);
+
+    assert_eq!(printed, "Secret([REDACTED])");
+    assert!(!printed.contains("66"));
+}
+
+#[test]
+fn test_file_cipher_debug_does_not_leak_key() {
+    let cipher = FileCipher::generate(EncryptionScheme::Aes256Gcm);
+    let key_bytes = format!("{:?}", cipher.key());
+
+    let printed = format!("{:?}", cipher);
+
+    assert!(printed.contains("REDACTED"));
+    assert!(!printed.contains(&key_bytes));
+}
+
+#[test]
+fn test_secret_is_zeroized_on_drop() {
+    let mut secret = ManuallyDrop::new(Secret::new([0x42u8; 32]));
+
+    // The storage stays alive inside ManuallyDrop, so it can be inspected after dropping.
+    unsafe { ManuallyDrop::drop(&mut secret) };
+
+    assert_eq!(secret.expose(), &[0u8; 32]);
+}
+
+#[test]
+fn test_random_secrets_differ() {
+    let first = SecretKey::random();
+    let second = SecretKey::random();
+
+    assert_ne!(first.expose(), second.expose());
+    assert_ne!(first.expose(), &[0u8; 32]);
+}
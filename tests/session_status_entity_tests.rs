@@ -0,0 +1,129 @@
+use catch_cli::api_client::session_status_entity::{
+    CatchSessionStatusResponse, SessionStep, StepStatus,
+};
+use serde_json::json;
+
+fn parse(value: serde_json::Value) -> CatchSessionStatusResponse {
+    serde_json::from_value(value).expect("Failed to deserialize session status")
+}
+
+#[test]
+fn test_partial_output_deserializes() {
+    let response = parse(json!({
+        "process": {
+            "id": "session-id",
+            "status": "in-progress",
+            "output": {
+                "extracting-candidates": {"status": "completed", "candidates": ["a.swift"]},
+                "fetching-code": {"step": "1/3", "status": "in-progress"}
+            }
+        }
+    }));
+
+    let output = response.process.output.as_ref().unwrap();
+    assert_eq!(response.process.status, Some(StepStatus::Running));
+    assert!(output.docs.is_empty());
+    assert!(output.generating_diff.is_none());
+    assert_eq!(
+        output.step_status(SessionStep::FetchingCode),
+        Some(StepStatus::Running)
+    );
+    assert_eq!(output.step_status(SessionStep::IndexingCode), None);
+    assert_eq!(output.current_step(), Some(SessionStep::FetchingCode));
+    assert!(!response.process.is_terminal());
+}
+
+#[test]
+fn test_step_status_aliases_and_unknown() {
+    let statuses: Vec<StepStatus> = serde_json::from_value(json!([
+        "not-yet",
+        "pending",
+        "in-progress",
+        "running",
+        "completed",
+        "failed",
+        "paused"
+    ]))
+    .unwrap();
+
+    assert_eq!(
+        statuses,
+        vec![
+            StepStatus::Pending,
+            StepStatus::Pending,
+            StepStatus::Running,
+            StepStatus::Running,
+            StepStatus::Completed,
+            StepStatus::Failed,
+            StepStatus::Unknown,
+        ]
+    );
+}
+
+#[test]
+fn test_generating_diff_without_files() {
+    let response = parse(json!({
+        "process": {
+            "output": {
+                "generating-diff": {"status": "not-yet"}
+            }
+        }
+    }));
+
+    let diff = response.process.output.unwrap().generating_diff.unwrap();
+    assert!(diff.files.is_empty());
+    assert_eq!(diff.status, StepStatus::Pending);
+}
+
+#[test]
+fn test_failed_step_is_terminal() {
+    let response = parse(json!({
+        "process": {
+            "output": {
+                "extracting-candidates": {"status": "completed", "candidates": []},
+                "fetching-code": {"step": "0/0", "status": "failed"}
+            }
+        }
+    }));
+
+    let output = response.process.output.as_ref().unwrap();
+    assert!(output.has_failed());
+    assert!(output.is_terminal());
+    assert!(response.process.is_terminal());
+}
+
+#[test]
+fn test_all_steps_completed_is_terminal() {
+    let completed = json!({"step": "1/1", "status": "completed"});
+    let response = parse(json!({
+        "process": {
+            "status": "completed",
+            "output": {
+                "docs": ["README.md"],
+                "extracting-candidates": {"status": "completed", "candidates": []},
+                "fetching-code": completed,
+                "indexing-code": completed,
+                "analyzing-platform": {"status": "completed", "platformInfo": {"platform": "ios", "architectureDescription": ""}},
+                "analyzing-module-structure": {"status": "completed", "structure": ""},
+                "generating-diff": {"status": "completed", "files": []},
+                "generating-docs": completed
+            }
+        }
+    }));
+
+    let output = response.process.output.as_ref().unwrap();
+    assert_eq!(output.current_step(), None);
+    assert!(output.is_terminal());
+    assert!(response.process.is_terminal());
+}
+
+#[test]
+fn test_empty_process_starts_at_first_step() {
+    let response = parse(json!({ "process": {} }));
+
+    assert_eq!(
+        response.process.current_step(),
+        Some(SessionStep::ExtractingCandidates)
+    );
+    assert!(!response.process.is_terminal());
+}
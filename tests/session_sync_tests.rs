@@ -0,0 +1,238 @@
+mod common;
+
+use catch_cli::audit_log::{AuditEvent, AuditLog, UploadRecord};
+use catch_cli::code_reader::content::{SkipReason, SkippedFile};
+use catch_cli::code_reader::walker::PruneReason;
+use catch_cli::code_reader::{CatchCLICodeFile, ScanOptions};
+use catch_cli::cryptography::{EncryptionScheme, FileCipher};
+use catch_cli::session_sync::SessionSync;
+use catch_cli::upload_cache::{UploadCache, UploadScope, UploadSnapshot};
+use catch_cli::upload_manifest::load_or_create_signing_key;
+use mockito::Matcher;
+use notify::event::{AccessKind, CreateKind, ModifyKind};
+use notify::{Event, EventKind};
+use rsa::sha2::{Digest, Sha256};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+const SERVER_PUBLIC_KEY_PEM: &str = include_str!("fixtures/server_public_key.pem");
+
+fn upload_record(environment: &str) -> UploadRecord {
+    UploadRecord {
+        environment: environment.to_string(),
+        ..common::upload_record("session-1")
+    }
+}
+
+fn uploaded_file(repo_root: &Path, path: &str) -> CatchCLICodeFile {
+    common::code_file(
+        path,
+        &hex::encode(Sha256::digest(fs::read(repo_root.join(path)).unwrap())),
+    )
+}
+
+fn session_sync(environment: &str, repo_root: &Path, state_dir: &Path) -> SessionSync {
+    let upload = upload_record(environment);
+    let snapshot = UploadSnapshot::new(
+        &upload.session_id,
+        &upload.org_name,
+        &upload.repo_name,
+        UploadScope::default(),
+        &[
+            uploaded_file(repo_root, "src/app.js"),
+            uploaded_file(repo_root, "src/old.js"),
+            uploaded_file(repo_root, "src/same.js"),
+        ],
+    );
+    UploadCache::new(state_dir, environment, &upload.integration_id)
+        .save(&snapshot)
+        .unwrap();
+
+    SessionSync::new(
+        upload,
+        SERVER_PUBLIC_KEY_PEM.to_string(),
+        Arc::new(FileCipher::generate(EncryptionScheme::Aes256Gcm)),
+        load_or_create_signing_key(state_dir).unwrap(),
+        repo_root,
+        state_dir,
+        ScanOptions::default(),
+    )
+    .unwrap()
+}
+
+fn create_repository(repo_root: &Path) {
+    fs::create_dir_all(repo_root.join("src")).unwrap();
+    for (path, content) in [
+        ("src/app.js", "let app = 1;"),
+        ("src/old.js", "let old = 1;"),
+        ("src/same.js", "let same = 1;"),
+        ("src/other.js", "let other = 1;"),
+    ] {
+        fs::write(repo_root.join(path), content).unwrap();
+    }
+}
+
+#[test]
+fn test_changed_paths() {
+    let repo_dir = tempdir().unwrap();
+    let state_dir = tempdir().unwrap();
+    let repo_root = repo_dir.path();
+    create_repository(repo_root);
+    let sync = session_sync("http://localhost", repo_root, state_dir.path());
+
+    assert_eq!(sync.session_id(), "session-1");
+    assert_eq!(sync.watched_dirs(), BTreeSet::from([repo_root.join("src")]));
+
+    let event = Event::new(EventKind::Modify(ModifyKind::Any))
+        .add_path(repo_root.join("src/app.js"))
+        .add_path(repo_root.join("src/other.js"));
+    assert_eq!(sync.changed_paths(&event), ["src/app.js"]);
+
+    let event =
+        Event::new(EventKind::Create(CreateKind::File)).add_path(repo_root.join("src/old.js"));
+    assert_eq!(sync.changed_paths(&event), ["src/old.js"]);
+
+    let event =
+        Event::new(EventKind::Access(AccessKind::Any)).add_path(repo_root.join("src/app.js"));
+    assert!(sync.changed_paths(&event).is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_uploads_changes_and_deletions() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/cli/integration-id/files")
+        .match_body(Matcher::PartialJson(json!({
+            "sessionId": "session-1",
+            "manifest": {
+                "manifest": {
+                    "baseSessionId": "session-1",
+                    "deletedFiles": ["src/old.js"],
+                }
+            }
+        })))
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let repo_dir = tempdir().unwrap();
+    let state_dir = tempdir().unwrap();
+    let repo_root = repo_dir.path();
+    create_repository(repo_root);
+    let mut sync = session_sync(&server.url(), repo_root, state_dir.path());
+
+    fs::write(repo_root.join("src/app.js"), "let app = 2;").unwrap();
+    fs::remove_file(repo_root.join("src/old.js")).unwrap();
+    let report = sync
+        .sync(BTreeSet::from([
+            "src/app.js".to_string(),
+            "src/old.js".to_string(),
+            "src/same.js".to_string(),
+        ]))
+        .await
+        .unwrap();
+
+    mock.assert();
+    assert_eq!(report.uploaded, ["src/app.js"]);
+    assert_eq!(report.deleted, ["src/old.js"]);
+    assert!(report.skipped.is_empty());
+
+    let snapshot = UploadCache::new(state_dir.path(), &server.url(), "integration-id")
+        .load("catch-org", "catch-app")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        snapshot.files.keys().collect::<Vec<_>>(),
+        ["src/app.js", "src/same.js"]
+    );
+    assert_eq!(
+        snapshot.files["src/app.js"],
+        hex::encode(Sha256::digest("let app = 2;"))
+    );
+
+    let entries = AuditLog::new(state_dir.path()).entries().unwrap();
+    assert_eq!(entries.len(), 1);
+    let AuditEvent::Upload(record) = &entries[0].event else {
+        panic!("expected an upload");
+    };
+    assert_eq!(record.base_session_id.as_deref(), Some("session-1"));
+    assert_eq!(record.files.len(), 1);
+    assert_eq!(record.deleted_files, ["src/old.js"]);
+
+    // Nothing is sent when saved files didn't change, or were already deleted.
+    let report = sync
+        .sync(BTreeSet::from([
+            "src/app.js".to_string(),
+            "src/old.js".to_string(),
+        ]))
+        .await
+        .unwrap();
+    assert!(report.is_empty());
+    mock.expect(1).assert();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sync_leaves_out_links_outside_the_repository() {
+    let repo_dir = tempdir().unwrap();
+    let state_dir = tempdir().unwrap();
+    let outside_dir = tempdir().unwrap();
+    let repo_root = repo_dir.path();
+    create_repository(repo_root);
+    // Nothing may be sent, so the environment is never contacted.
+    let mut sync = session_sync("http://localhost", repo_root, state_dir.path());
+
+    fs::write(outside_dir.path().join("secret.js"), "let secret = 1;").unwrap();
+    fs::remove_file(repo_root.join("src/app.js")).unwrap();
+    std::os::unix::fs::symlink(
+        outside_dir.path().join("secret.js"),
+        repo_root.join("src/app.js"),
+    )
+    .unwrap();
+    std::os::unix::fs::symlink(outside_dir.path(), repo_root.join("linked")).unwrap();
+    // Links within the repository are followed, as the walker does by default.
+    fs::write(repo_root.join("src/copy.js"), "let same = 1;").unwrap();
+    fs::remove_file(repo_root.join("src/same.js")).unwrap();
+    std::os::unix::fs::symlink("copy.js", repo_root.join("src/same.js")).unwrap();
+
+    let report = sync
+        .sync(BTreeSet::from([
+            "linked/secret.js".to_string(),
+            "src/app.js".to_string(),
+            "src/same.js".to_string(),
+        ]))
+        .await
+        .unwrap();
+
+    let outside = |path: &str| SkippedFile {
+        path: path.to_string(),
+        reason: SkipReason::Symlink(PruneReason::SymlinkOutsideRoot),
+    };
+    assert_eq!(
+        report.skipped,
+        [outside("linked/secret.js"), outside("src/app.js")]
+    );
+    assert!(report.uploaded.is_empty());
+    assert!(report.deleted.is_empty());
+}
+
+#[test]
+fn test_sync_requires_cached_upload() {
+    let repo_dir = tempdir().unwrap();
+    let state_dir = tempdir().unwrap();
+
+    let sync = SessionSync::new(
+        upload_record("http://localhost"),
+        SERVER_PUBLIC_KEY_PEM.to_string(),
+        Arc::new(FileCipher::generate(EncryptionScheme::Aes256Gcm)),
+        load_or_create_signing_key(state_dir.path()).unwrap(),
+        repo_dir.path(),
+        state_dir.path(),
+        ScanOptions::default(),
+    );
+    assert!(sync.is_err());
+}
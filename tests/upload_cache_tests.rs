@@ -0,0 +1,154 @@
+mod common;
+
+use catch_cli::code_reader::source::FileSource;
+use catch_cli::code_reader::ScanOptions;
+use catch_cli::upload_cache::{UploadCache, UploadScope, UploadSnapshot};
+use common::code_file;
+use std::collections::BTreeSet;
+use tempfile::tempdir;
+
+#[test]
+fn test_diff_against_previous_upload() {
+    let previous = UploadSnapshot::new(
+        "session-1",
+        "catch-org",
+        "catch-app",
+        UploadScope::default(),
+        &[
+            code_file("a.swift", "aa"),
+            code_file("b.kt", "bb"),
+            code_file("c.js", "cc"),
+        ],
+    );
+
+    let files = vec![
+        code_file("a.swift", "aa"),
+        code_file("b.kt", "b2"),
+        code_file("d.ts", "dd"),
+    ];
+    let delta = previous.diff(&files);
+
+    assert_eq!(delta.base_session_id, "session-1");
+    assert_eq!(delta.added, ["d.ts"]);
+    assert_eq!(delta.modified, ["b.kt"]);
+    assert_eq!(delta.unchanged, BTreeSet::from(["a.swift".to_string()]));
+    assert_eq!(delta.deleted, ["c.js"]);
+    assert_eq!(delta.change_count(), 3);
+    assert_eq!(
+        delta.summary(),
+        "1 added, 1 modified, 1 deleted, 1 unchanged"
+    );
+
+    let changed: Vec<String> = delta
+        .changed_files(files)
+        .into_iter()
+        .map(|file| file.path)
+        .collect();
+    assert_eq!(changed, ["b.kt", "d.ts"]);
+}
+
+#[test]
+fn test_diff_of_an_unchanged_selection_is_empty() {
+    let files = vec![code_file("a.swift", "aa"), code_file("b.kt", "bb")];
+    let previous = UploadSnapshot::new(
+        "session-1",
+        "catch-org",
+        "catch-app",
+        UploadScope::default(),
+        &files,
+    );
+
+    let delta = previous.diff(&files);
+    assert_eq!(delta.change_count(), 0);
+    assert_eq!(delta.unchanged.len(), 2);
+    assert!(delta.changed_files(files).is_empty());
+}
+
+#[test]
+fn test_upload_cache_round_trip() {
+    let dir = tempdir().unwrap();
+    let cache = UploadCache::new(dir.path(), "https://api.trycatch.ai", "integration-id");
+    assert_eq!(cache.load("catch-org", "catch-app").unwrap(), None);
+
+    let snapshot = UploadSnapshot::new(
+        "session-1",
+        "catch-org",
+        "catch-app",
+        UploadScope::default(),
+        &[code_file("a.swift", "aa")],
+    );
+    cache.save(&snapshot).unwrap();
+    assert!(cache.path().starts_with(dir.path()));
+    assert_eq!(
+        cache.load("catch-org", "catch-app").unwrap(),
+        Some(snapshot)
+    );
+
+    // The cache is per repository, environment and integration.
+    assert_eq!(cache.load("catch-org", "other-app").unwrap(), None);
+    let other_environment = UploadCache::new(dir.path(), "http://localhost", "integration-id");
+    assert_eq!(
+        other_environment.load("catch-org", "catch-app").unwrap(),
+        None
+    );
+    let other_integration = UploadCache::new(dir.path(), "https://api.trycatch.ai", "other");
+    assert_eq!(
+        other_integration.load("catch-org", "catch-app").unwrap(),
+        None
+    );
+
+    cache.clear().unwrap();
+    assert_eq!(cache.load("catch-org", "catch-app").unwrap(), None);
+    cache.clear().unwrap();
+}
+
+#[test]
+fn test_upload_scope() {
+    let options = ScanOptions {
+        roots: vec!["apps/ios".to_string()],
+        excludes: vec!["secrets/".to_string()],
+        source: FileSource::Revision("0123456789abcdef0123456789abcdef01234567".to_string()),
+        ..Default::default()
+    };
+    let scope = UploadScope::new(&options, vec!["App.xcodeproj/App".to_string()]);
+    assert_eq!(
+        scope,
+        UploadScope {
+            roots: vec!["apps/ios".to_string()],
+            targets: vec!["App.xcodeproj/App".to_string()],
+            includes: vec![],
+            excludes: vec!["secrets/".to_string()],
+            source: "revision".to_string(),
+        }
+    );
+
+    // Scans of another revision share the scope; scans of the working tree don't.
+    let other_revision = ScanOptions {
+        source: FileSource::Revision("fedcba9876543210fedcba9876543210fedcba98".to_string()),
+        ..options.clone()
+    };
+    assert_eq!(
+        UploadScope::new(&other_revision, scope.targets.clone()),
+        scope
+    );
+    let working_tree = ScanOptions {
+        source: FileSource::WorkingTree,
+        ..options
+    };
+    assert_ne!(
+        UploadScope::new(&working_tree, scope.targets.clone()),
+        scope
+    );
+
+    // Snapshots saved before scopes were recorded match no scan.
+    let snapshot: UploadSnapshot = serde_json::from_str(
+        r#"{"sessionId":"session-1","orgName":"catch-org","repoName":"catch-app",
+            "uploadedAt":"2026-01-01T00:00:00Z","files":{"a.swift":"aa"}}"#,
+    )
+    .unwrap();
+    assert_eq!(snapshot.scope, UploadScope::default());
+    assert_ne!(
+        UploadScope::new(&ScanOptions::default(), vec![]),
+        UploadScope::default()
+    );
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use base64::Engine;
+use catch_cli::code_reader::CatchCLICodeFile;
+use catch_cli::upload_manifest::{load_or_create_signing_key, UploadManifest, MANIFEST_VERSION};
+use rsa::sha2::{Digest, Sha256};
+use tempfile::tempdir;
+
+fn code_file(path: &str, ciphertext: &[u8]) -> CatchCLICodeFile {
+    let mut file = common::code_file(path, &"ab".repeat(32));
+    file.content = base64::engine::general_purpose::STANDARD.encode(ciphertext);
+    file.metadata.size = 12;
+    file
+}
+
+#[test]
+fn test_manifest_entries() {
+    let files = vec![
+        code_file("a.swift", b"cipher-a"),
+        code_file("b/c.kt", b"cipher-b"),
+    ];
+
+    let manifest = UploadManifest::new("session-id", "integration-id", &files);
+
+    assert_eq!(manifest.version, MANIFEST_VERSION);
+    assert_eq!(manifest.session_id, "session-id");
+    assert_eq!(manifest.integration_id, "integration-id");
+    assert_eq!(manifest.files.len(), 2);
+    assert_eq!(manifest.files[1].path, "b/c.kt");
+    assert_eq!(manifest.files[1].size, 12);
+    assert_eq!(manifest.files[1].plaintext_sha256, "ab".repeat(32));
+    assert_eq!(
+        manifest.files[1].ciphertext_sha256,
+        hex::encode(Sha256::digest(b"cipher-b"))
+    );
+}
+
+#[test]
+fn test_signed_manifest_verifies() {
+    let dir = tempdir().unwrap();
+    let signing_key = load_or_create_signing_key(dir.path()).unwrap();
+
+    let signed = UploadManifest::new("session-id", "integration-id", &[code_file("a.js", b"x")])
+        .sign(&signing_key);
+
+    assert!(signed.verify());
+    assert_eq!(
+        signed.public_key,
+        hex::encode(signing_key.verifying_key().to_bytes())
+    );
+}
+
+#[test]
+fn test_tampered_manifest_fails_verification() {
+    let dir = tempdir().unwrap();
+    let signing_key = load_or_create_signing_key(dir.path()).unwrap();
+
+    let mut signed =
+        UploadManifest::new("session-id", "integration-id", &[code_file("a.js", b"x")])
+            .sign(&signing_key);
+    signed.manifest.files[0].ciphertext_sha256 = "00".repeat(32);
+
+    assert!(!signed.verify());
+}
+
+#[test]
+fn test_signing_key_is_persisted() {
+    let dir = tempdir().unwrap();
+
+    let first = load_or_create_signing_key(dir.path()).unwrap();
+    let second = load_or_create_signing_key(dir.path()).unwrap();
+
+    assert_eq!(first.to_bytes(), second.to_bytes());
+    assert!(dir.path().join("installation_key").exists());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dir.path().join("installation_key"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn test_saved_manifest_round_trips() {
+    let dir = tempdir().unwrap();
+    let signing_key = load_or_create_signing_key(dir.path()).unwrap();
+    let signed = UploadManifest::new("session-id", "integration-id", &[code_file("a.js", b"x")])
+        .sign(&signing_key);
+
+    let path = signed.save(dir.path()).unwrap();
+
+    assert!(path.starts_with(dir.path().join("manifests")));
+    let loaded: catch_cli::upload_manifest::SignedUploadManifest =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(loaded, signed);
+    assert!(loaded.verify());
+}
+
+#[test]
+fn test_incremental_manifest() {
+    let dir = tempdir().unwrap();
+    let signing_key = load_or_create_signing_key(dir.path()).unwrap();
+
+    let full = UploadManifest::new("session-2", "integration-id", &[code_file("a.js", b"x")]);
+    let json = String::from_utf8(full.canonical_bytes()).unwrap();
+    assert!(!json.contains("baseSessionId"));
+    assert!(!json.contains("deletedFiles"));
+
+    let signed = full
+        .incremental("session-1", vec!["old.js".to_string()])
+        .sign(&signing_key);
+    assert!(signed.verify());
+    assert_eq!(
+        signed.manifest.base_session_id.as_deref(),
+        Some("session-1")
+    );
+    assert_eq!(signed.manifest.deleted_files, ["old.js"]);
+
+    let mut tampered = signed.clone();
+    tampered.manifest.deleted_files.clear();
+    assert!(!tampered.verify());
+}
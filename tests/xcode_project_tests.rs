@@ -0,0 +1,391 @@
+use catch_cli::code_reader::{IndexedFile, ScanOptions};
+use catch_cli::code_uploader::UploadMetadata;
+use catch_cli::cryptography::{EncryptionScheme, FileCipher};
+use catch_cli::upload_manifest::UploadManifest;
+use catch_cli::xcode_project::pbxproj::parse_project;
+use catch_cli::xcode_project::workspace::parse_workspace;
+use catch_cli::xcode_project::{analyze_xcode_projects, TargetKey, TargetKind, XcodeProjects};
+use std::collections::BTreeSet;
+use std::fs;
+use tempfile::tempdir;
+
+const PROJECT: &str = r#"// !$*UTF8*$!
+{
+	archiveVersion = 1;
+	classes = {
+	};
+	objectVersion = 77;
+	objects = {
+
+/* Begin PBXBuildFile section */
+		B001 /* AppDelegate.swift in Sources */ = {isa = PBXBuildFile; fileRef = F001 /* AppDelegate.swift */; };
+		B002 /* ContentView.swift in Sources */ = {isa = PBXBuildFile; fileRef = F002 /* ContentView.swift */; };
+		B003 /* Localizable.strings in Resources */ = {isa = PBXBuildFile; fileRef = V001 /* Localizable.strings */; };
+		B004 /* Assets.xcassets in Resources */ = {isa = PBXBuildFile; fileRef = F004 /* Assets.xcassets */; };
+		B005 /* AppTests.swift in Sources */ = {isa = PBXBuildFile; fileRef = F005 /* AppTests.swift */; };
+		B006 /* Shared.swift in Sources */ = {isa = PBXBuildFile; fileRef = F006 /* Shared.swift */; };
+		B007 /* libz.tbd in Frameworks */ = {isa = PBXBuildFile; fileRef = F007 /* libz.tbd */; };
+/* End PBXBuildFile section */
+
+/* Begin PBXFileReference section */
+		F001 /* AppDelegate.swift */ = {isa = PBXFileReference; lastKnownFileType = sourcecode.swift; path = AppDelegate.swift; sourceTree = "<group>"; };
+		F002 /* ContentView.swift */ = {isa = PBXFileReference; lastKnownFileType = sourcecode.swift; path = Views/ContentView.swift; sourceTree = "<group>"; };
+		F003 /* en */ = {isa = PBXFileReference; lastKnownFileType = text.plist.strings; name = en; path = en.lproj/Localizable.strings; sourceTree = "<group>"; };
+		F004 /* Assets.xcassets */ = {isa = PBXFileReference; lastKnownFileType = folder.assetcatalog; path = Assets.xcassets; sourceTree = "<group>"; };
+		F005 /* AppTests.swift */ = {isa = PBXFileReference; lastKnownFileType = sourcecode.swift; path = AppTests.swift; sourceTree = "<group>"; };
+		F006 /* Shared.swift */ = {isa = PBXFileReference; lastKnownFileType = sourcecode.swift; path = Shared/Shared.swift; sourceTree = SOURCE_ROOT; };
+		F007 /* libz.tbd */ = {isa = PBXFileReference; lastKnownFileType = "sourcecode.text-based-dylib-definition"; name = libz.tbd; path = usr/lib/libz.tbd; sourceTree = SDKROOT; };
+		F008 /* App.app */ = {isa = PBXFileReference; explicitFileType = wrapper.application; includeInIndex = 0; path = App.app; sourceTree = BUILT_PRODUCTS_DIR; };
+/* End PBXFileReference section */
+
+/* Begin PBXFileSystemSynchronizedBuildFileExceptionSet section */
+		E001 /* Exceptions for "Widget" folder in "Widget" target */ = {
+			isa = PBXFileSystemSynchronizedBuildFileExceptionSet;
+			membershipExceptions = (
+				Info.plist,
+			);
+			target = T003 /* Widget */;
+		};
+/* End PBXFileSystemSynchronizedBuildFileExceptionSet section */
+
+/* Begin PBXFileSystemSynchronizedRootGroup section */
+		S001 /* Widget */ = {isa = PBXFileSystemSynchronizedRootGroup; exceptions = (E001 /* Exceptions for "Widget" folder in "Widget" target */, ); path = Widget; sourceTree = "<group>"; };
+/* End PBXFileSystemSynchronizedRootGroup section */
+
+/* Begin PBXGroup section */
+		G000 = {
+			isa = PBXGroup;
+			children = (
+				G001 /* App */,
+				G002 /* AppTests */,
+				S001 /* Widget */,
+				G003 /* Frameworks */,
+				G004 /* Products */,
+			);
+			sourceTree = "<group>";
+		};
+		G001 /* App */ = {
+			isa = PBXGroup;
+			children = (
+				F001 /* AppDelegate.swift */,
+				F002 /* ContentView.swift */,
+				V001 /* Localizable.strings */,
+				F004 /* Assets.xcassets */,
+				F006 /* Shared.swift */,
+			);
+			path = App;
+			sourceTree = "<group>";
+		};
+		G002 /* AppTests */ = {
+			isa = PBXGroup;
+			children = (
+				F005 /* AppTests.swift */,
+			);
+			path = AppTests;
+			sourceTree = "<group>";
+		};
+		G003 /* Frameworks */ = {
+			isa = PBXGroup;
+			children = (
+				F007 /* libz.tbd */,
+			);
+			name = Frameworks;
+			sourceTree = "<group>";
+		};
+		G004 /* Products */ = {
+			isa = PBXGroup;
+			children = (
+				F008 /* App.app */,
+			);
+			name = Products;
+			sourceTree = "<group>";
+		};
+/* End PBXGroup section */
+
+/* Begin PBXNativeTarget section */
+		T001 /* App */ = {
+			isa = PBXNativeTarget;
+			buildPhases = (
+				P001 /* Sources */,
+				P002 /* Resources */,
+				P003 /* Frameworks */,
+			);
+			name = App;
+			productType = "com.apple.product-type.application";
+		};
+		T002 /* AppTests */ = {
+			isa = PBXNativeTarget;
+			buildPhases = (
+				P004 /* Sources */,
+			);
+			name = AppTests;
+			productType = "com.apple.product-type.bundle.unit-test";
+		};
+		T003 /* Widget */ = {
+			isa = PBXNativeTarget;
+			buildPhases = (
+			);
+			fileSystemSynchronizedGroups = (
+				S001 /* Widget */,
+			);
+			name = Widget;
+			productType = "com.apple.product-type.app-extension";
+		};
+/* End PBXNativeTarget section */
+
+/* Begin PBXProject section */
+		R001 /* Project object */ = {
+			isa = PBXProject;
+			mainGroup = G000;
+			projectDirPath = "";
+			projectRoot = "";
+			targets = (
+				T001 /* App */,
+				T002 /* AppTests */,
+				T003 /* Widget */,
+			);
+		};
+/* End PBXProject section */
+
+/* Begin PBXBuildPhase sections */
+		P001 /* Sources */ = {isa = PBXSourcesBuildPhase; files = (B001 /* AppDelegate.swift in Sources */, B002 /* ContentView.swift in Sources */, B006 /* Shared.swift in Sources */, ); };
+		P002 /* Resources */ = {isa = PBXResourcesBuildPhase; files = (B003 /* Localizable.strings in Resources */, B004 /* Assets.xcassets in Resources */, ); };
+		P003 /* Frameworks */ = {isa = PBXFrameworksBuildPhase; files = (B007 /* libz.tbd in Frameworks */, ); };
+		P004 /* Sources */ = {isa = PBXSourcesBuildPhase; files = (B005 /* AppTests.swift in Sources */, B006 /* Shared.swift in Sources */, ); };
+/* End PBXBuildPhase sections */
+
+/* Begin PBXVariantGroup section */
+		V001 /* Localizable.strings */ = {
+			isa = PBXVariantGroup;
+			children = (
+				F003 /* en */,
+			);
+			name = Localizable.strings;
+			sourceTree = "<group>";
+		};
+/* End PBXVariantGroup section */
+	};
+	rootObject = R001 /* Project object */;
+}
+"#;
+
+const WORKSPACE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Workspace
+   version = "1.0">
+   <FileRef
+      location = "group:App.xcodeproj">
+   </FileRef>
+   <Group
+      location = "group:Modules"
+      name = "Modules">
+      <FileRef
+         location = "group:Core/Core.xcodeproj">
+      </FileRef>
+   </Group>
+   <FileRef
+      location = "container:Pods/Pods.xcodeproj">
+   </FileRef>
+   <FileRef
+      location = "absolute:/Users/me/Other/Other.xcodeproj">
+   </FileRef>
+   <FileRef
+      location = "group:README.md">
+   </FileRef>
+</Workspace>
+"#;
+
+fn indexed(paths: &[&str]) -> Vec<IndexedFile> {
+    paths
+        .iter()
+        .map(|path| IndexedFile {
+            path: path.to_string(),
+            size: 1,
+        })
+        .collect()
+}
+
+fn key(target: &str) -> TargetKey {
+    TargetKey {
+        project: "ios/App.xcodeproj".to_string(),
+        target: target.to_string(),
+    }
+}
+
+#[test]
+fn test_parse_project() {
+    let project = parse_project("ios/App.xcodeproj", PROJECT.as_bytes()).unwrap();
+    assert_eq!(project.path, "ios/App.xcodeproj");
+
+    let names: Vec<_> = project
+        .targets
+        .iter()
+        .map(|target| (target.name.as_str(), target.kind))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("App", TargetKind::Application),
+            ("AppTests", TargetKind::UnitTests),
+            ("Widget", TargetKind::Extension),
+        ]
+    );
+
+    let app = &project.targets[0];
+    assert_eq!(
+        app.files,
+        BTreeSet::from([
+            "ios/App/AppDelegate.swift".to_string(),
+            "ios/App/Views/ContentView.swift".to_string(),
+            "ios/App/en.lproj/Localizable.strings".to_string(),
+            "ios/Shared/Shared.swift".to_string(),
+        ])
+    );
+    assert_eq!(app.folders, ["ios/App/Assets.xcassets"]);
+    assert!(app.contains("ios/App/Assets.xcassets/AppIcon.appiconset/Contents.json"));
+    assert!(!app.contains("ios/AppTests/AppTests.swift"));
+
+    let tests = &project.targets[1];
+    assert_eq!(
+        tests.files,
+        BTreeSet::from([
+            "ios/AppTests/AppTests.swift".to_string(),
+            "ios/Shared/Shared.swift".to_string(),
+        ])
+    );
+
+    let widget = &project.targets[2];
+    assert_eq!(widget.folders, ["ios/Widget"]);
+    assert!(widget.contains("ios/Widget/Widget.swift"));
+    assert!(!widget.contains("ios/Widget/Info.plist"));
+    assert!(!widget.contains("ios/WidgetKit/Other.swift"));
+
+    assert!(parse_project("ios/App.xcodeproj", b"{ objects = { }; }").is_none());
+    assert!(parse_project("ios/App.xcodeproj", b"not a project").is_none());
+}
+
+#[test]
+fn test_parse_workspace() {
+    assert_eq!(
+        parse_workspace("ios/App.xcworkspace", WORKSPACE).unwrap(),
+        [
+            "ios/App.xcodeproj",
+            "ios/Modules/Core/Core.xcodeproj",
+            "ios/Pods/Pods.xcodeproj",
+        ]
+    );
+    assert_eq!(
+        parse_workspace("App.xcworkspace", WORKSPACE).unwrap()[0],
+        "App.xcodeproj"
+    );
+}
+
+#[test]
+fn test_select_and_filter_files() {
+    let project = parse_project("ios/App.xcodeproj", PROJECT.as_bytes()).unwrap();
+    let projects = XcodeProjects {
+        projects: vec![project],
+    };
+
+    let defaults = projects.default_selection();
+    assert_eq!(defaults, BTreeSet::from([key("App")]));
+    assert_eq!(
+        projects
+            .select_by_name(&["AppTests".to_string(), "Widget".to_string()])
+            .unwrap(),
+        BTreeSet::from([key("AppTests"), key("Widget")])
+    );
+    assert!(projects
+        .select_by_name(&["App".to_string(), "Watch".to_string()])
+        .unwrap_err()
+        .to_string()
+        .contains("Watch"));
+
+    let files = indexed(&[
+        "ios/App/AppDelegate.swift",
+        "ios/Shared/Shared.swift",
+        "ios/AppTests/AppTests.swift",
+        "ios/Widget/Widget.swift",
+        "ios/Widget/Info.plist",
+        "android/app/src/main/MainActivity.kt",
+    ]);
+    let (kept, left_out) = projects.filter_files(files, &defaults);
+    let kept: Vec<_> = kept.iter().map(|file| file.path.as_str()).collect();
+    let left_out: Vec<_> = left_out.iter().map(|file| file.path.as_str()).collect();
+    assert_eq!(
+        kept,
+        [
+            "ios/App/AppDelegate.swift",
+            "ios/Shared/Shared.swift",
+            "ios/Widget/Info.plist",
+            "android/app/src/main/MainActivity.kt",
+        ]
+    );
+    assert_eq!(
+        left_out,
+        ["ios/AppTests/AppTests.swift", "ios/Widget/Widget.swift"]
+    );
+
+    let membership = projects.membership(&kept, &defaults);
+    let json = serde_json::to_value(&membership).unwrap();
+    assert_eq!(json["targets"][0]["target"], "App");
+    assert_eq!(json["targets"][0]["kind"], "application");
+    assert_eq!(json["targets"][0]["selected"], true);
+    assert_eq!(
+        json["targets"][0]["files"],
+        serde_json::json!(["ios/App/AppDelegate.swift", "ios/Shared/Shared.swift"])
+    );
+    assert_eq!(json["targets"][1]["kind"], "unit-tests");
+    assert_eq!(json["targets"][1]["selected"], false);
+    assert_eq!(json["targets"][1]["files"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_analyze_xcode_projects() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("ios/App.xcodeproj/project.xcworkspace")).unwrap();
+    fs::create_dir_all(dir.path().join("ios/App.xcworkspace")).unwrap();
+    fs::write(
+        dir.path().join("ios/App.xcodeproj/project.pbxproj"),
+        PROJECT,
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("ios/App.xcodeproj/project.xcworkspace/contents.xcworkspacedata"),
+        r#"<Workspace version = "1.0"><FileRef location = "self:"></FileRef></Workspace>"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("ios/App.xcworkspace/contents.xcworkspacedata"),
+        WORKSPACE,
+    )
+    .unwrap();
+
+    let projects = analyze_xcode_projects(dir.path(), &ScanOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(projects.projects.len(), 1);
+    assert_eq!(projects.projects[0].path, "ios/App.xcodeproj");
+    assert_eq!(
+        projects.projects[0].workspace.as_deref(),
+        Some("ios/App.xcworkspace")
+    );
+
+    let rendered = projects.render();
+    assert!(rendered.contains("ios/App.xcodeproj (in ios/App.xcworkspace)\n"));
+    assert!(rendered.contains("  + App "));
+    assert!(rendered.contains("  - AppTests "));
+    assert!(rendered.contains("  - Widget "));
+
+    let cipher = FileCipher::generate(EncryptionScheme::Aes256Gcm);
+    let membership = projects.membership(&["ios/App/AppDelegate.swift"], &BTreeSet::new());
+    let metadata = UploadMetadata {
+        xcode_targets: Some(membership.seal(&cipher).unwrap()),
+        ..Default::default()
+    };
+    let manifest = UploadManifest::new("session-1", "integration-id", &[]).with_metadata(&metadata);
+    assert!(manifest.xcode_targets_sha256.is_some());
+    let json = serde_json::to_value(&metadata).unwrap();
+    assert!(json.get("xcodeTargets").is_some());
+}